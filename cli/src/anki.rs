@@ -0,0 +1,304 @@
+//! Anki card export.
+//!
+//! Cards are written as tab separated text with the Anki file headers, so
+//! the output can be imported directly with "File > Import". Each card field
+//! is generated from a template (see `Template`).
+
+use std::collections::HashSet;
+use std::io::Write;
+
+use jp_dict::Term;
+
+/// Templates for the default card fields.
+pub const DEFAULT_TEMPLATES: &[&str] = &[
+	"{expression}",
+	"{furigana}",
+	"{reading}",
+	"{glossary}",
+	"{frequency}",
+];
+
+/// Template for a card field. Variables in braces (e.g. `{expression}`) are
+/// replaced by the term data, anything else is copied as is.
+pub struct Template {
+	parts: Vec<Part>,
+}
+
+enum Part {
+	Text(String),
+	Var(Var),
+}
+
+#[derive(Copy, Clone)]
+enum Var {
+	Query,
+	Expression,
+	Reading,
+	Furigana,
+	FuriganaHtml,
+	Glossary,
+	Tags,
+	Frequency,
+	Sequence,
+	Source,
+}
+
+/// Names of the template variables, for the help text.
+pub const VARIABLES: &str = "    {expression}     term expression
+    {reading}        term reading (empty if the same as the expression)
+    {furigana}       expression with the reading in the Anki format, for
+                     use with the `furigana:` field filter
+    {furigana_html}  expression with the reading as HTML ruby tags
+    {glossary}       definitions, separated by line breaks
+    {tags}           term and definition tags
+    {frequency}      number of occurrences in the frequency data
+    {sequence}       sequence number in the source dictionary
+    {source}         source dictionary name
+    {query}          input that found the term
+";
+
+impl Template {
+	pub fn parse(text: &str) -> Result<Template, String> {
+		let mut parts = Vec::new();
+		let mut text = text;
+		while let Some(sta) = text.find('{') {
+			if sta > 0 {
+				parts.push(Part::Text(text[..sta].to_string()));
+			}
+			let end = match text[sta..].find('}') {
+				Some(end) => sta + end,
+				None => return Err(format!("unclosed `{{` in template `{}`", text)),
+			};
+			let var = match &text[sta + 1..end] {
+				"query" => Var::Query,
+				"expression" => Var::Expression,
+				"reading" => Var::Reading,
+				"furigana" => Var::Furigana,
+				"furigana_html" => Var::FuriganaHtml,
+				"glossary" => Var::Glossary,
+				"tags" => Var::Tags,
+				"frequency" => Var::Frequency,
+				"sequence" => Var::Sequence,
+				"source" => Var::Source,
+				name => return Err(format!("invalid template variable `{{{}}}`", name)),
+			};
+			parts.push(Part::Var(var));
+			text = &text[end + 1..];
+		}
+		if !text.is_empty() {
+			parts.push(Part::Text(text.to_string()));
+		}
+		Ok(Template { parts })
+	}
+
+	/// Renders the template for a term. The result is HTML, so the term data
+	/// is escaped.
+	pub fn render(&self, query: &str, term: &Term) -> String {
+		let mut out = String::new();
+		for part in self.parts.iter() {
+			match part {
+				Part::Text(text) => out.push_str(text),
+				Part::Var(var) => out.push_str(&render_var(*var, query, term)),
+			}
+		}
+		// Tabs and line breaks would break the row format.
+		out.replace(['\t', '\r', '\n'], " ")
+	}
+}
+
+fn render_var(var: Var, query: &str, term: &Term) -> String {
+	match var {
+		Var::Query => escape(query),
+		Var::Expression => escape(term.expression()),
+		Var::Reading => {
+			let reading = term.reading();
+			if reading != term.expression() {
+				escape(reading)
+			} else {
+				String::new()
+			}
+		}
+		Var::Furigana => escape(&term.furigana().to_anki()),
+		Var::FuriganaHtml => term.furigana().to_html(),
+		Var::Glossary => term.glossary().map(escape).collect::<Vec<_>>().join("<br>"),
+		Var::Tags => escape(&tags(term).join(", ")),
+		Var::Frequency => term.frequency().map(|x| x.to_string()).unwrap_or_default(),
+		Var::Sequence => term.sequence().to_string(),
+		Var::Source => escape(term.source()),
+	}
+}
+
+/// Anki cards output, skipping terms already written.
+pub struct Deck<'t> {
+	templates: &'t [Template],
+	seen: HashSet<(String, u32)>,
+	seen_index: HashSet<usize>,
+}
+
+impl<'t> Deck<'t> {
+	pub fn new(templates: &'t [Template]) -> Deck<'t> {
+		Deck {
+			templates,
+			seen: HashSet::new(),
+			seen_index: HashSet::new(),
+		}
+	}
+
+	/// Writes the Anki file headers. The card tags are in the last column.
+	pub fn header<W: Write>(&self, writer: &mut W) -> std::io::Result<()> {
+		writeln!(writer, "#separator:tab")?;
+		writeln!(writer, "#html:true")?;
+		writeln!(writer, "#tags column:{}", self.templates.len() + 1)
+	}
+
+	/// Writes one card per term, skipping terms with the same sequence
+	/// number as a term already written (e.g. alternative readings of the
+	/// same entry).
+	pub fn write<W: Write>(
+		&mut self,
+		writer: &mut W,
+		query: &str,
+		terms: &[Term],
+	) -> std::io::Result<()> {
+		for term in terms {
+			// Sequence zero is used by dictionaries without sequence numbers,
+			// so those terms can only be matched by index.
+			let is_new = if term.sequence() > 0 {
+				let key = (term.source().to_string(), term.sequence());
+				self.seen.insert(key)
+			} else {
+				self.seen_index.insert(term.index())
+			};
+			if !is_new {
+				continue;
+			}
+
+			let mut row: Vec<_> = self
+				.templates
+				.iter()
+				.map(|x| x.render(query, term))
+				.collect();
+			// Anki tags are separated by spaces.
+			let tags: Vec<_> = tags(term).iter().map(|x| x.replace(' ', "_")).collect();
+			row.push(tags.join(" "));
+			writeln!(writer, "{}", row.join("\t"))?;
+		}
+		Ok(())
+	}
+}
+
+/// Term and definition tag names, without repetitions.
+fn tags(term: &Term) -> Vec<String> {
+	let mut names: Vec<String> = Vec::new();
+	for tag in term.term_tags().chain(term.definition_tags()) {
+		if !names.iter().any(|x| x == tag.name()) {
+			names.push(tag.name().to_string());
+		}
+	}
+	names
+}
+
+fn escape(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+	use jp_dict::{OwnedDB, TermData, Writer};
+
+	use super::*;
+
+	/// Builds a database with the given terms, as
+	/// `(expression, reading, glossary, source, sequence)`.
+	fn test_db(terms: &[(&str, &str, &str, &str, u32)]) -> OwnedDB {
+		let mut w = Writer::new();
+		for &(expression, reading, glossary, source, sequence) in terms.iter() {
+			let term = TermData {
+				expression: w.intern(expression.to_string()),
+				reading: w.intern(reading.to_string()),
+				search_key: w.intern(String::new()),
+				score: 0,
+				sequence,
+				frequency: 0,
+				glossary: vec![w.intern(glossary.to_string())],
+				rules: Vec::new(),
+				term_tags: Vec::new(),
+				definition_tags: Vec::new(),
+				source: w.intern(source.to_string()),
+			};
+			w.push_term(term);
+		}
+		let mut data = Vec::new();
+		w.write(&mut data).unwrap();
+		OwnedDB::load(&data).unwrap()
+	}
+
+	/// Writes all terms from the database to a deck and returns the rows.
+	fn rows(data: &OwnedDB, templates: &[Template]) -> Vec<String> {
+		let db = data.db();
+		let terms: Vec<_> = (0..db.term_count()).map(|n| db.term(n).unwrap()).collect();
+		let mut deck = Deck::new(templates);
+		let mut out = Vec::new();
+		deck.write(&mut out, "query", &terms).unwrap();
+		let out = String::from_utf8(out).unwrap();
+		out.lines().map(|x| x.to_string()).collect()
+	}
+
+	#[test]
+	fn parse() {
+		let template = Template::parse("<b>{expression}</b> ({reading})").unwrap();
+		assert_eq!(template.parts.len(), 5);
+		assert!(Template::parse("plain text").is_ok());
+		assert!(Template::parse("").is_ok());
+
+		let err = Template::parse("{expression").err().unwrap();
+		assert_eq!(err, "unclosed `{` in template `{expression`");
+		let err = Template::parse("a {expression} {meaning}").err().unwrap();
+		assert_eq!(err, "invalid template variable `{meaning}`");
+		let err = Template::parse("{}").err().unwrap();
+		assert_eq!(err, "invalid template variable `{}`");
+	}
+
+	#[test]
+	fn render() {
+		let data = test_db(&[
+			("<東京>", "とうきょう", "a & b\tc", "JMdict", 1),
+			("ひらがな", "ひらがな", "kana", "JMdict", 2),
+		]);
+		let db = data.db();
+
+		let template = Template::parse("{expression}|{reading}|{glossary}|{query}").unwrap();
+		let term = db.term(0).unwrap();
+		assert_eq!(
+			template.render("<q>", &term),
+			"&lt;東京&gt;|とうきょう|a &amp; b c|&lt;q&gt;"
+		);
+
+		// The reading is empty when the same as the expression.
+		let term = db.term(1).unwrap();
+		assert_eq!(template.render("", &term), "ひらがな||kana|");
+	}
+
+	#[test]
+	fn deck() {
+		let data = test_db(&[
+			("日本", "にほん", "Japan", "JMdict", 100),
+			("日本", "にっぽん", "Japan", "JMdict", 100),
+			("日本", "にほん", "Japan", "JMnedict", 100),
+			("本", "ほん", "book", "other", 0),
+			("本", "もと", "origin", "other", 0),
+		]);
+		let templates = [Template::parse("{reading} ({source})").unwrap()];
+		assert_eq!(
+			rows(&data, &templates),
+			[
+				"にほん (JMdict)\t",
+				"にほん (JMnedict)\t",
+				"ほん (other)\t",
+				"もと (other)\t",
+			]
+		);
+	}
+}
@@ -0,0 +1,389 @@
+//! Command line tool for batch dictionary lookups.
+//!
+//! Reads words from the arguments or from stdin (one per line), searches
+//! them in the dictionary and prints the results as JSON Lines, TSV or in the
+//! human readable format. The `anki` format writes flashcards that can be
+//! imported in Anki.
+
+extern crate serde_json;
+
+extern crate jp_dict;
+
+use std::io::{BufRead, Write};
+
+use jp_dict::KnownKanji;
+
+mod anki;
+use anki::Template;
+
+mod output;
+use output::{Field, Format, Output};
+
+const USAGE: &str = "\
+Usage: jp-dict [OPTIONS] [WORD...]
+
+Searches each WORD in the dictionary. If no words are given, they are read
+from stdin, one per line.
+
+Options:
+    -m, --mode MODE        search mode: exact (default), prefix, suffix,
+                           pattern, romaji, sequence or text
+    -d, --distance N       maximum number of typos for the romaji mode
+                           (default 1)
+    -s, --source NAME      source dictionary for the sequence mode (default
+                           is all dictionaries)
+    -f, --format FORMAT    output format: human (default), jsonl, tsv or anki
+    -n, --limit N          maximum number of terms per word (default 10, 0 for
+                           no limit). Not used in the sequence and text modes
+    -F, --fields FIELDS    comma separated list of fields to output for the
+                           jsonl and tsv formats
+    -k, --kanji            include the kanji in the term expression
+    -H, --header           print a header line for the tsv format
+    -T, --template TEXT    template for an anki card field, can be repeated
+                           for multiple fields (see below)
+    -K, --known KANJI      only output terms written with known kanji (see
+                           below), can be repeated
+        --jlpt LEVEL       add the kanji for the old JLPT LEVEL (4 to 1) and
+                           the easier levels to the known kanji
+        --grade GRADE      add the kanji taught up to the school GRADE (1 to
+                           6, 8 for the remaining jouyou kanji) to the known
+                           kanji
+    -h, --help             print this help
+
+Modes:
+    In the sequence mode, each WORD is a sequence number from the source
+    dictionary. In the text mode, each WORD is a text and the output has the
+    dictionary terms found in the text (see `DB::to_reading`).
+
+Known kanji:
+    With the --known, --jlpt or --grade options, only the terms that can be
+    read with the known kanji are output (see `KnownKanji::can_read`). Terms
+    written only in kana are always readable. This can be used with the text
+    mode and the anki format to build cards for a text at the reader's level.
+
+Fields:
+    query, index, expression, reading, search_key, score, sequence,
+    frequency, source, glossary, rules, term_tags, definition_tags, kanji
+
+The default fields are query, expression, reading, frequency and glossary.
+For the tsv format, lists are joined with `; `.
+
+Anki:
+    The anki format writes one card per term, with one field for each
+    template and the term tags in the last field. Terms with the same sequence
+    number are only written once. The default fields are:
+
+        {expression}, {furigana}, {reading}, {glossary}, {frequency}
+
+    Template variables:
+
+";
+
+#[derive(Copy, Clone)]
+enum Mode {
+	Exact,
+	Prefix,
+	Suffix,
+	Pattern,
+	Romaji,
+	Sequence,
+	Text,
+}
+
+struct Options {
+	mode: Mode,
+	distance: usize,
+	source: Option<String>,
+	format: Format,
+	limit: usize,
+	fields: Vec<Field>,
+	kanji: bool,
+	header: bool,
+	templates: Vec<Template>,
+	known: Option<String>,
+	jlpt: Option<u32>,
+	grade: Option<u32>,
+	words: Vec<String>,
+}
+
+fn main() {
+	let options = match parse_args(std::env::args().skip(1)) {
+		Ok(Some(options)) => options,
+		Ok(None) => {
+			print!("{}{}", USAGE, anki::VARIABLES);
+			return;
+		}
+		Err(err) => {
+			eprintln!("jp-dict: {}\n\n{}", err, USAGE);
+			std::process::exit(1);
+		}
+	};
+
+	// Write errors (e.g. a closed pipe) just stop the output.
+	if let Err(err) = run(&options) {
+		if err.kind() != std::io::ErrorKind::BrokenPipe {
+			eprintln!("jp-dict: {}", err);
+			std::process::exit(2);
+		}
+	}
+}
+
+fn run(options: &Options) -> std::io::Result<()> {
+	let db = jp_dict::get_db();
+	let stdout = std::io::stdout();
+	let mut out = Output::new(
+		std::io::BufWriter::new(stdout.lock()),
+		options.format,
+		&options.fields,
+		options.kanji,
+		&options.templates,
+	);
+
+	if options.header || matches!(options.format, Format::Anki) {
+		out.header()?;
+	}
+
+	// Listing the sources scans all terms, so it is done once for all words.
+	let sources = match options.source {
+		Some(ref source) => vec![source.as_str()],
+		None if matches!(options.mode, Mode::Sequence) => sources(db),
+		None => Vec::new(),
+	};
+
+	let known = known_kanji(db, options);
+	let known = known.as_ref();
+
+	if !options.words.is_empty() {
+		for word in options.words.iter() {
+			lookup(db, options, &sources, known, &mut out, word)?;
+		}
+	} else {
+		let stdin = std::io::stdin();
+		for line in stdin.lock().lines() {
+			let line = line?;
+			let word = line.trim();
+			if !word.is_empty() {
+				lookup(db, options, &sources, known, &mut out, word)?;
+			}
+		}
+	}
+
+	out.flush()
+}
+
+fn lookup<W: Write>(
+	db: &jp_dict::DB,
+	options: &Options,
+	sources: &[&str],
+	known: Option<&KnownKanji>,
+	out: &mut Output<W>,
+	word: &str,
+) -> std::io::Result<()> {
+	let indexes: Vec<usize> = match options.mode {
+		Mode::Romaji => {
+			let found = db.search_fuzzy(word, options.distance);
+			found.into_iter().map(|x| x.term).collect()
+		}
+		Mode::Sequence => {
+			let sequence = match word.parse() {
+				Ok(sequence) => sequence,
+				Err(_) => {
+					eprintln!("jp-dict: invalid sequence number `{}`", word);
+					return Ok(());
+				}
+			};
+			let terms = sources
+				.iter()
+				.flat_map(|&source| db.terms_by_sequence(source, sequence));
+			let mut indexes: Vec<_> = terms.map(|x| x.index()).collect();
+			indexes.sort_unstable();
+			return write_terms(db, out, word, readable(db, known, indexes));
+		}
+		Mode::Text => {
+			let mut indexes = Vec::new();
+			for segment in db.to_reading(word).segments {
+				if let Some(index) = segment.term {
+					if !indexes.contains(&index) {
+						indexes.push(index);
+					}
+				}
+			}
+			return write_terms(db, out, word, readable(db, known, indexes));
+		}
+		mode => {
+			let mut set = jp_dict::ResultSet::default();
+			match mode {
+				Mode::Exact => db.search_term(word, &mut set),
+				Mode::Prefix => db.search_prefix(word, &mut set),
+				Mode::Suffix => db.search_suffix(word, &mut set),
+				Mode::Pattern => db.search_pattern(word, &mut set),
+				Mode::Romaji | Mode::Sequence | Mode::Text => unreachable!(),
+			};
+			set.iter().collect()
+		}
+	};
+
+	let mut indexes = readable(db, known, indexes);
+	if options.limit > 0 {
+		indexes.truncate(options.limit);
+	}
+	write_terms(db, out, word, indexes)
+}
+
+fn write_terms<W: Write>(
+	db: &jp_dict::DB,
+	out: &mut Output<W>,
+	word: &str,
+	indexes: Vec<usize>,
+) -> std::io::Result<()> {
+	let terms: Vec<_> = indexes
+		.into_iter()
+		.map(|index| db.term(index).unwrap())
+		.collect();
+	out.write(db, word, &terms)
+}
+
+/// Returns the known kanji from the options, if any.
+fn known_kanji(db: &jp_dict::DB, options: &Options) -> Option<KnownKanji> {
+	if options.known.is_none() && options.jlpt.is_none() && options.grade.is_none() {
+		return None;
+	}
+	let mut known = KnownKanji::new();
+	if let Some(ref text) = options.known {
+		known.add_text(text);
+	}
+	if let Some(level) = options.jlpt {
+		for chr in db.kanji_by_jlpt(level).iter() {
+			known.insert(chr);
+		}
+	}
+	if let Some(grade) = options.grade {
+		for chr in db.kanji_by_grade(grade).iter() {
+			known.insert(chr);
+		}
+	}
+	Some(known)
+}
+
+/// Keeps only the terms readable with the known kanji.
+fn readable(db: &jp_dict::DB, known: Option<&KnownKanji>, mut indexes: Vec<usize>) -> Vec<usize> {
+	if let Some(known) = known {
+		indexes.retain(|&index| known.can_read(db.term(index).unwrap().expression()));
+	}
+	indexes
+}
+
+/// Returns the names of all source dictionaries with terms.
+fn sources<'db>(db: &'db jp_dict::DB<'db>) -> Vec<&'db str> {
+	let mut sources: Vec<&str> = Vec::new();
+	for index in 0..db.term_count() {
+		let source = db.term(index).unwrap().source();
+		if !sources.contains(&source) {
+			sources.push(source);
+		}
+	}
+	sources
+}
+
+fn parse_args<T: Iterator<Item = String>>(mut args: T) -> Result<Option<Options>, String> {
+	let mut options = Options {
+		mode: Mode::Exact,
+		distance: 1,
+		source: None,
+		format: Format::Human,
+		limit: 10,
+		fields: vec![
+			Field::Query,
+			Field::Expression,
+			Field::Reading,
+			Field::Frequency,
+			Field::Glossary,
+		],
+		kanji: false,
+		header: false,
+		templates: Vec::new(),
+		known: None,
+		jlpt: None,
+		grade: None,
+		words: Vec::new(),
+	};
+
+	while let Some(arg) = args.next() {
+		let mut value = |name: &str| match args.next() {
+			Some(value) => Ok(value),
+			None => Err(format!("missing value for {}", name)),
+		};
+		match arg.as_str() {
+			"-m" | "--mode" => {
+				options.mode = match value(&arg)?.as_str() {
+					"exact" => Mode::Exact,
+					"prefix" => Mode::Prefix,
+					"suffix" => Mode::Suffix,
+					"pattern" => Mode::Pattern,
+					"romaji" => Mode::Romaji,
+					"sequence" => Mode::Sequence,
+					"text" => Mode::Text,
+					mode => return Err(format!("invalid search mode `{}`", mode)),
+				}
+			}
+			"-d" | "--distance" => options.distance = parse_number(&arg, &value(&arg)?)?,
+			"-s" | "--source" => options.source = Some(value(&arg)?),
+			"-f" | "--format" => {
+				options.format = match value(&arg)?.as_str() {
+					"human" => Format::Human,
+					"jsonl" => Format::JsonLines,
+					"tsv" => Format::Tsv,
+					"anki" => Format::Anki,
+					format => return Err(format!("invalid format `{}`", format)),
+				}
+			}
+			"-n" | "--limit" => options.limit = parse_number(&arg, &value(&arg)?)?,
+			"-F" | "--fields" => {
+				let mut fields = Vec::new();
+				for name in value(&arg)?.split(',').map(|x| x.trim()) {
+					match Field::parse(name) {
+						Some(field) => fields.push(field),
+						None => return Err(format!("invalid field `{}`", name)),
+					}
+				}
+				options.fields = fields;
+			}
+			"-k" | "--kanji" => options.kanji = true,
+			"-H" | "--header" => options.header = true,
+			"-T" | "--template" => options.templates.push(Template::parse(&value(&arg)?)?),
+			"-K" | "--known" => {
+				let known = options.known.get_or_insert_with(String::new);
+				known.push_str(&value(&arg)?);
+			}
+			"--jlpt" => options.jlpt = Some(parse_number(&arg, &value(&arg)?)? as u32),
+			"--grade" => options.grade = Some(parse_number(&arg, &value(&arg)?)? as u32),
+			"-h" | "--help" => return Ok(None),
+			"--" => {
+				options.words.extend(args);
+				break;
+			}
+			_ if arg.starts_with('-') && arg.len() > 1 => {
+				return Err(format!("invalid option `{}`", arg));
+			}
+			_ => options.words.push(arg),
+		}
+	}
+
+	if options.kanji && !options.fields.contains(&Field::Kanji) {
+		options.fields.push(Field::Kanji);
+	}
+
+	if options.templates.is_empty() {
+		for &text in anki::DEFAULT_TEMPLATES {
+			options.templates.push(Template::parse(text).unwrap());
+		}
+	}
+
+	Ok(Some(options))
+}
+
+fn parse_number(name: &str, value: &str) -> Result<usize, String> {
+	value
+		.parse()
+		.map_err(|_| format!("invalid number for {}: `{}`", name, value))
+}
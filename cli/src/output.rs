@@ -0,0 +1,257 @@
+//! Output formats for the lookup results.
+
+use std::io::Write;
+
+use serde_json::{json, Map, Value};
+
+use jp_dict::{Kanji, Tag, Term, DB};
+
+use anki::{Deck, Template};
+
+#[derive(Copy, Clone)]
+pub enum Format {
+	/// The `Display` output for the terms and kanji.
+	Human,
+	/// One JSON object per term.
+	JsonLines,
+	/// One tab separated line per term.
+	Tsv,
+	/// Anki cards (see `anki::Deck`).
+	Anki,
+}
+
+/// Output field for the `JsonLines` and `Tsv` formats.
+#[derive(Copy, Clone, PartialEq, Eq)]
+pub enum Field {
+	Query,
+	Index,
+	Expression,
+	Reading,
+	SearchKey,
+	Score,
+	Sequence,
+	Frequency,
+	Source,
+	Glossary,
+	Rules,
+	TermTags,
+	DefinitionTags,
+	Kanji,
+}
+
+impl Field {
+	pub fn parse(name: &str) -> Option<Field> {
+		let field = match name {
+			"query" => Field::Query,
+			"index" => Field::Index,
+			"expression" => Field::Expression,
+			"reading" => Field::Reading,
+			"search_key" => Field::SearchKey,
+			"score" => Field::Score,
+			"sequence" => Field::Sequence,
+			"frequency" => Field::Frequency,
+			"source" => Field::Source,
+			"glossary" => Field::Glossary,
+			"rules" => Field::Rules,
+			"term_tags" => Field::TermTags,
+			"definition_tags" => Field::DefinitionTags,
+			"kanji" => Field::Kanji,
+			_ => return None,
+		};
+		Some(field)
+	}
+
+	pub fn name(&self) -> &'static str {
+		match self {
+			Field::Query => "query",
+			Field::Index => "index",
+			Field::Expression => "expression",
+			Field::Reading => "reading",
+			Field::SearchKey => "search_key",
+			Field::Score => "score",
+			Field::Sequence => "sequence",
+			Field::Frequency => "frequency",
+			Field::Source => "source",
+			Field::Glossary => "glossary",
+			Field::Rules => "rules",
+			Field::TermTags => "term_tags",
+			Field::DefinitionTags => "definition_tags",
+			Field::Kanji => "kanji",
+		}
+	}
+}
+
+pub struct Output<'f, W: Write> {
+	writer: W,
+	format: Format,
+	fields: &'f [Field],
+	kanji: bool,
+	deck: Deck<'f>,
+}
+
+impl<'f, W: Write> Output<'f, W> {
+	pub fn new(
+		writer: W,
+		format: Format,
+		fields: &'f [Field],
+		kanji: bool,
+		templates: &'f [Template],
+	) -> Output<'f, W> {
+		Output {
+			writer,
+			format,
+			fields,
+			kanji,
+			deck: Deck::new(templates),
+		}
+	}
+
+	/// Writes the header lines. Only used for the `Tsv` and `Anki` formats.
+	pub fn header(&mut self) -> std::io::Result<()> {
+		match self.format {
+			Format::Tsv => {
+				let names: Vec<_> = self.fields.iter().map(|x| x.name()).collect();
+				writeln!(self.writer, "{}", names.join("\t"))?;
+			}
+			Format::Anki => self.deck.header(&mut self.writer)?,
+			_ => {}
+		}
+		Ok(())
+	}
+
+	/// Writes the terms found for a query.
+	pub fn write(&mut self, db: &DB, query: &str, terms: &[Term]) -> std::io::Result<()> {
+		match self.format {
+			Format::Human => self.write_human(db, query, terms),
+			Format::JsonLines => {
+				for term in terms {
+					let mut row = Map::new();
+					for &field in self.fields {
+						row.insert(field.name().to_string(), to_json(db, field, query, term));
+					}
+					writeln!(self.writer, "{}", Value::Object(row))?;
+				}
+				Ok(())
+			}
+			Format::Tsv => {
+				for term in terms {
+					let row: Vec<_> = self
+						.fields
+						.iter()
+						.map(|&field| to_text(db, field, query, term))
+						.collect();
+					writeln!(self.writer, "{}", row.join("\t"))?;
+				}
+				Ok(())
+			}
+			Format::Anki => self.deck.write(&mut self.writer, query, terms),
+		}
+	}
+
+	pub fn flush(&mut self) -> std::io::Result<()> {
+		self.writer.flush()
+	}
+
+	fn write_human(&mut self, db: &DB, query: &str, terms: &[Term]) -> std::io::Result<()> {
+		writeln!(self.writer, "## {} ({} terms)\n", query, terms.len())?;
+		for term in terms {
+			writeln!(self.writer, "{}\n", term)?;
+			if self.kanji {
+				for kanji in term_kanji(db, term) {
+					for line in kanji.to_string().lines() {
+						if line.is_empty() {
+							writeln!(self.writer)?;
+						} else {
+							writeln!(self.writer, "    {}", line)?;
+						}
+					}
+					writeln!(self.writer)?;
+				}
+			}
+		}
+		Ok(())
+	}
+}
+
+/// Returns the kanji in the term expression, without repetitions.
+fn term_kanji<'db>(db: &'db DB<'db>, term: &Term) -> Vec<Kanji<'db, 'db>> {
+	let mut chars: Vec<char> = Vec::new();
+	for chr in term.expression().chars() {
+		if !chars.contains(&chr) {
+			chars.push(chr);
+		}
+	}
+	chars.into_iter().filter_map(|chr| db.kanji(chr)).collect()
+}
+
+fn to_json(db: &DB, field: Field, query: &str, term: &Term) -> Value {
+	let tags = |tags: &mut dyn Iterator<Item = Tag>| -> Value {
+		tags.map(|x| Value::from(x.name())).collect()
+	};
+	match field {
+		Field::Query => json!(query),
+		Field::Index => json!(term.index()),
+		Field::Expression => json!(term.expression()),
+		Field::Reading => json!(term.reading()),
+		Field::SearchKey => json!(term.search_key()),
+		Field::Score => json!(term.score()),
+		Field::Sequence => json!(term.sequence()),
+		Field::Frequency => json!(term.frequency()),
+		Field::Source => json!(term.source()),
+		Field::Glossary => term.glossary().collect(),
+		Field::Rules => tags(&mut term.rules()),
+		Field::TermTags => tags(&mut term.term_tags()),
+		Field::DefinitionTags => tags(&mut term.definition_tags()),
+		Field::Kanji => term_kanji(db, term)
+			.into_iter()
+			.map(|kanji| {
+				json!({
+					"character": kanji.character(),
+					"onyomi": kanji.onyomi().collect::<Vec<_>>(),
+					"kunyomi": kanji.kunyomi().collect::<Vec<_>>(),
+					"meanings": kanji.meanings().collect::<Vec<_>>(),
+					"frequency": kanji.frequency(),
+				})
+			})
+			.collect(),
+	}
+}
+
+fn to_text(db: &DB, field: Field, query: &str, term: &Term) -> String {
+	let text = match field {
+		Field::Query => query.to_string(),
+		Field::Index => term.index().to_string(),
+		Field::Expression => term.expression().to_string(),
+		Field::Reading => term.reading().to_string(),
+		Field::SearchKey => term.search_key().to_string(),
+		Field::Score => term.score().to_string(),
+		Field::Sequence => term.sequence().to_string(),
+		Field::Frequency => term.frequency().map(|x| x.to_string()).unwrap_or_default(),
+		Field::Source => term.source().to_string(),
+		Field::Glossary => term.glossary().collect::<Vec<_>>().join("; "),
+		Field::Rules => join_tags(term.rules()),
+		Field::TermTags => join_tags(term.term_tags()),
+		Field::DefinitionTags => join_tags(term.definition_tags()),
+		Field::Kanji => term_kanji(db, term)
+			.into_iter()
+			.map(|kanji| {
+				let readings: Vec<_> = kanji.onyomi().chain(kanji.kunyomi()).collect();
+				let meanings: Vec<_> = kanji.meanings().collect();
+				format!(
+					"{} [{}] {}",
+					kanji.character(),
+					readings.join(", "),
+					meanings.join(", ")
+				)
+			})
+			.collect::<Vec<_>>()
+			.join("; "),
+	};
+
+	// Tabs and line breaks would break the row format.
+	text.replace(['\t', '\r', '\n'], " ")
+}
+
+fn join_tags<'a, T: Iterator<Item = Tag<'a, 'a>>>(tags: T) -> String {
+	tags.map(|x| x.name()).collect::<Vec<_>>().join("; ")
+}
@@ -0,0 +1,166 @@
+//! Command line tool to extract the vocabulary from Japanese text files.
+//!
+//! Reads the text from the given files (or stdin) and writes the distinct
+//! dictionary words as CSV, with the number of occurrences of each word.
+
+extern crate jp_dict;
+
+use std::io::{Read, Write};
+
+use jp_dict::VocabularyOrder;
+
+const USAGE: &str = "\
+Usage: jp-dict-vocab [OPTIONS] [FILE...]
+
+Extracts the distinct dictionary words in the text FILEs (or stdin) and writes
+them as CSV. Inflected verbs and adjectives are counted as their dictionary
+form.
+
+Options:
+    -s, --sort ORDER       sort order: count (default), rarest, frequent or
+                           text (order of first occurrence)
+    -m, --min-count N      only output words with at least N occurrences
+    -n, --limit N          maximum number of words to output
+        --no-header        do not output the header line
+    -h, --help             print this help
+
+Columns:
+    expression, reading, count, rank, frequency, forms, glossary
+
+The `rank` is the position of the word in the dictionary frequency order (1
+for the most frequent). The `forms` are the distinct forms of the word found
+in the text, separated by spaces.
+";
+
+struct Options {
+	order: VocabularyOrder,
+	min_count: usize,
+	limit: usize,
+	header: bool,
+	files: Vec<String>,
+}
+
+fn main() {
+	let options = match parse_args(std::env::args().skip(1)) {
+		Ok(Some(options)) => options,
+		Ok(None) => {
+			print!("{}", USAGE);
+			return;
+		}
+		Err(err) => {
+			eprintln!("jp-dict-vocab: {}\n\n{}", err, USAGE);
+			std::process::exit(1);
+		}
+	};
+
+	if let Err(err) = run(&options) {
+		if err.kind() != std::io::ErrorKind::BrokenPipe {
+			eprintln!("jp-dict-vocab: {}", err);
+			std::process::exit(2);
+		}
+	}
+}
+
+fn run(options: &Options) -> std::io::Result<()> {
+	let mut text = String::new();
+	if options.files.is_empty() {
+		std::io::stdin().read_to_string(&mut text)?;
+	} else {
+		for name in options.files.iter() {
+			let file_text = std::fs::read_to_string(name)
+				.map_err(|err| std::io::Error::new(err.kind(), format!("{}: {}", name, err)))?;
+			text.push_str(&file_text);
+			text.push('\n');
+		}
+	}
+
+	let db = jp_dict::get_db();
+	let mut entries = db.extract_vocabulary(&text);
+	entries.retain(|x| x.count >= options.min_count);
+	options.order.sort(&mut entries);
+	if options.limit > 0 {
+		entries.truncate(options.limit);
+	}
+
+	let stdout = std::io::stdout();
+	let mut out = std::io::BufWriter::new(stdout.lock());
+	if options.header {
+		writeln!(
+			out,
+			"expression,reading,count,rank,frequency,forms,glossary"
+		)?;
+	}
+	for entry in entries {
+		let term = db.term(entry.term).unwrap();
+		let glossary: Vec<_> = term.glossary().collect();
+		let row = [
+			term.expression().to_string(),
+			term.reading().to_string(),
+			entry.count.to_string(),
+			(entry.term + 1).to_string(),
+			entry.frequency.map(|x| x.to_string()).unwrap_or_default(),
+			entry.forms.join(" "),
+			glossary.join("; "),
+		];
+		let row: Vec<_> = row.iter().map(|x| csv_field(x)).collect();
+		writeln!(out, "{}", row.join(","))?;
+	}
+	out.flush()
+}
+
+/// Quotes a CSV field if needed.
+fn csv_field(text: &str) -> String {
+	if text.contains(['"', ',', '\n', '\r']) {
+		format!("\"{}\"", text.replace('"', "\"\""))
+	} else {
+		text.to_string()
+	}
+}
+
+fn parse_args<T: Iterator<Item = String>>(mut args: T) -> Result<Option<Options>, String> {
+	let mut options = Options {
+		order: VocabularyOrder::Count,
+		min_count: 1,
+		limit: 0,
+		header: true,
+		files: Vec::new(),
+	};
+
+	while let Some(arg) = args.next() {
+		let mut value = |name: &str| match args.next() {
+			Some(value) => Ok(value),
+			None => Err(format!("missing value for {}", name)),
+		};
+		match arg.as_str() {
+			"-s" | "--sort" => {
+				options.order = match value(&arg)?.as_str() {
+					"count" => VocabularyOrder::Count,
+					"rarest" => VocabularyOrder::Rarest,
+					"frequent" => VocabularyOrder::Frequent,
+					"text" => VocabularyOrder::Text,
+					order => return Err(format!("invalid sort order `{}`", order)),
+				}
+			}
+			"-m" | "--min-count" => options.min_count = parse_number(&arg, &value(&arg)?)?,
+			"-n" | "--limit" => options.limit = parse_number(&arg, &value(&arg)?)?,
+			"--no-header" => options.header = false,
+			"-h" | "--help" => return Ok(None),
+			"--" => {
+				options.files.extend(args);
+				break;
+			}
+			_ if arg.starts_with('-') && arg.len() > 1 => {
+				return Err(format!("invalid option `{}`", arg));
+			}
+			_ => options.files.push(arg),
+		}
+	}
+
+	Ok(Some(options))
+}
+
+fn parse_number(name: &str, value: &str) -> Result<usize, String> {
+	value
+		.parse()
+		.map_err(|_| format!("invalid number for {}: `{}`", name, value))
+}
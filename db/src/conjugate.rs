@@ -0,0 +1,782 @@
+//! Conjugation tables for verbs and i-adjectives.
+
+use super::deinflect::GODAN;
+use super::AdjectiveClass;
+use super::PartOfSpeech;
+use super::Term;
+use super::VerbClass;
+use super::VerbType;
+
+/// Conjugated form. See [Term::conjugate].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum ConjugationForm {
+	/// Dictionary or present form (e.g. `食べる`, `食べない`, `食べます`).
+	NonPast,
+	/// Past form (e.g. `食べた`).
+	Past,
+	/// Te-form (e.g. `食べて`).
+	Te,
+	/// Volitional (e.g. `食べよう`).
+	Volitional,
+	/// Imperative (e.g. `食べろ`, `食べるな`).
+	Imperative,
+	/// Provisional conditional (e.g. `食べれば`).
+	Conditional,
+	/// Past conditional (e.g. `食べたら`).
+	Tara,
+	/// Desire (e.g. `食べたい`).
+	Desire,
+	/// Potential (e.g. `食べられる`).
+	Potential,
+	/// Passive (e.g. `食べられる`).
+	Passive,
+	/// Causative (e.g. `食べさせる`).
+	Causative,
+	/// Causative passive (e.g. `食べさせられる`).
+	CausativePassive,
+	/// Adverbial form of adjectives (e.g. `高く`).
+	Adverbial,
+}
+
+/// Single entry in the conjugation table for a term.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Conjugation {
+	pub form: ConjugationForm,
+	/// Polite (`ます` or `です`) form.
+	pub polite: bool,
+	pub negative: bool,
+	/// Conjugated expression.
+	pub expression: String,
+	/// Conjugated reading. Empty if the term has no reading.
+	pub reading: String,
+}
+
+/// Word class used for the conjugation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Class {
+	Verb(VerbClass, VerbType),
+	Adjective(AdjectiveClass),
+}
+
+/// Stems used to build the verb forms. The `potential`, `passive`,
+/// `causative` and `causative_passive` are complete ichidan verbs.
+struct VerbStems {
+	dict: String,
+	/// Stem for `ない`.
+	nai: String,
+	/// Stem for `ます` and `たい`.
+	masu: String,
+	te: String,
+	ta: String,
+	/// Stem for `ば`.
+	ba: String,
+	volitional: String,
+	imperative: String,
+	potential: String,
+	passive: String,
+	causative: String,
+	causative_passive: String,
+}
+
+type Row = (ConjugationForm, bool, bool, String);
+
+impl<'db, 'a: 'db> Term<'db, 'a> {
+	/// Generates the conjugation table for a verb or i-adjective, using the
+	/// term parts of speech or [rules](Term::rules) to determine the
+	/// conjugation class.
+	///
+	/// Irregular verbs (`する`, `来る`, `行く`, `ある`, `得る` read as `うる`
+	/// and the honorific `v5aru` verbs), the `いい` adjective and nouns taking
+	/// `する` are supported. Returns an empty list for other terms.
+	pub fn conjugate(&'a self) -> Vec<Conjugation> {
+		let class = match self.conjugation_class() {
+			Some(class) => class,
+			None => return Vec::new(),
+		};
+
+		let expression = match conjugate_word(self.expression(), class) {
+			Some(rows) => rows,
+			None => return Vec::new(),
+		};
+		let reading = match self.reading() {
+			"" => None,
+			reading => conjugate_word(reading, class),
+		};
+
+		let mut out = Vec::new();
+		for (index, (form, polite, negative, text)) in expression.into_iter().enumerate() {
+			let reading = match reading {
+				Some(ref rows) if rows.len() > index => rows[index].3.clone(),
+				_ => String::new(),
+			};
+			out.push(Conjugation {
+				form,
+				polite,
+				negative,
+				expression: text,
+				reading,
+			});
+		}
+		out
+	}
+
+	fn conjugation_class(&'a self) -> Option<Class> {
+		for pos in self.parts_of_speech() {
+			match pos {
+				PartOfSpeech::Verb(class, kind) => return Some(Class::Verb(class, kind)),
+				PartOfSpeech::Adjective(class @ AdjectiveClass::I)
+				| PartOfSpeech::Adjective(class @ AdjectiveClass::Ii) => {
+					return Some(Class::Adjective(class));
+				}
+				_ => {}
+			}
+		}
+
+		// Fallback to the deinflection rules, which do not have the godan
+		// ending.
+		let expression = self.expression();
+		for rule in self.rules() {
+			let class = match rule.name() {
+				"v1" => Class::Verb(VerbClass::Ichidan, VerbType::Regular),
+				"v5" => match expression.chars().last() {
+					Some(chr) => Class::Verb(VerbClass::Godan(chr), VerbType::Regular),
+					None => continue,
+				},
+				"vk" => Class::Verb(VerbClass::Kuru, VerbType::Regular),
+				"vs" if expression.ends_with("する") => {
+					Class::Verb(VerbClass::Suru, VerbType::Regular)
+				}
+				"vs" => Class::Verb(VerbClass::Suru, VerbType::Noun),
+				"vz" => Class::Verb(VerbClass::Zuru, VerbType::Regular),
+				"adj-i" => Class::Adjective(AdjectiveClass::I),
+				_ => continue,
+			};
+			return Some(class);
+		}
+		None
+	}
+}
+
+/// Conjugates a single word (either the expression or reading).
+fn conjugate_word(word: &str, class: Class) -> Option<Vec<Row>> {
+	match class {
+		Class::Verb(class, kind) => verb_stems(word, class, kind).map(|x| verb_forms(&x)),
+		Class::Adjective(class) => adjective_forms(word, class),
+	}
+}
+
+fn verb_stems(word: &str, class: VerbClass, kind: VerbType) -> Option<VerbStems> {
+	let stems = match class {
+		VerbClass::Ichidan => {
+			let base = word.strip_suffix("る")?;
+			let imperative = if kind == VerbType::Special {
+				base.to_string()
+			} else {
+				format!("{}ろ", base)
+			};
+			VerbStems {
+				dict: word.to_string(),
+				nai: base.to_string(),
+				masu: base.to_string(),
+				te: format!("{}て", base),
+				ta: format!("{}た", base),
+				ba: format!("{}れ", base),
+				volitional: format!("{}よう", base),
+				imperative,
+				potential: format!("{}られる", base),
+				passive: format!("{}られる", base),
+				causative: format!("{}させる", base),
+				causative_passive: format!("{}させられる", base),
+			}
+		}
+		VerbClass::Godan(ending) => {
+			let base = word.strip_suffix(ending)?;
+			let &(_, i, a, e, o, te, ta) = GODAN.iter().find(|x| x.0.starts_with(ending))?;
+			let mut stems = VerbStems {
+				dict: word.to_string(),
+				nai: format!("{}{}", base, a),
+				masu: format!("{}{}", base, i),
+				te: format!("{}{}", base, te),
+				ta: format!("{}{}", base, ta),
+				ba: format!("{}{}", base, e),
+				volitional: format!("{}{}う", base, o),
+				imperative: format!("{}{}", base, e),
+				potential: format!("{}{}る", base, e),
+				passive: format!("{}{}れる", base, a),
+				causative: format!("{}{}せる", base, a),
+				causative_passive: format!("{}{}せられる", base, a),
+			};
+			if kind == VerbType::Special {
+				match ending {
+					// 行く
+					'く' => {
+						stems.te = format!("{}って", base);
+						stems.ta = format!("{}った", base);
+					}
+					// ある, with the exception of ござる and the other
+					// honorific verbs below
+					'る' if word.ends_with("ある")
+						|| word.ends_with("有る")
+						|| word.ends_with("在る") =>
+					{
+						stems.nai = String::new();
+					}
+					// ござる, なさる, くださる, いらっしゃる, おっしゃる
+					'る' => {
+						stems.masu = format!("{}い", base);
+						stems.imperative = format!("{}い", base);
+					}
+					// 問う, 請う
+					'う' => {
+						stems.te = format!("{}うて", base);
+						stems.ta = format!("{}うた", base);
+					}
+					_ => {}
+				}
+			}
+			stems
+		}
+		VerbClass::Uru => {
+			// Only the dictionary form is read as うる, the other stems are
+			// the ones for the ichidan える. The expression 得る is the same
+			// for both.
+			let ichidan = match word.strip_suffix("うる") {
+				Some(base) => format!("{}える", base),
+				None => word.to_string(),
+			};
+			return verb_stems(&ichidan, VerbClass::Ichidan, VerbType::Regular).map(|mut stems| {
+				stems.dict = word.to_string();
+				stems
+			});
+		}
+		VerbClass::Kuru => {
+			// The kanji 来 is used for all stems, otherwise the stem vowel
+			// changes.
+			let (base, kanji) = match word.strip_suffix("来る") {
+				Some(base) => (base, true),
+				None => (word.strip_suffix("くる")?, false),
+			};
+			let stem = |kana: &str, suffix: &str| {
+				format!("{}{}{}", base, if kanji { "来" } else { kana }, suffix)
+			};
+			VerbStems {
+				dict: word.to_string(),
+				nai: stem("こ", ""),
+				masu: stem("き", ""),
+				te: stem("き", "て"),
+				ta: stem("き", "た"),
+				ba: stem("く", "れ"),
+				volitional: stem("こ", "よう"),
+				imperative: stem("こ", "い"),
+				potential: stem("こ", "られる"),
+				passive: stem("こ", "られる"),
+				causative: stem("こ", "させる"),
+				causative_passive: stem("こ", "させられる"),
+			}
+		}
+		VerbClass::Suru if kind == VerbType::Special => {
+			// Special suru verbs (e.g. 愛する) mostly conjugate as godan
+			// verbs ending in す.
+			let base = word.strip_suffix("する")?;
+			return verb_stems(
+				&format!("{}す", base),
+				VerbClass::Godan('す'),
+				VerbType::Regular,
+			)
+			.map(|mut stems| {
+				stems.dict = word.to_string();
+				stems
+			});
+		}
+		VerbClass::Suru | VerbClass::Zuru => {
+			let (base, dict) = if class == VerbClass::Zuru {
+				(word.strip_suffix("ずる")?, "ずる")
+			} else if kind == VerbType::Noun {
+				(word, "する")
+			} else {
+				(word.strip_suffix("する")?, "する")
+			};
+			let (si, su) = if class == VerbClass::Zuru {
+				("じ", "ず")
+			} else {
+				("し", "す")
+			};
+			VerbStems {
+				dict: format!("{}{}", base, dict),
+				nai: format!("{}{}", base, si),
+				masu: format!("{}{}", base, si),
+				te: format!("{}{}て", base, si),
+				ta: format!("{}{}た", base, si),
+				ba: format!("{}{}れ", base, su),
+				volitional: format!("{}{}よう", base, si),
+				imperative: format!("{}{}ろ", base, si),
+				potential: if class == VerbClass::Zuru {
+					format!("{}じられる", base)
+				} else {
+					format!("{}できる", base)
+				},
+				passive: format!(
+					"{}{}れる",
+					base,
+					if class == VerbClass::Zuru {
+						"じら"
+					} else {
+						"さ"
+					}
+				),
+				causative: format!(
+					"{}{}せる",
+					base,
+					if class == VerbClass::Zuru {
+						"じさ"
+					} else {
+						"さ"
+					}
+				),
+				causative_passive: format!(
+					"{}{}せられる",
+					base,
+					if class == VerbClass::Zuru {
+						"じさ"
+					} else {
+						"さ"
+					}
+				),
+			}
+		}
+		VerbClass::Archaic | VerbClass::Irregular => return None,
+	};
+	Some(stems)
+}
+
+fn verb_forms(stems: &VerbStems) -> Vec<Row> {
+	use self::ConjugationForm::*;
+
+	let s = stems;
+	let mut out: Vec<Row> = vec![
+		(NonPast, false, false, s.dict.clone()),
+		(NonPast, false, true, format!("{}ない", s.nai)),
+		(NonPast, true, false, format!("{}ます", s.masu)),
+		(NonPast, true, true, format!("{}ません", s.masu)),
+		(Past, false, false, s.ta.clone()),
+		(Past, false, true, format!("{}なかった", s.nai)),
+		(Past, true, false, format!("{}ました", s.masu)),
+		(Past, true, true, format!("{}ませんでした", s.masu)),
+		(Te, false, false, s.te.clone()),
+		(Te, false, true, format!("{}なくて", s.nai)),
+		(Te, true, false, format!("{}まして", s.masu)),
+		(Volitional, false, false, s.volitional.clone()),
+		(Volitional, true, false, format!("{}ましょう", s.masu)),
+		(Imperative, false, false, s.imperative.clone()),
+		(Imperative, false, true, format!("{}な", s.dict)),
+		(Conditional, false, false, format!("{}ば", s.ba)),
+		(Conditional, false, true, format!("{}なければ", s.nai)),
+		(Tara, false, false, format!("{}ら", s.ta)),
+		(Tara, false, true, format!("{}なかったら", s.nai)),
+		(Tara, true, false, format!("{}ましたら", s.masu)),
+		(Desire, false, false, format!("{}たい", s.masu)),
+		(Desire, false, true, format!("{}たくない", s.masu)),
+	];
+
+	// The derived forms are all ichidan verbs.
+	let derived = [
+		(Potential, &s.potential),
+		(Passive, &s.passive),
+		(Causative, &s.causative),
+		(CausativePassive, &s.causative_passive),
+	];
+	for &(form, verb) in derived.iter() {
+		let base = &verb[..verb.len() - "る".len()];
+		out.push((form, false, false, verb.clone()));
+		out.push((form, false, true, format!("{}ない", base)));
+		out.push((form, true, false, format!("{}ます", base)));
+		out.push((form, true, true, format!("{}ません", base)));
+	}
+
+	out
+}
+
+fn adjective_forms(word: &str, class: AdjectiveClass) -> Option<Vec<Row>> {
+	use self::ConjugationForm::*;
+
+	// いい uses the よい stem for everything but the non-past.
+	let base = if class == AdjectiveClass::Ii {
+		match word.strip_suffix("いい") {
+			Some(base) => format!("{}よ", base),
+			None => word.strip_suffix("い")?.to_string(),
+		}
+	} else {
+		word.strip_suffix("い")?.to_string()
+	};
+	let b = &base;
+	Some(vec![
+		(NonPast, false, false, word.to_string()),
+		(NonPast, false, true, format!("{}くない", b)),
+		(NonPast, true, false, format!("{}です", word)),
+		(NonPast, true, true, format!("{}くないです", b)),
+		(Past, false, false, format!("{}かった", b)),
+		(Past, false, true, format!("{}くなかった", b)),
+		(Past, true, false, format!("{}かったです", b)),
+		(Past, true, true, format!("{}くなかったです", b)),
+		(Te, false, false, format!("{}くて", b)),
+		(Te, false, true, format!("{}くなくて", b)),
+		(Conditional, false, false, format!("{}ければ", b)),
+		(Conditional, false, true, format!("{}くなければ", b)),
+		(Tara, false, false, format!("{}かったら", b)),
+		(Tara, false, true, format!("{}くなかったら", b)),
+		(Adverbial, false, false, format!("{}く", b)),
+	])
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::testing::*;
+	use super::super::*;
+
+	/// Terms for the tests, as `(expression, reading, part of speech tag)`.
+	const TERMS: &[(&str, &str, &str)] = &[
+		("食べる", "たべる", "v1"),
+		("書く", "かく", "v5k"),
+		("行く", "いく", "v5k-s"),
+		("問う", "とう", "v5u-s"),
+		("ある", "ある", "v5r-i"),
+		("ござる", "ござる", "v5aru"),
+		("得る", "うる", "v5uru"),
+		("する", "する", "vs-i"),
+		("愛する", "あいする", "vs-s"),
+		("略す", "りゃくす", "vs-c"),
+		("来る", "くる", "vk"),
+		("くる", "くる", "vk"),
+		("高い", "たかい", "adj-i"),
+		("いい", "いい", "adj-ix"),
+	];
+
+	fn test_db() -> TestDB {
+		let mut tags: Vec<(&str, &str)> = Vec::new();
+		for &(_, _, tag) in TERMS {
+			if !tags.iter().any(|x| x.0 == tag) {
+				tags.push((tag, "partOfSpeech"));
+			}
+		}
+		let mut w = writer(&tags);
+		for &(expression, reading, tag) in TERMS {
+			let mut data = term(&mut w, expression, reading, "", 0);
+			data.definition_tags = w.get_tags([tag]);
+			w.push_term(data);
+		}
+		TestDB::new(w)
+	}
+
+	/// Checks the conjugated expressions and readings for the term, given in
+	/// the table order separated by whitespace.
+	fn check(db: &DB, expression: &str, reading: &str, expressions: &str, readings: &str) {
+		let term = (0..db.term_count())
+			.map(|index| db.term(index).unwrap())
+			.find(|x| x.expression() == expression && x.reading() == reading)
+			.unwrap();
+		let table = term.conjugate();
+		let actual: Vec<&str> = table.iter().map(|x| x.expression.as_str()).collect();
+		let expected: Vec<&str> = expressions.split_whitespace().collect();
+		assert_eq!(actual, expected, "{} expressions", expression);
+		let actual: Vec<&str> = table.iter().map(|x| x.reading.as_str()).collect();
+		let expected: Vec<&str> = readings.split_whitespace().collect();
+		assert_eq!(actual, expected, "{} readings", expression);
+	}
+
+	#[test]
+	fn v5uru() {
+		let pos = PartOfSpeech::parse("v5uru");
+		assert_eq!(pos, PartOfSpeech::Verb(VerbClass::Uru, VerbType::Regular));
+
+		// Only the dictionary form is read as うる.
+		let data = test_db();
+		let db = data.db();
+		check(
+			&db,
+			"得る",
+			"うる",
+			"得る 得ない 得ます 得ません
+			得た 得なかった 得ました 得ませんでした
+			得て 得なくて 得まして
+			得よう 得ましょう
+			得ろ 得るな
+			得れば 得なければ
+			得たら 得なかったら 得ましたら
+			得たい 得たくない
+			得られる 得られない 得られます 得られません
+			得られる 得られない 得られます 得られません
+			得させる 得させない 得させます 得させません
+			得させられる 得させられない 得させられます 得させられません",
+			"うる えない えます えません
+			えた えなかった えました えませんでした
+			えて えなくて えまして
+			えよう えましょう
+			えろ うるな
+			えれば えなければ
+			えたら えなかったら えましたら
+			えたい えたくない
+			えられる えられない えられます えられません
+			えられる えられない えられます えられません
+			えさせる えさせない えさせます えさせません
+			えさせられる えさせられない えさせられます えさせられません",
+		);
+	}
+
+	#[test]
+	fn vs_c() {
+		let pos = PartOfSpeech::parse("vs-c");
+		assert_eq!(
+			pos,
+			PartOfSpeech::Verb(VerbClass::Godan('す'), VerbType::Regular)
+		);
+
+		let data = test_db();
+		let db = data.db();
+		let expressions = "略す 略さない 略します 略しません
+			略した 略さなかった 略しました 略しませんでした
+			略して 略さなくて 略しまして
+			略そう 略しましょう
+			略せ 略すな
+			略せば 略さなければ
+			略したら 略さなかったら 略しましたら
+			略したい 略したくない
+			略せる 略せない 略せます 略せません
+			略される 略されない 略されます 略されません
+			略させる 略させない 略させます 略させません
+			略させられる 略させられない 略させられます 略させられません";
+		let readings = expressions.replace('略', "りゃく");
+		check(&db, "略す", "りゃくす", expressions, &readings);
+	}
+
+	#[test]
+	fn verbs() {
+		let data = test_db();
+		let db = data.db();
+
+		let expressions = "食べる 食べない 食べます 食べません
+			食べた 食べなかった 食べました 食べませんでした
+			食べて 食べなくて 食べまして
+			食べよう 食べましょう
+			食べろ 食べるな
+			食べれば 食べなければ
+			食べたら 食べなかったら 食べましたら
+			食べたい 食べたくない
+			食べられる 食べられない 食べられます 食べられません
+			食べられる 食べられない 食べられます 食べられません
+			食べさせる 食べさせない 食べさせます 食べさせません
+			食べさせられる 食べさせられない 食べさせられます 食べさせられません";
+		let readings = expressions.replace('食', "た");
+		check(&db, "食べる", "たべる", expressions, &readings);
+
+		let expressions = "書く 書かない 書きます 書きません
+			書いた 書かなかった 書きました 書きませんでした
+			書いて 書かなくて 書きまして
+			書こう 書きましょう
+			書け 書くな
+			書けば 書かなければ
+			書いたら 書かなかったら 書きましたら
+			書きたい 書きたくない
+			書ける 書けない 書けます 書けません
+			書かれる 書かれない 書かれます 書かれません
+			書かせる 書かせない 書かせます 書かせません
+			書かせられる 書かせられない 書かせられます 書かせられません";
+		let readings = expressions.replace('書', "か");
+		check(&db, "書く", "かく", expressions, &readings);
+
+		let expressions = "愛する 愛さない 愛します 愛しません
+			愛した 愛さなかった 愛しました 愛しませんでした
+			愛して 愛さなくて 愛しまして
+			愛そう 愛しましょう
+			愛せ 愛するな
+			愛せば 愛さなければ
+			愛したら 愛さなかったら 愛しましたら
+			愛したい 愛したくない
+			愛せる 愛せない 愛せます 愛せません
+			愛される 愛されない 愛されます 愛されません
+			愛させる 愛させない 愛させます 愛させません
+			愛させられる 愛させられない 愛させられます 愛させられません";
+		let readings = expressions.replace('愛', "あい");
+		check(&db, "愛する", "あいする", expressions, &readings);
+	}
+
+	#[test]
+	fn irregular_verbs() {
+		let data = test_db();
+		let db = data.db();
+
+		let expressions = "行く 行かない 行きます 行きません
+			行った 行かなかった 行きました 行きませんでした
+			行って 行かなくて 行きまして
+			行こう 行きましょう
+			行け 行くな
+			行けば 行かなければ
+			行ったら 行かなかったら 行きましたら
+			行きたい 行きたくない
+			行ける 行けない 行けます 行けません
+			行かれる 行かれない 行かれます 行かれません
+			行かせる 行かせない 行かせます 行かせません
+			行かせられる 行かせられない 行かせられます 行かせられません";
+		let readings = expressions.replace('行', "い");
+		check(&db, "行く", "いく", expressions, &readings);
+
+		let expressions = "問う 問わない 問います 問いません
+			問うた 問わなかった 問いました 問いませんでした
+			問うて 問わなくて 問いまして
+			問おう 問いましょう
+			問え 問うな
+			問えば 問わなければ
+			問うたら 問わなかったら 問いましたら
+			問いたい 問いたくない
+			問える 問えない 問えます 問えません
+			問われる 問われない 問われます 問われません
+			問わせる 問わせない 問わせます 問わせません
+			問わせられる 問わせられない 問わせられます 問わせられません";
+		let readings = expressions.replace('問', "と");
+		check(&db, "問う", "とう", expressions, &readings);
+
+		// The negative forms of ある are just ない.
+		let expressions = "ある ない あります ありません
+			あった なかった ありました ありませんでした
+			あって なくて ありまして
+			あろう ありましょう
+			あれ あるな
+			あれば なければ
+			あったら なかったら ありましたら
+			ありたい ありたくない
+			あれる あれない あれます あれません
+			あられる あられない あられます あられません
+			あらせる あらせない あらせます あらせません
+			あらせられる あらせられない あらせられます あらせられません";
+		check(&db, "ある", "ある", expressions, expressions);
+
+		let expressions = "ござる ござらない ございます ございません
+			ござった ござらなかった ございました ございませんでした
+			ござって ござらなくて ございまして
+			ござろう ございましょう
+			ござい ござるな
+			ござれば ござらなければ
+			ござったら ござらなかったら ございましたら
+			ございたい ございたくない
+			ござれる ござれない ござれます ござれません
+			ござられる ござられない ござられます ござられません
+			ござらせる ござらせない ござらせます ござらせません
+			ござらせられる ござらせられない ござらせられます ござらせられません";
+		check(&db, "ござる", "ござる", expressions, expressions);
+
+		let expressions = "する しない します しません
+			した しなかった しました しませんでした
+			して しなくて しまして
+			しよう しましょう
+			しろ するな
+			すれば しなければ
+			したら しなかったら しましたら
+			したい したくない
+			できる できない できます できません
+			される されない されます されません
+			させる させない させます させません
+			させられる させられない させられます させられません";
+		check(&db, "する", "する", expressions, expressions);
+
+		// The kanji 来 is the same for all forms, while the kana changes.
+		let expressions = "来る 来ない 来ます 来ません
+			来た 来なかった 来ました 来ませんでした
+			来て 来なくて 来まして
+			来よう 来ましょう
+			来い 来るな
+			来れば 来なければ
+			来たら 来なかったら 来ましたら
+			来たい 来たくない
+			来られる 来られない 来られます 来られません
+			来られる 来られない 来られます 来られません
+			来させる 来させない 来させます 来させません
+			来させられる 来させられない 来させられます 来させられません";
+		let readings = "くる こない きます きません
+			きた こなかった きました きませんでした
+			きて こなくて きまして
+			こよう きましょう
+			こい くるな
+			くれば こなければ
+			きたら こなかったら きましたら
+			きたい きたくない
+			こられる こられない こられます こられません
+			こられる こられない こられます こられません
+			こさせる こさせない こさせます こさせません
+			こさせられる こさせられない こさせられます こさせられません";
+		check(&db, "来る", "くる", expressions, readings);
+		check(&db, "くる", "くる", readings, readings);
+	}
+
+	#[test]
+	fn adjectives() {
+		let data = test_db();
+		let db = data.db();
+
+		let expressions = "高い 高くない 高いです 高くないです
+			高かった 高くなかった 高かったです 高くなかったです
+			高くて 高くなくて
+			高ければ 高くなければ
+			高かったら 高くなかったら
+			高く";
+		let readings = expressions.replace('高', "たか");
+		check(&db, "高い", "たかい", expressions, &readings);
+
+		// Only the non-past form uses いい.
+		let expressions = "いい よくない いいです よくないです
+			よかった よくなかった よかったです よくなかったです
+			よくて よくなくて
+			よければ よくなければ
+			よかったら よくなかったら
+			よく";
+		check(&db, "いい", "いい", expressions, expressions);
+	}
+
+	#[test]
+	fn forms() {
+		use super::ConjugationForm::*;
+
+		let data = test_db();
+		let db = data.db();
+		let find = |expression: &str, form, polite, negative| {
+			let term = (0..db.term_count())
+				.map(|index| db.term(index).unwrap())
+				.find(|x| x.expression() == expression)
+				.unwrap();
+			let table = term.conjugate();
+			let row = table
+				.iter()
+				.find(|x| x.form == form && x.polite == polite && x.negative == negative);
+			row.map(|x| x.expression.clone())
+		};
+
+		assert_eq!(find("書く", NonPast, true, true).unwrap(), "書きません");
+		assert_eq!(find("書く", Past, true, true).unwrap(), "書きませんでした");
+		assert_eq!(find("書く", Te, true, false).unwrap(), "書きまして");
+		assert_eq!(
+			find("書く", Volitional, true, false).unwrap(),
+			"書きましょう"
+		);
+		assert_eq!(find("書く", Imperative, false, true).unwrap(), "書くな");
+		assert_eq!(
+			find("書く", Conditional, false, true).unwrap(),
+			"書かなければ"
+		);
+		assert_eq!(find("書く", Tara, true, false).unwrap(), "書きましたら");
+		assert_eq!(find("書く", Desire, false, true).unwrap(), "書きたくない");
+		assert_eq!(find("書く", Potential, true, true).unwrap(), "書けません");
+		assert_eq!(find("書く", Passive, false, false).unwrap(), "書かれる");
+		assert_eq!(find("書く", Causative, false, true).unwrap(), "書かせない");
+		assert_eq!(
+			find("書く", CausativePassive, true, false).unwrap(),
+			"書かせられます"
+		);
+		assert_eq!(find("書く", Adverbial, false, false), None);
+
+		assert_eq!(find("高い", Past, true, false).unwrap(), "高かったです");
+		assert_eq!(
+			find("高い", Conditional, false, true).unwrap(),
+			"高くなければ"
+		);
+		assert_eq!(find("高い", Adverbial, false, false).unwrap(), "高く");
+		assert_eq!(find("高い", Volitional, false, false), None);
+	}
+}
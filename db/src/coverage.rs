@@ -0,0 +1,278 @@
+//! Readability of terms and texts for a set of known kanji.
+
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::iter::FromIterator;
+
+use super::kana;
+use super::DB;
+
+/// Set of kanji known by a reader. See [readable_terms](DB::readable_terms)
+/// and [text_coverage](DB::text_coverage).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct KnownKanji {
+	chars: HashSet<char>,
+}
+
+impl KnownKanji {
+	pub fn new() -> KnownKanji {
+		KnownKanji::default()
+	}
+
+	/// Adds all kanji in the text. Other characters, including the iteration
+	/// marks (see [can_read](KnownKanji::can_read)), are ignored.
+	pub fn add_text(&mut self, text: &str) {
+		self.chars
+			.extend(text.chars().filter(|&x| needs_reading(x)));
+	}
+
+	pub fn insert(&mut self, chr: char) {
+		self.chars.insert(chr);
+	}
+
+	pub fn contains(&self, chr: char) -> bool {
+		self.chars.contains(&chr)
+	}
+
+	pub fn len(&self) -> usize {
+		self.chars.len()
+	}
+
+	pub fn is_empty(&self) -> bool {
+		self.chars.is_empty()
+	}
+
+	pub fn iter<'a>(&'a self) -> impl 'a + Iterator<Item = char> {
+		self.chars.iter().cloned()
+	}
+
+	/// Returns true if all kanji in the text are known. Kana and any other
+	/// characters are always readable, as are `々`, `〆` and `ヶ` (which
+	/// repeat or abbreviate other characters).
+	pub fn can_read(&self, text: &str) -> bool {
+		text.chars().all(|x| !needs_reading(x) || self.contains(x))
+	}
+}
+
+impl FromIterator<char> for KnownKanji {
+	fn from_iter<T: IntoIterator<Item = char>>(iter: T) -> KnownKanji {
+		KnownKanji {
+			chars: iter.into_iter().collect(),
+		}
+	}
+}
+
+/// Returns true if the character is a kanji that must be known to be read.
+fn needs_reading(chr: char) -> bool {
+	match chr {
+		'々' | '〆' | 'ヶ' => false,
+		_ => kana::is_kanji(chr),
+	}
+}
+
+/// Readability of a text for a set of known kanji. See
+/// [text_coverage](DB::text_coverage).
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct TextCoverage {
+	/// Number of kana and kanji characters in the text.
+	pub characters: usize,
+	/// Number of kana and known kanji characters in the text.
+	pub readable_characters: usize,
+	/// Number of dictionary words in the text (see
+	/// [extract_vocabulary](DB::extract_vocabulary)).
+	pub words: usize,
+	/// Number of dictionary words in the text using only known kanji.
+	pub readable_words: usize,
+	/// Unknown kanji in the text with their number of occurrences, most
+	/// common first.
+	pub unknown_kanji: Vec<(char, usize)>,
+}
+
+impl TextCoverage {
+	/// Percentage of the kana and kanji characters in the text that are
+	/// readable.
+	pub fn character_percentage(&self) -> f64 {
+		percentage(self.readable_characters, self.characters)
+	}
+
+	/// Percentage of the dictionary words in the text that are readable.
+	pub fn word_percentage(&self) -> f64 {
+		percentage(self.readable_words, self.words)
+	}
+}
+
+fn percentage(count: usize, total: usize) -> f64 {
+	if total > 0 {
+		(count as f64) * 100.0 / (total as f64)
+	} else {
+		100.0
+	}
+}
+
+impl<'db> DB<'db> {
+	/// Returns the kanji with the given JLPT level or an easier one.
+	///
+	/// The levels are from the kanji `jlpt` stat, which uses the old JLPT
+	/// levels from 4 (easiest) to 1 (hardest).
+	pub fn kanji_by_jlpt<'a: 'db>(&'a self, level: u32) -> KnownKanji {
+		self.known_kanji_by_stat("jlpt", |x| x >= level)
+	}
+
+	/// Returns the kanji taught up to the given school grade, using the kanji
+	/// `grade` stat (1 to 6 for elementary school, 8 for the remaining jouyou
+	/// kanji and 9 and 10 for jinmeiyou kanji).
+	pub fn kanji_by_grade<'a: 'db>(&'a self, grade: u32) -> KnownKanji {
+		self.known_kanji_by_stat("grade", |x| x <= grade)
+	}
+
+	fn known_kanji_by_stat<'a: 'db, F: Fn(u32) -> bool>(
+		&'a self,
+		name: &str,
+		filter: F,
+	) -> KnownKanji {
+		self.kanji_with_stat(name)
+			.filter(|&(_, value)| value.parse().ok().map(&filter).unwrap_or(false))
+			.map(|(kanji, _)| kanji.character())
+			.collect()
+	}
+
+	/// Returns the terms with at least one kanji in their expression that use
+	/// only known kanji, sorted by frequency.
+	///
+	/// Terms written only in kana are not included, since those are readable
+	/// regardless of the known kanji.
+	pub fn readable_terms(&self, known: &KnownKanji) -> Vec<usize> {
+		// Any matching term is in the character index of its kanji.
+		let mut candidates = BTreeSet::new();
+		for chr in known.iter() {
+			for &index in self.get_char_index(chr) {
+				let index: usize = index.into();
+				candidates.insert(index);
+			}
+		}
+
+		candidates
+			.into_iter()
+			.filter(|&index| {
+				let expression = self.get_str(self.terms[index].expression);
+				expression.chars().any(needs_reading) && known.can_read(expression)
+			})
+			.collect()
+	}
+
+	/// Computes how much of a text is readable with the known kanji, both by
+	/// characters and by dictionary words.
+	pub fn text_coverage<'a: 'db>(&'a self, text: &str, known: &KnownKanji) -> TextCoverage {
+		let mut coverage = TextCoverage::default();
+		let mut unknown = HashMap::new();
+		for chr in text.chars() {
+			if needs_reading(chr) {
+				coverage.characters += 1;
+				if known.contains(chr) {
+					coverage.readable_characters += 1;
+				} else {
+					*unknown.entry(chr).or_insert(0) += 1;
+				}
+			} else if kana::is_kana(chr) || kana::is_kanji(chr) {
+				coverage.characters += 1;
+				coverage.readable_characters += 1;
+			}
+		}
+
+		self.for_each_word(text, |offset, length, _| {
+			coverage.words += 1;
+			if known.can_read(&text[offset..offset + length]) {
+				coverage.readable_words += 1;
+			}
+		});
+
+		let mut unknown: Vec<_> = unknown.into_iter().collect();
+		unknown.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+		coverage.unknown_kanji = unknown;
+		coverage
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::testing::*;
+	use super::KnownKanji;
+
+	fn test_db() -> TestDB {
+		let mut w = writer(&[]);
+		let terms = [
+			("日本", "にほん", 900),
+			("本", "ほん", 800),
+			("人々", "ひとびと", 700),
+			("日曜日", "にちようび", 600),
+			("ひらがな", "ひらがな", 500),
+			("東京", "とうきょう", 400),
+			("行く", "いく", 300),
+			("本当", "ほんとう", 200),
+		];
+		for &(expression, reading, frequency) in terms.iter() {
+			let item = term(&mut w, expression, reading, "", frequency);
+			w.push_term(item);
+		}
+		TestDB::new(w)
+	}
+
+	#[test]
+	fn known_kanji() {
+		let mut known = KnownKanji::new();
+		known.add_text("日本の人々、ヶ〆abc");
+		let mut chars: Vec<_> = known.iter().collect();
+		chars.sort();
+		assert_eq!(chars, ['人', '日', '本']);
+
+		assert!(known.can_read("日本"));
+		assert!(known.can_read("人々"));
+		assert!(known.can_read("ひらがな abc"));
+		assert!(known.can_read(""));
+		assert!(!known.can_read("ヶ月"));
+		known.insert('月');
+		assert!(known.can_read("ヶ月"));
+		assert!(!known.can_read("日曜日"));
+	}
+
+	#[test]
+	fn readable_terms() {
+		let data = test_db();
+		let db = data.db();
+		let expressions = |known: &KnownKanji| {
+			db.readable_terms(known)
+				.into_iter()
+				.map(|x| db.term(x).unwrap().expression())
+				.collect::<Vec<_>>()
+		};
+
+		let known: KnownKanji = "日本人".chars().collect();
+		assert_eq!(expressions(&known), ["日本", "本", "人々"]);
+
+		let known: KnownKanji = "行当本".chars().collect();
+		assert_eq!(expressions(&known), ["本", "行く", "本当"]);
+
+		assert!(expressions(&KnownKanji::new()).is_empty());
+	}
+
+	#[test]
+	fn text_coverage() {
+		let data = test_db();
+		let db = data.db();
+
+		let known: KnownKanji = "日本人".chars().collect();
+		let coverage = db.text_coverage("日本の人々は東京に行く。東 abc", &known);
+		assert_eq!(coverage.characters, 12);
+		assert_eq!(coverage.readable_characters, 8);
+		assert_eq!(coverage.words, 4);
+		assert_eq!(coverage.readable_words, 2);
+		assert_eq!(coverage.unknown_kanji, [('東', 2), ('京', 1), ('行', 1)]);
+		assert!((coverage.character_percentage() - 800.0 / 12.0).abs() < 1e-9);
+		assert_eq!(coverage.word_percentage(), 50.0);
+
+		let coverage = db.text_coverage("abc", &known);
+		assert_eq!(coverage.characters, 0);
+		assert_eq!(coverage.words, 0);
+		assert_eq!(coverage.character_percentage(), 100.0);
+		assert_eq!(coverage.word_percentage(), 100.0);
+	}
+}
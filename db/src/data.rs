@@ -1,5 +1,6 @@
 use std::fmt;
 
+use super::KanjiRaw;
 use super::TagRaw;
 use super::TermRaw;
 use super::DB;
@@ -58,6 +59,12 @@ pub struct Term<'db, 'a: 'db> {
 }
 
 impl<'db, 'a: 'db> Term<'db, 'a> {
+	/// Index of the term in the database. Note that the index changes every
+	/// time the database is built.
+	pub fn index(&self) -> usize {
+		self.pos
+	}
+
 	/// Main Japanese expression for the term.
 	pub fn expression(&self) -> &'db str {
 		self.data.get_str(self.item.expression)
@@ -103,10 +110,7 @@ impl<'db, 'a: 'db> Term<'db, 'a> {
 
 	/// English definitions for the term.
 	pub fn glossary(&'a self) -> impl 'a + Iterator<Item = &'db str> {
-		let (sta, end) = self.item.glossary.range();
-		self.data.vector_data[sta..end]
-			.iter()
-			.map(move |&index| self.data.get_str(index))
+		self.data.get_strs(self.item.glossary)
 	}
 
 	/// Semantic rules for the term (tag indexes).
@@ -201,3 +205,108 @@ impl<'db, 'a: 'db> fmt::Display for Term<'db, 'a> {
 		Ok(())
 	}
 }
+
+/// Kanji from the database.
+pub struct Kanji<'db, 'a: 'db> {
+	pub(super) data: &'a DB<'db>,
+	pub(super) item: &'a KanjiRaw,
+}
+
+impl<'db, 'a: 'db> Kanji<'db, 'a> {
+	/// Kanji character.
+	pub fn character(&self) -> char {
+		let character: u32 = self.item.character.into();
+		std::char::from_u32(character).unwrap()
+	}
+
+	/// Number of occurrences for the kanji in the frequency database.
+	pub fn frequency(&self) -> Option<u32> {
+		let frequency: u32 = self.item.frequency.into();
+		if frequency > 0 {
+			Some(frequency)
+		} else {
+			None
+		}
+	}
+
+	/// Source dictionary name.
+	pub fn source(&self) -> &'db str {
+		self.data.get_str(self.item.source)
+	}
+
+	/// English meanings for the kanji.
+	pub fn meanings(&'a self) -> impl 'a + Iterator<Item = &'db str> {
+		self.data.get_strs(self.item.meanings)
+	}
+
+	/// Onyomi (chinese) readings for the kanji.
+	pub fn onyomi(&'a self) -> impl 'a + Iterator<Item = &'db str> {
+		self.data.get_strs(self.item.onyomi)
+	}
+
+	/// Kunyomi (japanese) readings for the kanji.
+	pub fn kunyomi(&'a self) -> impl 'a + Iterator<Item = &'db str> {
+		self.data.get_strs(self.item.kunyomi)
+	}
+
+	/// Tags for the kanji.
+	pub fn tags(&'a self) -> impl 'a + Iterator<Item = Tag<'db, 'a>> {
+		self.data.get_tags(self.item.tags)
+	}
+
+	/// Additional information for the kanji (e.g. stroke count and grade) as
+	/// pairs of a tag describing the stat and its value.
+	pub fn stats(&'a self) -> impl 'a + Iterator<Item = (Tag<'db, 'a>, &'db str)> {
+		self.data.get_stats(self.item.stats)
+	}
+
+	/// Returns the value of the kanji stat with the given tag name (e.g.
+	/// `grade` or `jlpt`), if available.
+	pub fn stat(&'a self, name: &str) -> Option<&'db str> {
+		self.stats()
+			.find(|(tag, _)| tag.name() == name)
+			.map(|(_, value)| value)
+	}
+}
+
+impl<'db, 'a: 'db> fmt::Display for Kanji<'db, 'a> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}", self.character())?;
+
+		let onyomi: Vec<_> = self.onyomi().collect();
+		let kunyomi: Vec<_> = self.kunyomi().collect();
+		if !onyomi.is_empty() || !kunyomi.is_empty() {
+			write!(f, " [{}", onyomi.join(", "))?;
+			if !onyomi.is_empty() && !kunyomi.is_empty() {
+				write!(f, " / ")?;
+			}
+			write!(f, "{}]", kunyomi.join(", "))?;
+		}
+
+		if let Some(frequency) = self.frequency() {
+			write!(f, " #{}", frequency)?;
+		}
+
+		write!(f, " -- source: {}", self.source())?;
+		writeln!(f)?;
+
+		for (i, it) in self.meanings().enumerate() {
+			if i > 0 {
+				write!(f, ", ")?;
+			} else {
+				write!(f, "\n    ")?;
+			}
+			write!(f, "{}", it)?;
+		}
+
+		let tags: Vec<_> = self.tags().collect();
+		if !tags.is_empty() {
+			write!(f, "\n\n    Tags:")?;
+			for tag in tags {
+				write!(f, "\n    -> {}", tag)?;
+			}
+		}
+
+		Ok(())
+	}
+}
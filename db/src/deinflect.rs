@@ -0,0 +1,293 @@
+//! Deinflection of conjugated verbs and adjectives.
+//!
+//! The rules are applied repeatedly by replacing the inflected ending with
+//! the dictionary form ending (e.g. `食べなかった` becomes `食べない` and then
+//! `食べる`). Each rule also constrains the word class of the form it applies
+//! to and of the result, which is checked against the term rules from the
+//! dictionary (e.g. `v1` or `adj-i`).
+
+use super::Term;
+
+/// Ichidan verbs (`v1`).
+const V1: u32 = 1 << 0;
+/// Godan verbs (`v5`).
+const V5: u32 = 1 << 1;
+/// Suru verbs (`vs`).
+const VS: u32 = 1 << 2;
+/// Kuru verb (`vk`).
+const VK: u32 = 1 << 3;
+/// I-adjectives (`adj-i`).
+const ADJ_I: u32 = 1 << 4;
+/// Intermediate: te-form followed by `いる`.
+const IRU: u32 = 1 << 5;
+/// Intermediate: polite `ます` form.
+const MASU: u32 = 1 << 6;
+/// Intermediate: past form, also used by `たら` and `たり`.
+const TA: u32 = 1 << 7;
+
+/// Godan verb endings: dictionary form, i-stem, a-stem, e-stem, o-stem,
+/// te-form and past form.
+pub(super) const GODAN: &[(&str, &str, &str, &str, &str, &str, &str)] = &[
+	("う", "い", "わ", "え", "お", "って", "った"),
+	("く", "き", "か", "け", "こ", "いて", "いた"),
+	("ぐ", "ぎ", "が", "げ", "ご", "いで", "いだ"),
+	("す", "し", "さ", "せ", "そ", "して", "した"),
+	("つ", "ち", "た", "て", "と", "って", "った"),
+	("ぬ", "に", "な", "ね", "の", "んで", "んだ"),
+	("ぶ", "び", "ば", "べ", "ぼ", "んで", "んだ"),
+	("む", "み", "ま", "め", "も", "んで", "んだ"),
+	("る", "り", "ら", "れ", "ろ", "って", "った"),
+];
+
+/// Deinflected form of a text. See [deinflect](Deinflector::deinflect).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Deinflection {
+	/// Text in the dictionary form.
+	pub text: String,
+	/// Inflections removed from the original text, outermost first (e.g.
+	/// `past` and `negative` for `食べなかった`). This is empty for the
+	/// original text.
+	pub reasons: Vec<&'static str>,
+	rules: u32,
+}
+
+impl Deinflection {
+	/// Returns true if the deinflected text can be the dictionary form of
+	/// the given term. This checks the term rules against the word class
+	/// required by the deinflection.
+	///
+	/// The original text (with no inflections removed) matches any term.
+	pub fn matches(&self, term: &Term) -> bool {
+		if self.rules == 0 {
+			return true;
+		}
+		term.rules()
+			.any(|tag| self.rules & rule_flags(tag.name()) != 0)
+	}
+}
+
+/// Maps a term rule name to the rule flags.
+fn rule_flags(name: &str) -> u32 {
+	match name {
+		"v1" => V1,
+		"v5" => V5,
+		"vs" => VS,
+		"vk" => VK,
+		"adj-i" => ADJ_I,
+		_ => 0,
+	}
+}
+
+struct Rule {
+	reason: &'static str,
+	from: String,
+	to: String,
+	rules_in: u32,
+	rules_out: u32,
+}
+
+/// Deinflects Japanese verbs and adjectives.
+pub struct Deinflector {
+	rules: Vec<Rule>,
+}
+
+impl Deinflector {
+	pub fn new() -> Deinflector {
+		let mut rules = Vec::new();
+		{
+			let mut add = |reason, from: &str, to: &str, rules_in, rules_out| {
+				rules.push(Rule {
+					reason,
+					from: from.to_string(),
+					to: to.to_string(),
+					rules_in,
+					rules_out,
+				})
+			};
+
+			// Ichidan verbs
+			add("negative", "ない", "る", ADJ_I, V1);
+			add("past", "た", "る", TA, V1);
+			add("-te", "て", "る", IRU, V1);
+			add("polite", "ます", "る", MASU, V1);
+			add("volitional", "よう", "る", 0, V1);
+			add("conditional", "れば", "る", 0, V1);
+			add("imperative", "ろ", "る", 0, V1);
+			add("-tai", "たい", "る", ADJ_I, V1);
+			add("potential or passive", "られる", "る", V1, V1);
+			add("causative", "させる", "る", V1, V1);
+
+			// Godan verbs
+			for &(u, i, a, e, o, te, ta) in GODAN {
+				add("negative", &format!("{}ない", a), u, ADJ_I, V5);
+				add("past", ta, u, TA, V5);
+				add("-te", te, u, IRU, V5);
+				add("polite", &format!("{}ます", i), u, MASU, V5);
+				add("volitional", &format!("{}う", o), u, 0, V5);
+				add("conditional", &format!("{}ば", e), u, 0, V5);
+				add("imperative", e, u, 0, V5);
+				add("-tai", &format!("{}たい", i), u, ADJ_I, V5);
+				add("potential", &format!("{}る", e), u, V1, V5);
+				add("passive", &format!("{}れる", a), u, V1, V5);
+				add("causative", &format!("{}せる", a), u, V1, V5);
+			}
+			add("past", "いった", "いく", TA, V5);
+			add("past", "行った", "行く", TA, V5);
+			add("-te", "いって", "いく", IRU, V5);
+			add("-te", "行って", "行く", IRU, V5);
+
+			// Suru verbs. Nouns taking `する` have the `vs` rule, so the
+			// `する` is also removed.
+			add("negative", "しない", "する", ADJ_I, VS);
+			add("past", "した", "する", TA, VS);
+			add("-te", "して", "する", IRU, VS);
+			add("polite", "します", "する", MASU, VS);
+			add("volitional", "しよう", "する", 0, VS);
+			add("conditional", "すれば", "する", 0, VS);
+			add("imperative", "しろ", "する", 0, VS);
+			add("-tai", "したい", "する", ADJ_I, VS);
+			add("passive", "される", "する", V1, VS);
+			add("causative", "させる", "する", V1, VS);
+			add("suru", "する", "", VS, VS);
+
+			// Kuru verb
+			for &(ku, ko, ki) in &[("くる", "こ", "き"), ("来る", "来", "来")] {
+				add("negative", &format!("{}ない", ko), ku, ADJ_I, VK);
+				add("past", &format!("{}た", ki), ku, TA, VK);
+				add("-te", &format!("{}て", ki), ku, IRU, VK);
+				add("polite", &format!("{}ます", ki), ku, MASU, VK);
+				add("volitional", &format!("{}よう", ko), ku, 0, VK);
+				add(
+					"conditional",
+					&format!("{}れば", &ku[..ku.len() - 3]),
+					ku,
+					0,
+					VK,
+				);
+				add("imperative", &format!("{}い", ko), ku, 0, VK);
+				add("-tai", &format!("{}たい", ki), ku, ADJ_I, VK);
+				add("potential or passive", &format!("{}られる", ko), ku, V1, VK);
+				add("causative", &format!("{}させる", ko), ku, V1, VK);
+			}
+
+			// I-adjectives
+			add("negative", "くない", "い", ADJ_I, ADJ_I);
+			add("past", "かった", "い", TA, ADJ_I);
+			add("-te", "くて", "い", 0, ADJ_I);
+			add("adverb", "く", "い", 0, ADJ_I);
+			add("noun", "さ", "い", 0, ADJ_I);
+			add("conditional", "ければ", "い", 0, ADJ_I);
+
+			// Intermediate forms
+			add("progressive or perfect", "いる", "", V1, IRU);
+			add("progressive or perfect", "てる", "て", V1, IRU);
+			add("progressive or perfect", "でる", "で", V1, IRU);
+			add("polite past", "ました", "ます", 0, MASU);
+			add("polite negative", "ません", "ます", 0, MASU);
+			add("polite volitional", "ましょう", "ます", 0, MASU);
+			add("polite -te", "まして", "ます", 0, MASU);
+			add("-tara", "たら", "た", 0, TA);
+			add("-tara", "だら", "だ", 0, TA);
+			add("-tari", "たり", "た", 0, TA);
+			add("-tari", "だり", "だ", 0, TA);
+		}
+		Deinflector { rules }
+	}
+
+	/// Returns all possible dictionary forms for the text, starting with the
+	/// text itself. Forms with fewer inflections come first.
+	///
+	/// Most of the returned forms are not valid words, so each must be looked
+	/// up in the dictionary and checked with [Deinflection::matches].
+	pub fn deinflect(&self, text: &str) -> Vec<Deinflection> {
+		let mut out = vec![Deinflection {
+			text: text.to_string(),
+			reasons: Vec::new(),
+			rules: 0,
+		}];
+
+		// Every rule either shortens the text or requires a form produced by
+		// a rule that does, so this always terminates.
+		let mut next = 0;
+		while next < out.len() {
+			for rule in self.rules.iter() {
+				let current = &out[next];
+				if current.rules != 0 && current.rules & rule.rules_in == 0 {
+					continue;
+				}
+				if !current.text.ends_with(rule.from.as_str()) {
+					continue;
+				}
+
+				let stem = &current.text[..current.text.len() - rule.from.len()];
+				if stem.is_empty() && rule.to.is_empty() {
+					continue;
+				}
+				let mut reasons = current.reasons.clone();
+				reasons.push(rule.reason);
+				let item = Deinflection {
+					text: format!("{}{}", stem, rule.to),
+					reasons,
+					rules: rule.rules_out,
+				};
+				if !out
+					.iter()
+					.any(|x| x.text == item.text && x.rules == item.rules)
+				{
+					out.push(item);
+				}
+			}
+			next += 1;
+		}
+
+		out
+	}
+}
+
+impl Default for Deinflector {
+	fn default() -> Deinflector {
+		Deinflector::new()
+	}
+}
+
+/// Returns all possible dictionary forms for the text. See
+/// [Deinflector::deinflect].
+///
+/// This builds the rule table on every call, so use a [Deinflector] when
+/// deinflecting many words.
+pub fn deinflect(text: &str) -> Vec<Deinflection> {
+	Deinflector::new().deinflect(text)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::{deinflect, ADJ_I, V1, V5, VK, VS};
+
+	#[test]
+	fn dictionary_forms() {
+		let cases: &[(&str, &str, &[&str], u32)] = &[
+			("食べなかった", "食べる", &["past", "negative"], V1),
+			("行った", "行く", &["past"], V5),
+			("勉強しました", "勉強する", &["polite past", "polite"], VS),
+			("来られる", "来る", &["potential or passive"], VK),
+			("高くない", "高い", &["negative"], ADJ_I),
+		];
+		for &(text, expected, reasons, rules) in cases {
+			let forms = deinflect(text);
+			assert_eq!(forms[0].text, text);
+			assert!(forms[0].reasons.is_empty());
+			let found = forms
+				.iter()
+				.find(|x| x.text == expected && x.rules == rules)
+				.unwrap_or_else(|| panic!("{} not deinflected to {}", text, expected));
+			assert_eq!(found.reasons, reasons, "{}", text);
+		}
+	}
+
+	#[test]
+	fn no_inflection() {
+		let forms = deinflect("東京");
+		assert_eq!(forms.len(), 1);
+		assert_eq!(forms[0].text, "東京");
+	}
+}
@@ -0,0 +1,448 @@
+//! Furigana alignment of term expressions and readings.
+
+use std::collections::HashMap;
+
+use super::kana;
+use super::Term;
+
+/// Maximum number of candidate alignments considered for a single term.
+const MAX_ALIGNMENTS: usize = 32;
+
+/// Furigana for a term, as a list of `(base, ruby)` segments. See
+/// [Term::furigana].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Furigana {
+	pub segments: Vec<FuriganaSegment>,
+}
+
+/// Single segment in a [Furigana].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FuriganaSegment {
+	/// Base text from the expression.
+	pub base: String,
+	/// Reading for the base text. Empty for segments that need no reading
+	/// (e.g. kana).
+	pub ruby: String,
+}
+
+impl Furigana {
+	/// Formats the furigana as HTML using `<ruby>` tags (e.g.
+	/// `<ruby>食<rt>た</rt></ruby>べ<ruby>物<rt>もの</rt></ruby>`).
+	pub fn to_html(&self) -> String {
+		let mut out = String::new();
+		for it in self.segments.iter() {
+			if !it.ruby.is_empty() {
+				out.push_str("<ruby>");
+				out.push_str(&escape_html(&it.base));
+				out.push_str("<rt>");
+				out.push_str(&escape_html(&it.ruby));
+				out.push_str("</rt></ruby>");
+			} else {
+				out.push_str(&escape_html(&it.base));
+			}
+		}
+		out
+	}
+
+	/// Formats the furigana using the Anki bracket format (e.g.
+	/// `食[た]べ 物[もの]`).
+	///
+	/// Anki uses the text between a space and the bracket as base text, so a
+	/// space is added before each segment with a reading.
+	pub fn to_anki(&self) -> String {
+		let mut out = String::new();
+		for it in self.segments.iter() {
+			if !it.ruby.is_empty() {
+				if !out.is_empty() {
+					out.push(' ');
+				}
+				out.push_str(&it.base);
+				out.push('[');
+				out.push_str(&it.ruby);
+				out.push(']');
+			} else {
+				out.push_str(&it.base);
+			}
+		}
+		out
+	}
+}
+
+fn escape_html(text: &str) -> String {
+	text.replace('&', "&amp;")
+		.replace('<', "&lt;")
+		.replace('>', "&gt;")
+}
+
+impl<'db, 'a: 'db> Term<'db, 'a> {
+	/// Aligns the term expression and reading into furigana segments (e.g.
+	/// `食べ物` is split into `食[た]`, `べ` and `物[もの]`).
+	///
+	/// Kana in the expression is matched to the reading and the remaining
+	/// parts of the reading are assigned to the kanji between them. When that
+	/// is ambiguous, or for runs of multiple kanji, the kanji readings from
+	/// the database are used to choose an alignment.
+	///
+	/// If the expression and reading cannot be aligned, the result is a single
+	/// segment with the whole expression and reading.
+	pub fn furigana(&self) -> Furigana {
+		let expression = self.expression();
+		let reading = self.reading();
+
+		let has_kanji = expression.chars().any(|x| !kana::is_kana(x));
+		if reading.is_empty() || reading == expression || !has_kanji {
+			return Furigana {
+				segments: vec![FuriganaSegment {
+					base: expression.to_string(),
+					ruby: String::new(),
+				}],
+			};
+		}
+
+		let mut aligner = Aligner {
+			term: self,
+			runs: split_runs(expression),
+			reading: reading.chars().collect(),
+			reading_hiragana: kana::to_hiragana(reading).chars().collect(),
+			readings: HashMap::new(),
+		};
+
+		if let Some(segments) = aligner.align() {
+			Furigana { segments }
+		} else {
+			Furigana {
+				segments: vec![FuriganaSegment {
+					base: expression.to_string(),
+					ruby: reading.to_string(),
+				}],
+			}
+		}
+	}
+}
+
+/// Run of either kana or non-kana characters in an expression.
+struct Run {
+	is_kana: bool,
+	chars: Vec<char>,
+}
+
+fn split_runs(expression: &str) -> Vec<Run> {
+	let mut runs: Vec<Run> = Vec::new();
+	for chr in expression.chars() {
+		let is_kana = kana::is_kana(chr);
+		match runs.last_mut() {
+			Some(ref mut run) if run.is_kana == is_kana => {
+				run.chars.push(chr);
+			}
+			_ => runs.push(Run {
+				is_kana,
+				chars: vec![chr],
+			}),
+		}
+	}
+	runs
+}
+
+struct Aligner<'t, 'db: 't, 'a: 'db> {
+	term: &'t Term<'db, 'a>,
+	runs: Vec<Run>,
+	reading: Vec<char>,
+	reading_hiragana: Vec<char>,
+	/// Cache of the possible readings for each kanji.
+	readings: HashMap<char, Vec<Vec<char>>>,
+}
+
+impl<'t, 'db: 't, 'a: 'db> Aligner<'t, 'db, 'a> {
+	fn align(&mut self) -> Option<Vec<FuriganaSegment>> {
+		// Find all possible alignments where the kana runs match the reading.
+		// Each alignment is a list of `(start, end)` reading ranges per run.
+		let mut alignments = Vec::new();
+		self.find_alignments(0, 0, &mut Vec::new(), &mut alignments);
+
+		// Choose the alignment with the most kanji matching a known reading.
+		let mut best: Option<(usize, Vec<FuriganaSegment>)> = None;
+		for alignment in alignments {
+			let mut score = 0;
+			let mut segments = Vec::new();
+			for (run, &(sta, end)) in alignment.iter().enumerate() {
+				let run_chars = self.runs[run].chars.clone();
+				let base: String = run_chars.iter().collect();
+				if self.runs[run].is_kana {
+					segments.push(FuriganaSegment {
+						base,
+						ruby: String::new(),
+					});
+					continue;
+				}
+
+				if let Some(split) = self.split_run(&run_chars, None, sta, end) {
+					score += run_chars.len();
+					let mut pos = sta;
+					for (chr, end) in run_chars.into_iter().zip(split) {
+						segments.push(FuriganaSegment {
+							base: chr.to_string(),
+							ruby: self.reading[pos..end].iter().collect(),
+						});
+						pos = end;
+					}
+				} else {
+					segments.push(FuriganaSegment {
+						base,
+						ruby: self.reading[sta..end].iter().collect(),
+					});
+				}
+			}
+
+			if best.as_ref().map(|x| score > x.0).unwrap_or(true) {
+				best = Some((score, segments));
+			}
+		}
+
+		best.map(|x| x.1)
+	}
+
+	fn find_alignments(
+		&self,
+		run: usize,
+		pos: usize,
+		current: &mut Vec<(usize, usize)>,
+		out: &mut Vec<Vec<(usize, usize)>>,
+	) {
+		if out.len() >= MAX_ALIGNMENTS {
+			return;
+		}
+
+		let reading = &self.reading_hiragana;
+		if run == self.runs.len() {
+			if pos == reading.len() {
+				out.push(current.clone());
+			}
+			return;
+		}
+
+		let chars = &self.runs[run].chars;
+		if self.runs[run].is_kana {
+			let end = pos + chars.len();
+			let matches = end <= reading.len()
+				&& chars
+					.iter()
+					.zip(reading[pos..end].iter())
+					.all(|(&a, &b)| kana::to_hiragana_char(a) == b);
+			if matches {
+				current.push((pos, end));
+				self.find_alignments(run + 1, end, current, out);
+				current.pop();
+			}
+		} else {
+			// Each remaining kanji run needs at least one character from the
+			// reading and each kana run needs its own length.
+			let needed: usize = self.runs[run + 1..]
+				.iter()
+				.map(|x| if x.is_kana { x.chars.len() } else { 1 })
+				.sum();
+			let mut end = pos + 1;
+			while end + needed <= reading.len() {
+				current.push((pos, end));
+				self.find_alignments(run + 1, end, current, out);
+				current.pop();
+				end += 1;
+			}
+		}
+	}
+
+	/// Splits the reading range `sta..end` among the kanji in a run using the
+	/// known kanji readings. Returns the end position of each kanji reading.
+	///
+	/// The `prev` argument is the previous kanji in the run, used to resolve
+	/// the `々` iteration mark.
+	fn split_run(
+		&mut self,
+		chars: &[char],
+		prev: Option<char>,
+		sta: usize,
+		end: usize,
+	) -> Option<Vec<usize>> {
+		if chars.is_empty() {
+			return if sta == end { Some(Vec::new()) } else { None };
+		}
+
+		let chr = match (chars[0], prev) {
+			('々', Some(prev)) => prev,
+			(chr, _) => chr,
+		};
+
+		for candidate in self.kanji_readings(chr) {
+			let next = sta + candidate.len();
+			if next <= end && self.reading_hiragana[sta..next] == candidate[..] {
+				if let Some(mut rest) = self.split_run(&chars[1..], Some(chr), next, end) {
+					rest.insert(0, next);
+					return Some(rest);
+				}
+			}
+		}
+
+		None
+	}
+
+	fn kanji_readings(&mut self, chr: char) -> Vec<Vec<char>> {
+		let term = self.term;
+		self.readings
+			.entry(chr)
+			.or_insert_with(|| {
+				let readings: Vec<String> = match term.data.kanji(chr) {
+					Some(kanji) => kanji
+						.onyomi()
+						.chain(kanji.kunyomi())
+						.map(kana::kanji_reading)
+						.collect(),
+					// `ヶ` is not in the kanji dictionary, but is read as `か`
+					// (e.g. `一ヶ月`) or with rendaku as `が` (e.g. `関ヶ原`).
+					None if chr == 'ヶ' => vec!["か".to_string()],
+					None => Vec::new(),
+				};
+
+				let mut out = Vec::new();
+				for reading in readings {
+					for it in kana::reading_variants(&reading) {
+						let it: Vec<char> = it.chars().collect();
+						if !it.is_empty() && !out.contains(&it) {
+							out.push(it);
+						}
+					}
+				}
+				out
+			})
+			.clone()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashMap;
+
+	use super::super::testing::*;
+	use super::{kana, split_runs, Aligner, MAX_ALIGNMENTS};
+
+	#[test]
+	fn furigana() {
+		let mut w = writer(&[]);
+		let terms = [
+			("食べ物", "たべもの"),
+			("人々", "ひとびと"),
+			("学校", "がっこう"),
+			("一ヶ月", "いっかげつ"),
+			("関ヶ原", "せきがはら"),
+			("缶ビール", "かんビール"),
+			("ひらがな", "ひらがな"),
+			("日本", ""),
+			("食べる", "のむ"),
+		];
+		for &(expression, reading) in terms.iter() {
+			let term = term(&mut w, expression, reading, "", 0);
+			w.push_term(term);
+		}
+		let characters = [
+			('食', &["ショク"][..], &["た.べる", "く.う"][..]),
+			('物', &["ブツ", "モツ"][..], &["もの"][..]),
+			('人', &["ジン", "ニン"][..], &["ひと"][..]),
+			('学', &["ガク"][..], &["まな.ぶ"][..]),
+			('校', &["コウ"][..], &[][..]),
+			('一', &["イチ", "イツ"][..], &["ひと"][..]),
+			('月', &["ゲツ", "ガツ"][..], &["つき"][..]),
+			('関', &["カン"][..], &["せき"][..]),
+			('原', &["ゲン"][..], &["はら"][..]),
+			('缶', &["カン"][..], &[][..]),
+		];
+		for &(character, onyomi, kunyomi) in characters.iter() {
+			let kanji = kanji(&mut w, character, onyomi, kunyomi);
+			w.push_kanji(kanji);
+		}
+		let db = TestDB::new(w);
+		let db = db.db();
+
+		let furigana = |expression: &str| {
+			let mut set = super::super::ResultSet::default();
+			db.search_term(expression, &mut set);
+			let term = db.term(set.iter().next().unwrap()).unwrap();
+			let furigana = term.furigana();
+			furigana
+				.segments
+				.into_iter()
+				.map(|x| format!("{}[{}]", x.base, x.ruby))
+				.collect::<Vec<_>>()
+				.join(" ")
+		};
+
+		assert_eq!(furigana("食べ物"), "食[た] べ[] 物[もの]");
+
+		// Rendaku with the `々` iteration mark and gemination.
+		assert_eq!(furigana("人々"), "人[ひと] 々[びと]");
+		assert_eq!(furigana("学校"), "学[がっ] 校[こう]");
+
+		// `ヶ` is aligned as part of the kanji run.
+		assert_eq!(furigana("一ヶ月"), "一[いっ] ヶ[か] 月[げつ]");
+		assert_eq!(furigana("関ヶ原"), "関[せき] ヶ[が] 原[はら]");
+
+		// Kana in the expression is matched regardless of the script.
+		assert_eq!(furigana("缶ビール"), "缶[かん] ビール[]");
+
+		// No reading needed, or no alignment possible.
+		assert_eq!(furigana("ひらがな"), "ひらがな[]");
+		assert_eq!(furigana("日本"), "日本[]");
+		assert_eq!(furigana("食べる"), "食べる[のむ]");
+	}
+
+	#[test]
+	fn format() {
+		let mut w = writer(&[]);
+		let term = term(&mut w, "食べ物", "たべもの", "", 0);
+		w.push_term(term);
+		let kanji = kanji(&mut w, '食', &[], &["た.べる"]);
+		w.push_kanji(kanji);
+		let db = TestDB::new(w);
+		let db = db.db();
+
+		let furigana = db.term(0).unwrap().furigana();
+		assert_eq!(
+			furigana.to_html(),
+			"<ruby>食<rt>た</rt></ruby>べ<ruby>物<rt>もの</rt></ruby>"
+		);
+		assert_eq!(furigana.to_anki(), "食[た]べ 物[もの]");
+	}
+
+	#[test]
+	fn max_alignments() {
+		// Every split of the reading between the kanji runs is a candidate
+		// alignment, so the search must stop at `MAX_ALIGNMENTS`.
+		let expression = "字の字の字の字の字";
+		let reading = "の".repeat(20);
+		let mut w = writer(&[]);
+		let term = term(&mut w, expression, &reading, "", 0);
+		w.push_term(term);
+		let db = TestDB::new(w);
+		let db = db.db();
+		let term = db.term(0).unwrap();
+
+		let aligner = Aligner {
+			term: &term,
+			runs: split_runs(expression),
+			reading: reading.chars().collect(),
+			reading_hiragana: kana::to_hiragana(&reading).chars().collect(),
+			readings: HashMap::new(),
+		};
+		let mut alignments = Vec::new();
+		aligner.find_alignments(0, 0, &mut Vec::new(), &mut alignments);
+		assert_eq!(alignments.len(), MAX_ALIGNMENTS);
+
+		// The result is still a full alignment of the reading.
+		let furigana = term.furigana();
+		assert_eq!(furigana.segments.len(), 9);
+		let ruby: String = furigana
+			.segments
+			.iter()
+			.map(|x| if x.ruby.is_empty() { &x.base } else { &x.ruby })
+			.map(|x| x.as_str())
+			.collect();
+		assert_eq!(ruby, reading);
+	}
+}
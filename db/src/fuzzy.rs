@@ -0,0 +1,160 @@
+//! Typo-tolerant search over the romaji search keys.
+
+use super::TrieNode;
+use super::DB;
+
+/// Single result from a [search_fuzzy](DB::search_fuzzy).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct FuzzyMatch {
+	/// Term index.
+	pub term: usize,
+	/// Edit distance between the term search key and the searched key.
+	pub distance: usize,
+}
+
+impl<'a> DB<'a> {
+	/// Search for terms with a romaji search key within `max_distance` edits
+	/// of the given `key` (e.g. `tokyo` will match `toukyou`).
+	///
+	/// The distance used is the Damerau-Levenshtein distance (restricted to
+	/// adjacent transpositions), so insertions, deletions, substitutions and
+	/// swapping two adjacent characters all count as a single edit.
+	///
+	/// Results are sorted by distance and then by term relevance.
+	pub fn search_fuzzy<S: AsRef<str>>(&self, key: S, max_distance: usize) -> Vec<FuzzyMatch> {
+		let key: Vec<char> = key.as_ref().to_lowercase().chars().collect();
+		let mut out = Vec::new();
+		if key.is_empty() || self.index_key_trie.is_empty() {
+			return out;
+		}
+
+		// The first row of the distance matrix corresponds to the empty
+		// prefix at the trie root.
+		let root_row: Vec<usize> = (0..=key.len()).collect();
+		let search = FuzzySearch {
+			db: self,
+			key,
+			max_distance,
+		};
+
+		let (sta, end) = self.index_key_trie[0].children();
+		for node in sta..end {
+			search.visit(node, None, &root_row, &mut out);
+		}
+
+		out.sort_by(|a, b| a.distance.cmp(&b.distance).then(a.term.cmp(&b.term)));
+		out
+	}
+}
+
+struct FuzzySearch<'a, 'b: 'a> {
+	db: &'a DB<'b>,
+	key: Vec<char>,
+	max_distance: usize,
+}
+
+impl<'a, 'b: 'a> FuzzySearch<'a, 'b> {
+	/// Visits a trie node computing the next row of the edit distance matrix
+	/// from the parent rows.
+	///
+	/// The `parent` argument is the parent character and the row before
+	/// `prev_row`, used for transpositions.
+	fn visit(
+		&self,
+		index: usize,
+		parent: Option<(char, &[usize])>,
+		prev_row: &[usize],
+		out: &mut Vec<FuzzyMatch>,
+	) {
+		let node: &TrieNode = &self.db.index_key_trie[index];
+		let chr: u32 = node.character.into();
+		let chr = std::char::from_u32(chr).unwrap_or('\0');
+
+		let key = &self.key;
+		let mut row = Vec::with_capacity(prev_row.len());
+		row.push(prev_row[0] + 1);
+		for j in 1..=key.len() {
+			let cost = if key[j - 1] == chr { 0 } else { 1 };
+			let mut value = std::cmp::min(prev_row[j] + 1, row[j - 1] + 1);
+			value = std::cmp::min(value, prev_row[j - 1] + cost);
+			if let Some((parent_chr, parent_row)) = parent {
+				if j > 1 && chr == key[j - 2] && parent_chr == key[j - 1] {
+					value = std::cmp::min(value, parent_row[j - 2] + 1);
+				}
+			}
+			row.push(value);
+		}
+
+		let distance = row[key.len()];
+		if distance <= self.max_distance {
+			let (sta, end) = node.terms.range();
+			for &term in self.db.vector_data[sta..end].iter() {
+				out.push(FuzzyMatch {
+					term: term.into(),
+					distance,
+				});
+			}
+		}
+
+		// If every cell in the row is already over the maximum distance then
+		// no key under this node can match.
+		if row.iter().all(|&it| it > self.max_distance) {
+			return;
+		}
+
+		let (sta, end) = node.children();
+		for child in sta..end {
+			self.visit(child, Some((chr, prev_row)), &row, out);
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::testing::*;
+	use super::FuzzyMatch;
+
+	#[test]
+	fn search_fuzzy() {
+		let mut w = writer(&[]);
+		let terms = [
+			("東京", "とうきょう", "toukyou", 100),
+			("京都", "きょうと", "kyouto", 90),
+			("友達", "ともだち", "tomodachi", 80),
+			("特許", "とっきょ", "tokkyo", 50),
+			("箸", "はし", "hashi", 30),
+			("橋", "はし", "hashi", 10),
+		];
+		for &(expression, reading, key, frequency) in terms.iter() {
+			let term = term(&mut w, expression, reading, key, frequency);
+			w.push_term(term);
+		}
+		let db = TestDB::new(w);
+		let db = db.db();
+
+		let search = |key: &str, max_distance: usize| {
+			db.search_fuzzy(key, max_distance)
+				.into_iter()
+				.map(|FuzzyMatch { term, distance }| {
+					(db.term(term).unwrap().expression(), distance)
+				})
+				.collect::<Vec<_>>()
+		};
+
+		// Sorted by distance, then by term relevance.
+		assert_eq!(search("tokyo", 2), [("特許", 1), ("東京", 2)]);
+		assert_eq!(search("tokyo", 1), [("特許", 1)]);
+		assert_eq!(search("hashi", 0), [("箸", 0), ("橋", 0)]);
+		assert_eq!(search("hasi", 1), [("箸", 1), ("橋", 1)]);
+
+		// Adjacent transpositions are a single edit.
+		assert_eq!(search("toukyuo", 1), [("東京", 1)]);
+		assert_eq!(search("tomodahci", 1), [("友達", 1)]);
+
+		// Keys are case insensitive.
+		assert_eq!(search("KYOUTO", 0), [("京都", 0)]);
+
+		assert!(search("", 2).is_empty());
+		assert!(search("xyz", 1).is_empty());
+	}
+}
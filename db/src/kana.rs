@@ -0,0 +1,140 @@
+//! Helpers for classifying and converting Japanese characters.
+
+/// Returns true for kanji characters, including the `々` iteration mark.
+pub fn is_kanji(chr: char) -> bool {
+	match chr {
+		'\u{4E00}'..='\u{9FFF}' => true,   // CJK Unified Ideographs
+		'\u{3400}'..='\u{4DBF}' => true,   // CJK Unified Ideographs Extension A
+		'\u{F900}'..='\u{FAFF}' => true,   // CJK Compatibility Ideographs
+		'\u{20000}'..='\u{2A6DF}' => true, // CJK Unified Ideographs Extension B
+		'々' | '〆' | 'ヶ' => true,
+		_ => false,
+	}
+}
+
+/// Returns true for hiragana characters.
+pub fn is_hiragana(chr: char) -> bool {
+	matches!(chr, '\u{3041}'..='\u{309F}')
+}
+
+/// Returns true for katakana characters, including the `ー` prolonged sound
+/// mark.
+pub fn is_katakana(chr: char) -> bool {
+	match chr {
+		'\u{30A0}'..='\u{30FF}' => chr != 'ヶ',
+		'\u{31F0}'..='\u{31FF}' => true, // Katakana Phonetic Extensions
+		'\u{FF66}'..='\u{FF9F}' => true, // Halfwidth Katakana
+		_ => false,
+	}
+}
+
+/// Returns true for either hiragana or katakana characters.
+pub fn is_kana(chr: char) -> bool {
+	is_hiragana(chr) || is_katakana(chr)
+}
+
+/// Converts a katakana character to hiragana. Other characters are returned
+/// unchanged.
+pub fn to_hiragana_char(chr: char) -> char {
+	match chr {
+		'\u{30A1}'..='\u{30F6}' | '\u{30FD}' | '\u{30FE}' => {
+			std::char::from_u32(chr as u32 - 0x60).unwrap()
+		}
+		_ => chr,
+	}
+}
+
+/// Converts all katakana in the text to hiragana.
+pub fn to_hiragana<S: AsRef<str>>(text: S) -> String {
+	text.as_ref().chars().map(to_hiragana_char).collect()
+}
+
+/// Cleans up a kanji reading from the dictionary into a plain kana string.
+///
+/// Kanji readings may contain a `.` separating the okurigana (e.g. `た.べる`)
+/// and a `-` marking prefix or suffix readings (e.g. `-たち`). Those are used
+/// to return only the part of the reading that corresponds to the kanji.
+pub fn kanji_reading<S: AsRef<str>>(reading: S) -> String {
+	let reading = reading.as_ref();
+	let reading = reading.split('.').next().unwrap_or(reading);
+	to_hiragana(reading.trim_matches('-'))
+}
+
+/// Returns the possible sound changes for a kanji reading when it appears in
+/// a compound word, including the reading itself.
+///
+/// This handles rendaku (e.g. `ひと` in `人々` read as `びと`) and gemination
+/// (e.g. `がく` in `学校` read as `がっ`).
+pub fn reading_variants(reading: &str) -> Vec<String> {
+	let mut out = vec![reading.to_string()];
+
+	let mut chars = reading.chars();
+	if let Some(first) = chars.next() {
+		let rest = chars.as_str();
+		let voiced: &[u32] = match first {
+			'か' | 'き' | 'く' | 'け' | 'こ' => &[1],
+			'さ' | 'し' | 'す' | 'せ' | 'そ' => &[1],
+			'た' | 'ち' | 'つ' | 'て' | 'と' => &[1],
+			'は' | 'ひ' | 'ふ' | 'へ' | 'ほ' => &[1, 2],
+			_ => &[],
+		};
+		for &offset in voiced {
+			let first = std::char::from_u32(first as u32 + offset).unwrap();
+			out.push(format!("{}{}", first, rest));
+		}
+	}
+
+	let count = out.len();
+	for i in 0..count {
+		let reading = out[i].clone();
+		let mut chars = reading.chars();
+		match chars.next_back() {
+			Some('つ') | Some('ち') | Some('く') | Some('き') if !chars.as_str().is_empty() => {
+				out.push(format!("{}っ", chars.as_str()));
+			}
+			_ => {}
+		}
+	}
+
+	out
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn kana() {
+		assert!(is_hiragana('あ') && !is_hiragana('ア'));
+		assert!(is_katakana('ア') && is_katakana('ー') && is_katakana('ｱ'));
+		assert!(!is_katakana('ヶ') && is_kanji('ヶ'));
+		assert!(is_kanji('漢') && is_kanji('々') && !is_kanji('あ'));
+		assert_eq!(to_hiragana("カタカナとひらがな"), "かたかなとひらがな");
+		assert_eq!(to_hiragana("ヴァー"), "ゔぁー");
+	}
+
+	#[test]
+	fn kanji_reading() {
+		assert_eq!(super::kanji_reading("た.べる"), "た");
+		assert_eq!(super::kanji_reading("-たち"), "たち");
+		assert_eq!(super::kanji_reading("ガク"), "がく");
+	}
+
+	#[test]
+	fn reading_variants() {
+		// Rendaku, including both voicings for the `は` row.
+		assert_eq!(super::reading_variants("ひと"), ["ひと", "びと", "ぴと"]);
+		assert_eq!(super::reading_variants("かわ"), ["かわ", "がわ"]);
+
+		// Gemination, also combined with rendaku.
+		assert_eq!(super::reading_variants("がく"), ["がく", "がっ"]);
+		assert_eq!(
+			super::reading_variants("かつ"),
+			["かつ", "がつ", "かっ", "がっ"]
+		);
+
+		// Single character readings are not geminated.
+		assert_eq!(super::reading_variants("き"), ["き", "ぎ"]);
+		assert_eq!(super::reading_variants("め"), ["め"]);
+	}
+}
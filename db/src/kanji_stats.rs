@@ -0,0 +1,252 @@
+//! Typed kanji stats and the kanji lookup by stat.
+//!
+//! The stat names are the ones used by the Yomichan KANJIDIC dictionary.
+
+use std::fmt;
+
+use super::Kanji;
+use super::KanjiStatIndex;
+use super::DB;
+
+/// SKIP (System of Kanji Indexing by Patterns) code for a kanji, e.g. `1-4-3`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub struct SkipCode {
+	/// Pattern: 1 for left-right, 2 for up-down, 3 for enclosure and 4 for
+	/// solid kanji.
+	pub pattern: u32,
+	pub first: u32,
+	pub second: u32,
+}
+
+impl SkipCode {
+	/// Parses a code in the `P-N-N` format.
+	pub fn parse(code: &str) -> Option<SkipCode> {
+		let mut parts = code.trim().split('-').map(|x| x.parse::<u32>().ok());
+		match (parts.next(), parts.next(), parts.next(), parts.next()) {
+			(Some(Some(pattern)), Some(Some(first)), Some(Some(second)), None) => Some(SkipCode {
+				pattern,
+				first,
+				second,
+			}),
+			_ => None,
+		}
+	}
+}
+
+impl fmt::Display for SkipCode {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		write!(f, "{}-{}-{}", self.pattern, self.first, self.second)
+	}
+}
+
+impl<'db, 'a: 'db> Kanji<'db, 'a> {
+	/// Stroke count (`strokes` stat).
+	pub fn strokes(&'a self) -> Option<u32> {
+		self.stat_number("strokes")
+	}
+
+	/// School grade (`grade` stat): 1 to 6 for elementary school, 8 for the
+	/// remaining jouyou kanji and 9 and 10 for jinmeiyou kanji.
+	pub fn grade(&'a self) -> Option<u32> {
+		self.stat_number("grade")
+	}
+
+	/// JLPT level (`jlpt` stat), using the old levels from 4 (easiest) to 1
+	/// (hardest).
+	pub fn jlpt(&'a self) -> Option<u32> {
+		self.stat_number("jlpt")
+	}
+
+	/// Rank among the 2500 most used kanji in newspapers (`freq` stat), with 1
+	/// for the most used. Unlike [frequency](Kanji::frequency), lower is more
+	/// frequent.
+	pub fn frequency_rank(&'a self) -> Option<u32> {
+		self.stat_number("freq")
+	}
+
+	/// SKIP code (`skip` stat).
+	pub fn skip(&'a self) -> Option<SkipCode> {
+		self.stat("skip").and_then(SkipCode::parse)
+	}
+
+	/// Index in the "Remembering the Kanji" books by James Heisig (`heisig`
+	/// stat).
+	pub fn heisig(&'a self) -> Option<u32> {
+		self.stat_number("heisig")
+	}
+
+	fn stat_number(&'a self, name: &str) -> Option<u32> {
+		self.stat(name).and_then(|x| x.trim().parse().ok())
+	}
+}
+
+impl<'db> DB<'db> {
+	/// Returns the kanji with the given stat value (e.g. `jlpt` and `4`),
+	/// sorted by frequency.
+	pub fn kanji_by_stat<'a: 'db, S: AsRef<str>, V: AsRef<str>>(
+		&'a self,
+		stat: S,
+		value: V,
+	) -> impl 'a + Iterator<Item = Kanji<'db, 'a>> {
+		let value = value.as_ref();
+		let rows = self.get_kanji_stat_rows(stat.as_ref());
+		let sta = rows.partition_point(|row| self.get_str(row.value) < value);
+		let end = rows.partition_point(|row| self.get_str(row.value) <= value);
+		rows[sta..end]
+			.iter()
+			.map(move |row| self.get_kanji(row.kanji.into()))
+	}
+
+	/// Returns all kanji with the given stat and the stat value, sorted by the
+	/// value string.
+	pub(super) fn kanji_with_stat<'a: 'db>(
+		&'a self,
+		stat: &str,
+	) -> impl 'a + Iterator<Item = (Kanji<'db, 'a>, &'db str)> {
+		self.get_kanji_stat_rows(stat)
+			.iter()
+			.map(move |row| (self.get_kanji(row.kanji.into()), self.get_str(row.value)))
+	}
+
+	fn get_kanji_stat_rows(&self, stat: &str) -> &'db [KanjiStatIndex] {
+		let index = self.index_kanji_stat;
+		let name = |row: &KanjiStatIndex| {
+			let tag: usize = row.stat.into();
+			self.get_str(self.tags[tag].name)
+		};
+		let sta = index.partition_point(|row| name(row) < stat);
+		let end = index.partition_point(|row| name(row) <= stat);
+		&index[sta..end]
+	}
+
+	fn get_kanji<'a: 'db>(&'a self, index: usize) -> Kanji<'db, 'a> {
+		Kanji {
+			data: self,
+			item: &self.kanji[index],
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::testing::*;
+	use super::super::{KanjiStatIndex, KnownKanji, DB};
+	use super::SkipCode;
+
+	fn test_db() -> TestDB {
+		let stats = ["strokes", "grade", "jlpt", "freq", "skip", "heisig"];
+		let tags: Vec<_> = stats.iter().map(|&x| (x, "misc")).collect();
+		let mut w = writer(&tags);
+		let rows = [
+			('一', 1000, &["1", "1", "4", "2", "4-1-4", "1"]),
+			('二', 900, &["2", "1", "4", "9", "4-2-1", "2"]),
+			('東', 500, &["8", "2", "4", "37", "4-8-3", "1703"]),
+			('京', 200, &["8", "2", "3", "", " 2-2-6 ", ""]),
+			('亜', 0, &["7", "8", "1", "", "4-7", ""]),
+			('丑', 0, &["4", "10", "", "", "", ""]),
+		];
+		for &(character, frequency, values) in rows.iter() {
+			let mut item = kanji(&mut w, character, &[], &[]);
+			item.frequency = frequency;
+			for (name, value) in stats.iter().zip(values.iter()) {
+				if !value.is_empty() {
+					let stat = (w.get_tag(name), w.intern(value.to_string()));
+					item.stats.push(stat);
+				}
+			}
+			w.push_kanji(item);
+		}
+
+		let term = term(&mut w, "東京", "とうきょう", "toukyou", 0);
+		w.push_term(term);
+		TestDB::new(w)
+	}
+
+	#[test]
+	fn skip_code() {
+		let code = SkipCode::parse("1-4-3").unwrap();
+		assert_eq!(code.pattern, 1);
+		assert_eq!(code.first, 4);
+		assert_eq!(code.second, 3);
+		assert_eq!(code.to_string(), "1-4-3");
+		assert_eq!(SkipCode::parse(" 2-2-6 ").unwrap().to_string(), "2-2-6");
+
+		assert!(SkipCode::parse("").is_none());
+		assert!(SkipCode::parse("1-4").is_none());
+		assert!(SkipCode::parse("1-4-3-2").is_none());
+		assert!(SkipCode::parse("1-x-3").is_none());
+	}
+
+	#[test]
+	fn typed_stats() {
+		let data = test_db();
+		let db = data.db();
+
+		let kanji = db.kanji('東').unwrap();
+		assert_eq!(kanji.strokes(), Some(8));
+		assert_eq!(kanji.grade(), Some(2));
+		assert_eq!(kanji.jlpt(), Some(4));
+		assert_eq!(kanji.frequency_rank(), Some(37));
+		assert_eq!(kanji.skip(), SkipCode::parse("4-8-3"));
+		assert_eq!(kanji.heisig(), Some(1703));
+
+		let kanji = db.kanji('京').unwrap();
+		assert_eq!(kanji.skip().unwrap().to_string(), "2-2-6");
+		assert_eq!(kanji.frequency_rank(), None);
+		assert_eq!(kanji.heisig(), None);
+
+		let kanji = db.kanji('亜').unwrap();
+		assert_eq!(kanji.skip(), None);
+	}
+
+	#[test]
+	fn kanji_by_stat() {
+		let data = test_db();
+		let db = data.db();
+		let characters = |stat: &str, value: &str| {
+			db.kanji_by_stat(stat, value)
+				.map(|x| x.character())
+				.collect::<String>()
+		};
+
+		// Kanji are sorted by frequency for each value.
+		assert_eq!(characters("grade", "1"), "一二");
+		assert_eq!(characters("grade", "2"), "東京");
+		assert_eq!(characters("grade", "10"), "丑");
+		assert_eq!(characters("strokes", "8"), "東京");
+		assert_eq!(characters("jlpt", "4"), "一二東");
+		assert_eq!(characters("heisig", "1"), "一");
+		assert_eq!(characters("grade", "3"), "");
+		assert_eq!(characters("grade", ""), "");
+		assert_eq!(characters("other", "1"), "");
+
+		// Values are compared as numbers for the levels.
+		let sorted = |known: KnownKanji| {
+			let mut chars: Vec<char> = known.iter().collect();
+			chars.sort();
+			chars.into_iter().collect::<String>()
+		};
+		assert_eq!(sorted(db.kanji_by_grade(2)), "一二京東");
+		assert_eq!(sorted(db.kanji_by_grade(10)), "一丑二亜京東");
+		assert_eq!(sorted(db.kanji_by_jlpt(3)), "一二京東");
+		assert_eq!(sorted(db.kanji_by_jlpt(1)), "一二亜京東");
+	}
+
+	#[test]
+	fn verify() {
+		let data = test_db();
+		let db = data.db();
+		assert!(db.verify().is_ok());
+
+		let mut index = db.index_kanji_stat.to_vec();
+		index[0] = KanjiStatIndex {
+			kanji: 6u32.into(),
+			..index[0]
+		};
+		let bad = DB {
+			index_kanji_stat: &index,
+			..db
+		};
+		assert!(bad.verify().is_err());
+	}
+}
@@ -2,7 +2,15 @@
 
 extern crate unicode_segmentation;
 
-use std::time::Instant;
+#[cfg(feature = "serde")]
+extern crate serde;
+
+#[cfg(all(test, feature = "serde"))]
+extern crate serde_json;
+
+#[macro_use]
+mod util;
+use util::Timer;
 
 mod raw;
 use raw::*;
@@ -16,6 +24,53 @@ pub use writer::*;
 mod search;
 pub use search::*;
 
+mod query;
+pub use query::*;
+
+mod suggest;
+pub use suggest::*;
+
+mod pattern;
+pub use pattern::*;
+
+mod fuzzy;
+pub use fuzzy::*;
+
+mod kana;
+
+mod reading;
+pub use reading::*;
+
+mod furigana;
+pub use furigana::*;
+
+mod tags;
+pub use tags::*;
+
+mod deinflect;
+pub use deinflect::*;
+
+mod conjugate;
+pub use conjugate::*;
+
+mod vocabulary;
+pub use vocabulary::*;
+
+mod coverage;
+pub use coverage::*;
+
+mod kanji_stats;
+pub use kanji_stats::*;
+
+mod owned;
+pub use owned::*;
+
+#[cfg(feature = "serde")]
+mod serialize;
+
+#[cfg(test)]
+mod testing;
+
 /// Root structure for the Japanese database.
 ///
 /// The structure can be loaded from a binary blob using the [load](DB::load)
@@ -29,12 +84,70 @@ pub struct DB<'a> {
 	index_prefix_jp: &'a [TermIndex],
 	index_suffix_jp: &'a [TermIndex],
 	index_chars_jp: &'a [CharIndex],
+	index_sequence: &'a [SequenceIndex],
+	index_key_trie: &'a [TrieNode],
+	index_kanji: &'a [RawUint32],
+	index_suggest: &'a [SuggestIndex],
+	index_tag_terms: &'a [VecHandle],
+	index_kanji_stat: &'a [KanjiStatIndex],
 	vector_data: &'a [RawUint32],
 	string_list: &'a [StrHandle],
 	string_data: &'a str,
 }
 
+/// Returns an error from the enclosing function if the condition is false.
+macro_rules! ensure {
+	($cond:expr, $($arg:tt)*) => {
+		if !$cond {
+			return Err(format!($($arg)*));
+		}
+	};
+}
+
+/// Summary statistics for a database. See [DB::stats].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+	/// Number of terms.
+	pub terms: usize,
+	/// Number of kanji.
+	pub kanji: usize,
+	/// Number of tags.
+	pub tags: usize,
+	/// Number of entries in the term search index (expressions, readings and
+	/// search keys).
+	pub index_entries: usize,
+	/// Number of distinct characters in the character index.
+	pub indexed_chars: usize,
+	/// Number of strings.
+	pub strings: usize,
+	/// Size of the string data in bytes.
+	pub string_bytes: usize,
+	/// Size of the vector data in bytes.
+	pub vector_bytes: usize,
+}
+
 impl<'db> DB<'db> {
+	/// Number of terms in the database. Valid term indexes are in the range
+	/// `0..term_count()`.
+	pub fn term_count(&self) -> usize {
+		self.terms.len()
+	}
+
+	/// Returns summary statistics for the database.
+	pub fn stats(&self) -> Stats {
+		Stats {
+			terms: self.terms.len(),
+			kanji: self.kanji.len(),
+			tags: self.tags.len(),
+			index_entries: self.index_prefix_jp.len(),
+			indexed_chars: self.index_chars_jp.len(),
+			strings: self.string_list.len(),
+			string_bytes: self.string_data.len(),
+			vector_bytes: self.vector_data.len() * std::mem::size_of::<u32>(),
+		}
+	}
+
 	pub fn term<'a: 'db>(&'a self, index: usize) -> Option<Term<'db, 'a>> {
 		if index < self.terms.len() {
 			Some(Term {
@@ -47,6 +160,86 @@ impl<'db> DB<'db> {
 		}
 	}
 
+	/// Returns the kanji entry for the given character, if available.
+	pub fn kanji<'a: 'db>(&'a self, character: char) -> Option<Kanji<'db, 'a>> {
+		let character = character as u32;
+		let row = self.index_kanji.binary_search_by(|&index| {
+			let index: usize = index.into();
+			let other: u32 = self.kanji[index].character.into();
+			other.cmp(&character)
+		});
+		row.ok().map(|row| {
+			let index: usize = self.index_kanji[row].into();
+			Kanji {
+				data: self,
+				item: &self.kanji[index],
+			}
+		})
+	}
+
+	/// Returns all kanji in the database.
+	pub fn all_kanji<'a: 'db>(&'a self) -> impl 'a + Iterator<Item = Kanji<'db, 'a>> {
+		self.kanji.iter().map(move |item| Kanji {
+			data: self,
+			item,
+		})
+	}
+
+	/// Returns the terms with the given `sequence` number in the `source`
+	/// dictionary.
+	///
+	/// Term indexes change on every database build, so this should be used
+	/// whenever a stable reference to a term is needed.
+	pub fn terms_by_sequence<'a: 'db, S: AsRef<str>>(
+		&'a self,
+		source: S,
+		sequence: u32,
+	) -> impl 'a + Iterator<Item = Term<'db, 'a>> {
+		let source = source.as_ref();
+		let key = |row: &SequenceIndex| {
+			let row_sequence: u32 = row.sequence.into();
+			(self.get_str(row.source), row_sequence)
+		};
+		let sta = self
+			.index_sequence
+			.partition_point(|row| key(row) < (source, sequence));
+		let end = self
+			.index_sequence
+			.partition_point(|row| key(row) <= (source, sequence));
+		self.index_sequence[sta..end]
+			.iter()
+			.map(move |row| self.term(row.term.into()).unwrap())
+	}
+
+	/// Returns all tags in the database.
+	pub fn tags<'a: 'db>(&'a self) -> impl 'a + Iterator<Item = Tag<'db, 'a>> {
+		self.tags.iter().map(move |item| Tag {
+			data: self,
+			item,
+		})
+	}
+
+	/// Returns the tag with the given name, if available.
+	pub fn tag<'a: 'db, S: AsRef<str>>(&'a self, name: S) -> Option<Tag<'db, 'a>> {
+		let name = name.as_ref();
+		self.tags().find(|tag| tag.name() == name)
+	}
+
+	/// Returns the indexes of the terms with the given tag in their term or
+	/// definition tags (e.g. `P` for common terms), sorted by frequency.
+	pub fn terms_with_tag<'a: 'db, S: AsRef<str>>(&'a self, name: S) -> impl 'a + Iterator<Item = usize> {
+		let name = name.as_ref();
+		let index = self.tags.iter().position(|tag| self.get_str(tag.name) == name);
+		let terms = match index {
+			Some(index) if index < self.index_tag_terms.len() => {
+				let (sta, end) = self.index_tag_terms[index].range();
+				&self.vector_data[sta..end]
+			}
+			_ => &[],
+		};
+		terms.iter().map(|&index| index.into())
+	}
+
 	fn get_tag<'a: 'db>(&'a self, index: RawUint32) -> Tag<'db, 'a> {
 		let index: usize = index.into();
 		Tag {
@@ -62,6 +255,40 @@ impl<'db> DB<'db> {
 			.map(move |&index| self.get_tag(index))
 	}
 
+	/// Returns the `(stat, info)` pairs from a list of kanji stats.
+	fn get_stats<'a: 'db>(
+		&'a self,
+		stats: VecHandle,
+	) -> impl 'a + Iterator<Item = (Tag<'db, 'a>, &'db str)> {
+		let (sta, end) = stats.range();
+		self.vector_data[sta..end]
+			.chunks(2)
+			.map(move |it| (self.get_tag(it[0]), self.get_str(it[1])))
+	}
+
+	/// Returns the sorted list of term indexes containing the given character
+	/// in their expression or reading.
+	fn get_char_index(&self, chr: char) -> &'db [RawUint32] {
+		let chr = chr as u32;
+		let row = self.index_chars_jp.binary_search_by(|it| {
+			let character: u32 = it.character.into();
+			character.cmp(&chr)
+		});
+		if let Ok(row) = row {
+			let (sta, end) = self.index_chars_jp[row].indexes.range();
+			&self.vector_data[sta..end]
+		} else {
+			&[]
+		}
+	}
+
+	fn get_strs<'a: 'db>(&'a self, strings: VecHandle) -> impl 'a + Iterator<Item = &'db str> {
+		let (sta, end) = strings.range();
+		self.vector_data[sta..end]
+			.iter()
+			.map(move |&index| self.get_str(index))
+	}
+
 	fn get_str(&self, index: RawUint32) -> &'db str {
 		let index: usize = index.into();
 		let string = &self.string_list[index];
@@ -73,168 +300,279 @@ impl<'db> DB<'db> {
 impl<'a> DB<'a> {
 	/// Does a sanity check on the database structure and outputs some database
 	/// statistics. This method is used only for debugging purposes.
+	///
+	/// Panics if the check fails. See [verify](DB::verify) for a non-panicking
+	/// version.
 	pub fn check(&self) {
-		let start = Instant::now();
+		let start = Timer::start();
+
+		let stats = match self.verify() {
+			Ok(stats) => stats,
+			Err(err) => panic!("database check failed: {}", err),
+		};
+
+		let mut chars_cnt = 0;
+		let mut chars_max = 0;
+		for row in self.index_chars_jp.iter() {
+			let count: u32 = row.indexes.length.into();
+			let count = count as usize;
+			chars_cnt += count;
+			chars_max = std::cmp::max(chars_max, count);
+		}
+		let chars_len = self.index_chars_jp.len();
+		let chars_avg = chars_cnt.checked_div(chars_len).unwrap_or(0);
+
+		debug_log!("Database check finished (elapsed {:?})", start.elapsed());
+		debug_log!(
+			"-> {} terms / {} kanji / {} tags",
+			stats.terms,
+			stats.kanji,
+			stats.tags
+		);
+		debug_log!(
+			"-> {} indexed terms / {} chars ({} avg / {} max / {} total)",
+			stats.index_entries,
+			chars_len,
+			chars_avg,
+			chars_max,
+			chars_cnt,
+		);
+		debug_log!("-> {} vector data", bytes(stats.vector_bytes));
+		debug_log!(
+			"-> {} string data ({} strings)",
+			bytes(stats.string_bytes),
+			stats.strings
+		);
+	}
+
+	/// Checks the database for consistency, making sure all indexes and
+	/// references are within bounds.
+	///
+	/// Returns the database statistics or a description of the first error
+	/// found.
+	pub fn verify(&self) -> Result<Stats, String> {
+		// String handles are checked first, since the checks below may need
+		// to read the strings.
+		for (index, s) in self.string_list.iter().enumerate() {
+			let (sta, end) = s.range();
+			ensure!(
+				sta <= end && end <= self.string_data.len(),
+				"string #{}: string out of bounds",
+				index + 1
+			);
+			ensure!(
+				self.string_data.is_char_boundary(sta) && self.string_data.is_char_boundary(end),
+				"string #{}: invalid UTF-8 boundary",
+				index + 1
+			);
+		}
 
 		for tag in self.tags.iter() {
-			self.check_string(tag.name, "tag name");
-			self.check_string(tag.category, "tag category");
-			self.check_string(tag.notes, "tag notes");
+			self.check_string(tag.name, "tag name")?;
+			self.check_string(tag.category, "tag category")?;
+			self.check_string(tag.notes, "tag notes")?;
 		}
 
 		for term in self.terms.iter() {
-			self.check_string(term.expression, "term expression");
-			self.check_string(term.reading, "term reading");
-			self.check_string(term.search_key, "term search key");
-			self.check_string(term.source, "term source");
-			self.check_vector_strings(term.glossary, "term glossary");
-			self.check_vector_tags(term.rules, "term rules");
-			self.check_vector_tags(term.term_tags, "term tags");
-			self.check_vector_tags(term.definition_tags, "term definition tags");
+			self.check_string(term.expression, "term expression")?;
+			self.check_string(term.reading, "term reading")?;
+			self.check_string(term.search_key, "term search key")?;
+			self.check_string(term.source, "term source")?;
+			self.check_vector_strings(term.glossary, "term glossary")?;
+			self.check_vector_tags(term.rules, "term rules")?;
+			self.check_vector_tags(term.term_tags, "term tags")?;
+			self.check_vector_tags(term.definition_tags, "term definition tags")?;
 		}
 
 		for kanji in self.kanji.iter() {
-			self.check_vector_strings(kanji.meanings, "kanji meanings");
-			self.check_vector_strings(kanji.onyomi, "kanji onyomi");
-			self.check_vector_strings(kanji.kunyomi, "kanji kunyomi");
-			self.check_string(kanji.source, "kanji source");
-			self.check_vector_tags(kanji.tags, "kanji tags");
+			let character: u32 = kanji.character.into();
+			ensure!(
+				std::char::from_u32(character).is_some(),
+				"kanji character is not valid"
+			);
+			self.check_vector_strings(kanji.meanings, "kanji meanings")?;
+			self.check_vector_strings(kanji.onyomi, "kanji onyomi")?;
+			self.check_vector_strings(kanji.kunyomi, "kanji kunyomi")?;
+			self.check_string(kanji.source, "kanji source")?;
+			self.check_vector_tags(kanji.tags, "kanji tags")?;
 
-			self.check_vector(kanji.stats, "kanji stats");
+			self.check_vector(kanji.stats, "kanji stats")?;
 			let (sta, end) = kanji.stats.range();
 			let mut iter = self.vector_data[sta..end].iter();
 			while let Some(&stat_tag) = iter.next() {
 				let stat_tag: u32 = stat_tag.into();
 				let stat_tag = stat_tag as usize;
-				let stat_val = iter.next().expect("kanji stat tag missing value");
-				assert!(stat_tag <= self.tags.len(), "kanji stat tag out of bounds");
-				self.check_string(*stat_val, "kanji stat value");
+				let stat_val = match iter.next() {
+					Some(value) => value,
+					None => return Err("kanji stat tag missing value".to_string()),
+				};
+				ensure!(stat_tag < self.tags.len(), "kanji stat tag out of bounds");
+				self.check_string(*stat_val, "kanji stat value")?;
 			}
 		}
 
 		for row in self.index_prefix_jp.iter() {
-			self.check_term_index(*row, "prefix index");
+			self.check_term_index(*row, "prefix index")?;
 		}
 
 		for row in self.index_suffix_jp.iter() {
-			self.check_term_index(*row, "suffix index");
+			self.check_term_index(*row, "suffix index")?;
+		}
+
+		for row in self.index_sequence.iter() {
+			self.check_string(row.source, "sequence index")?;
+			let index: usize = row.term.into();
+			ensure!(index < self.terms.len(), "sequence index: term out of bounds");
+		}
+
+		// The trie is flattened in breadth-first order, so the children of
+		// each node follow the children of the previous nodes and always
+		// come after the node itself. This guarantees that the trie has no
+		// cycles, which would overflow the stack in the recursive walk.
+		let mut next_child = 1;
+		for (index, node) in self.index_key_trie.iter().enumerate() {
+			let (sta, end) = node.children();
+			ensure!(sta == next_child, "key trie: invalid child position");
+			ensure!(sta > index || sta == end, "key trie: invalid child position");
+			ensure!(end <= self.index_key_trie.len(), "key trie: child out of bounds");
+			next_child = end;
+
+			let mut last = None;
+			for child in self.index_key_trie[sta..end].iter() {
+				let character: u32 = child.character.into();
+				ensure!(
+					std::char::from_u32(character).is_some(),
+					"key trie: invalid character"
+				);
+				ensure!(
+					last.map(|x| x < character).unwrap_or(true),
+					"key trie: children not sorted"
+				);
+				last = Some(character);
+			}
+
+			self.check_vector_terms(node.terms, "key trie terms")?;
+		}
+		ensure!(
+			self.index_key_trie.is_empty() || next_child == self.index_key_trie.len(),
+			"key trie: invalid number of nodes"
+		);
+
+		for &index in self.index_kanji.iter() {
+			let index: usize = index.into();
+			ensure!(index < self.kanji.len(), "kanji index: kanji out of bounds");
 		}
 
-		let mut chars_cnt = 0;
-		let mut chars_max = 0;
 		for row in self.index_chars_jp.iter() {
-			let count: u32 = row.indexes.length.into();
-			let count = count as usize;
-			chars_cnt += count;
-			chars_max = std::cmp::max(chars_max, count);
-			self.check_vector_terms(row.indexes, "index chars row");
+			self.check_vector_terms(row.indexes, "index chars row")?;
 		}
-		let chars_len = self.index_chars_jp.len();
-		let chars_avg = chars_cnt / chars_len;
 
-		for (index, s) in self.string_list.iter().enumerate() {
-			let (sta, end) = s.range();
-			assert!(
-				sta <= self.string_data.len(),
-				"string #{}: string start out of bounds",
-				index + 1
-			);
-			assert!(
-				end <= self.string_data.len(),
-				"string #{}: string end out of bounds",
-				index + 1
+		ensure!(
+			self.index_tag_terms.len() == self.tags.len(),
+			"tag index: invalid number of tags"
+		);
+		for &row in self.index_tag_terms.iter() {
+			self.check_vector_terms(row, "tag index terms")?;
+		}
+
+		for row in self.index_kanji_stat.iter() {
+			let stat: usize = row.stat.into();
+			ensure!(stat < self.tags.len(), "kanji stat index: tag out of bounds");
+			self.check_string(row.value, "kanji stat index")?;
+			let index: usize = row.kanji.into();
+			ensure!(index < self.kanji.len(), "kanji stat index: kanji out of bounds");
+		}
+
+		for row in self.index_suggest.iter() {
+			self.check_string(row.key, "suggest index")?;
+			let length: usize = row.length.into();
+			let key = self.get_str(row.key);
+			ensure!(
+				length <= key.len() && key.is_char_boundary(length),
+				"suggest index: invalid prefix length"
 			);
+			self.check_vector(row.keys, "suggest index keys")?;
+			let (sta, end) = row.keys.range();
+			ensure!((end - sta) % 2 == 0, "suggest index: key missing term");
+			for pair in self.vector_data[sta..end].chunks(2) {
+				self.check_string(pair[0], "suggest index key")?;
+				let index: usize = pair[1].into();
+				ensure!(index < self.terms.len(), "suggest index: term out of bounds");
+			}
 		}
 
-		println!("Database check finished (elapsed {:?})", start.elapsed());
-		println!(
-			"-> {} terms / {} kanji / {} tags",
-			self.terms.len(),
-			self.kanji.len(),
-			self.tags.len()
-		);
-		println!(
-			"-> {} indexed terms / {} chars ({} avg / {} max / {} total)",
-			self.index_chars_jp.len(),
-			chars_len,
-			chars_avg,
-			chars_max,
-			chars_cnt,
-		);
-		println!(
-			"-> {} vector data",
-			bytes(self.vector_data.len() * std::mem::size_of::<u32>())
-		);
-		println!(
-			"-> {} string data ({} strings)",
-			bytes(self.string_data.len()),
-			self.string_list.len()
-		);
+		Ok(self.stats())
 	}
 
-	fn check_term_index(&self, row: TermIndex, name: &str) {
-		self.check_string(row.key, name);
+	fn check_term_index(&self, row: TermIndex, name: &str) -> Result<(), String> {
+		self.check_string(row.key, name)?;
 		let index: u32 = row.term.into();
 		let index = index as usize;
-		assert!(index <= self.terms.len(), "{}: term out of bounds", name);
+		ensure!(index < self.terms.len(), "{}: term out of bounds", name);
+		Ok(())
 	}
 
-	fn check_string(&self, index: RawUint32, name: &str) {
+	fn check_string(&self, index: RawUint32, name: &str) -> Result<(), String> {
 		let index: u32 = index.into();
 		let index = index as usize;
-		assert!(
+		ensure!(
 			index < self.string_list.len(),
 			"{}: string index out of bounds",
 			name
 		);
+		Ok(())
 	}
 
-	fn check_vector_strings(&self, vec: VecHandle, name: &str) {
-		self.check_vector(vec, name);
+	fn check_vector_strings(&self, vec: VecHandle, name: &str) -> Result<(), String> {
+		self.check_vector(vec, name)?;
 		let (sta, end) = vec.range();
 		let name = format!("{} string index:", name);
 		let name = name.as_str();
 		for &index in self.vector_data[sta..end].iter() {
-			self.check_string(index, name);
+			self.check_string(index, name)?;
 		}
+		Ok(())
 	}
 
-	fn check_vector_tags(&self, vec: VecHandle, name: &str) {
-		self.check_vector(vec, name);
+	fn check_vector_tags(&self, vec: VecHandle, name: &str) -> Result<(), String> {
+		self.check_vector(vec, name)?;
 		let (sta, end) = vec.range();
 		for &index in self.vector_data[sta..end].iter() {
 			let index: u32 = index.into();
 			let index = index as usize;
-			assert!(index < self.tags.len(), "{}: tag index out of bounds", name);
+			ensure!(index < self.tags.len(), "{}: tag index out of bounds", name);
 		}
+		Ok(())
 	}
 
-	fn check_vector_terms(&self, vec: VecHandle, name: &str) {
-		self.check_vector(vec, name);
+	fn check_vector_terms(&self, vec: VecHandle, name: &str) -> Result<(), String> {
+		self.check_vector(vec, name)?;
 		let (sta, end) = vec.range();
 		for &index in self.vector_data[sta..end].iter() {
 			let index: u32 = index.into();
 			let index = index as usize;
-			assert!(
+			ensure!(
 				index < self.terms.len(),
 				"{}: term index out of bounds",
 				name
 			);
 		}
+		Ok(())
 	}
 
-	fn check_vector(&self, vec: VecHandle, name: &str) {
+	fn check_vector(&self, vec: VecHandle, name: &str) -> Result<(), String> {
 		let (sta, end) = vec.range();
-		assert!(
-			sta <= self.vector_data.len(),
+		ensure!(
+			sta <= end && sta <= self.vector_data.len(),
 			"{}: vector start out of bounds",
 			name
 		);
-		assert!(
+		ensure!(
 			end <= self.vector_data.len(),
 			"{}: vector end out of bounds",
 			name
 		);
+		Ok(())
 	}
 }
 
@@ -251,3 +589,265 @@ fn bytes(value: usize) -> String {
 		format!("{:.2} MB", mb)
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::testing::*;
+	use super::*;
+
+	#[test]
+	fn terms_by_sequence() {
+		let mut w = writer(&[]);
+		let terms = [
+			("日本", "にほん", "JMdict", 100, 20),
+			("日本", "にっぽん", "JMdict", 100, 10),
+			("本", "ほん", "JMdict", 200, 30),
+			("本", "もと", "JMnedict", 100, 0),
+			("本", "ぽん", "JMdict", 0, 0),
+		];
+		for &(expression, reading, source, sequence, frequency) in terms.iter() {
+			let mut term = term(&mut w, expression, reading, "", frequency);
+			term.source = w.intern(source.to_string());
+			term.sequence = sequence;
+			w.push_term(term);
+		}
+
+		let db = TestDB::new(w);
+		let db = db.db();
+		let readings = |source: &str, sequence: u32| {
+			db.terms_by_sequence(source, sequence)
+				.map(|x| x.reading())
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(readings("JMdict", 100), ["にほん", "にっぽん"]);
+		assert_eq!(readings("JMdict", 200), ["ほん"]);
+		assert_eq!(readings("JMnedict", 100), ["もと"]);
+		assert!(readings("JMdict", 300).is_empty());
+		assert!(readings("other", 100).is_empty());
+
+		// Terms without a sequence number are not indexed.
+		assert!(readings("JMdict", 0).is_empty());
+	}
+
+	#[test]
+	fn terms_with_tag() {
+		let mut w = writer(&[
+			("P", "popular"),
+			("n", "partOfSpeech"),
+			("v1", "partOfSpeech"),
+			("arch", "archaism"),
+		]);
+		let terms = [
+			("食べる", 100, &["P"][..], &["v1"][..]),
+			("東京", 200, &["P"], &["n"]),
+			("京都", 20, &["P"], &["P", "n"]),
+			("本", 50, &[], &["n"]),
+		];
+		for &(expression, frequency, term_tags, definition_tags) in terms.iter() {
+			let mut term = term(&mut w, expression, "", "", frequency);
+			term.term_tags = w.get_tags(term_tags.iter());
+			term.definition_tags = w.get_tags(definition_tags.iter());
+			w.push_term(term);
+		}
+
+		let db = TestDB::new(w);
+		let db = db.db();
+		let expressions = |name: &str| {
+			db.terms_with_tag(name)
+				.map(|x| db.term(x).unwrap().expression())
+				.collect::<Vec<_>>()
+		};
+
+		// Terms are sorted by frequency and listed once, even with the tag in
+		// both the term and definition tags.
+		assert_eq!(expressions("P"), ["東京", "食べる", "京都"]);
+		assert_eq!(expressions("n"), ["東京", "本", "京都"]);
+		assert_eq!(expressions("v1"), ["食べる"]);
+		assert!(expressions("arch").is_empty());
+		assert!(expressions("other").is_empty());
+
+		let tag = db.tag("v1").unwrap();
+		assert_eq!(tag.name(), "v1");
+		assert_eq!(tag.category(), "partOfSpeech");
+		assert!(db.tag("other").is_none());
+		assert!(db.tag("").is_none());
+
+		// The index must have a row for each tag.
+		assert!(db.verify().is_ok());
+		let bad = DB {
+			index_tag_terms: &db.index_tag_terms[1..],
+			..db
+		};
+		assert!(bad.verify().is_err());
+	}
+
+	/// Database with a few terms and kanji, used to test [DB::verify].
+	fn verify_db() -> TestDB {
+		let mut w = writer(&[("P", "popular")]);
+		let terms = [
+			("東京", "とうきょう", "toukyou"),
+			("京都", "きょうと", "kyouto"),
+			("都", "みやこ", "miyako"),
+		];
+		for &(expression, reading, key) in terms.iter() {
+			let mut term = term(&mut w, expression, reading, key, 0);
+			term.term_tags = w.get_tags(["P"].iter());
+			w.push_term(term);
+		}
+		let kanji = kanji(&mut w, '京', &["キョウ"], &["みやこ"]);
+		w.push_kanji(kanji);
+		TestDB::new(w)
+	}
+
+	#[test]
+	fn verify() {
+		let data = verify_db();
+		let db = data.db();
+		let stats = db.verify().unwrap();
+		assert_eq!(stats.terms, 3);
+		assert_eq!(stats.kanji, 1);
+		assert_eq!(stats.tags, 1);
+
+		// Truncated data.
+		let bytes = data.bytes();
+		assert!(DB::try_load(&bytes[..bytes.len() - 1]).is_err());
+		assert!(DB::try_load(&bytes[..bytes.len() / 2]).is_err());
+		assert!(DB::try_load(&[]).is_err());
+	}
+
+	#[test]
+	fn owned_db() {
+		let data = verify_db();
+		let bytes = data.bytes();
+
+		// Data at any offset is copied into an aligned buffer.
+		let mut unaligned = vec![0u8; bytes.len() + 1];
+		unaligned[1..].copy_from_slice(bytes);
+		let owned = OwnedDB::load(&unaligned[1..]).unwrap();
+		assert_eq!(owned.bytes(), bytes);
+		let db = owned.db();
+		assert_eq!(db.term_count(), 3);
+		assert_eq!(db.term(0).unwrap().expression(), "東京");
+
+		assert!(OwnedDB::load(&bytes[..bytes.len() - 1]).is_err());
+		assert!(OwnedDB::load(&[]).is_err());
+	}
+
+	#[test]
+	fn verify_strings() {
+		let data = verify_db();
+		let db = data.db();
+
+		let mut string_list = db.string_list.to_vec();
+		string_list[0] = StrHandle {
+			offset: 0xFFFF_FFF0u32.into(),
+			length: 0u32.into(),
+		};
+		let bad = DB {
+			string_list: &string_list,
+			..db
+		};
+		assert!(bad.verify().is_err());
+
+		// Strings are checked before anything that reads them.
+		let index_suggest = [SuggestIndex {
+			key: 0u32.into(),
+			length: 0u32.into(),
+			keys: VecHandle {
+				offset: 0u32.into(),
+				length: 0u32.into(),
+			},
+		}];
+		let bad = DB {
+			string_list: &string_list,
+			index_suggest: &index_suggest,
+			..db
+		};
+		assert!(bad.verify().is_err());
+
+		// Handles in the middle of a character.
+		let mut string_list = db.string_list.to_vec();
+		let index = (0..string_list.len())
+			.find(|&x| db.get_str((x as u32).into()).starts_with('東'))
+			.unwrap();
+		let (sta, _) = string_list[index].range();
+		string_list[index] = StrHandle {
+			offset: (sta as u32 + 1).into(),
+			length: 1u32.into(),
+		};
+		let bad = DB {
+			string_list: &string_list,
+			..db
+		};
+		assert!(bad.verify().is_err());
+	}
+
+	#[test]
+	fn verify_kanji() {
+		let data = verify_db();
+		let db = data.db();
+
+		let item = &db.kanji[0];
+		let kanji = [KanjiRaw {
+			character: 0xD800u32.into(),
+			frequency: item.frequency,
+			source: item.source,
+			meanings: item.meanings,
+			onyomi: item.onyomi,
+			kunyomi: item.kunyomi,
+			tags: item.tags,
+			stats: item.stats,
+		}];
+		let bad = DB {
+			kanji: &kanji,
+			..db
+		};
+		assert!(bad.verify().is_err());
+	}
+
+	#[test]
+	fn verify_key_trie() {
+		let data = verify_db();
+		let db = data.db();
+		let trie = db.index_key_trie.to_vec();
+		let check = |trie: &[TrieNode]| {
+			let bad = DB {
+				index_key_trie: trie,
+				..db
+			};
+			bad.verify()
+		};
+		assert!(check(&trie).is_ok());
+
+		// Root children are `k`, `m` and `t`.
+		assert_eq!(trie[0].children(), (1, 4));
+
+		// Node pointing back to the root.
+		let mut cycle = trie.clone();
+		let last = cycle.len() - 1;
+		cycle[last].first_child = 0u32.into();
+		cycle[last].child_count = 1u32.into();
+		assert!(check(&cycle).is_err());
+
+		// Children out of range.
+		let mut out_of_range = trie.clone();
+		out_of_range[0].child_count = 100u32.into();
+		assert!(check(&out_of_range).is_err());
+
+		// Children shared with another node.
+		let mut shared = trie.clone();
+		shared[1].first_child = 1u32.into();
+		shared[1].child_count = 1u32.into();
+		assert!(check(&shared).is_err());
+
+		// Unsorted children.
+		let mut unsorted = trie.clone();
+		unsorted.swap(1, 2);
+		assert!(check(&unsorted).is_err());
+
+		// Invalid character.
+		let mut invalid = trie.clone();
+		invalid[3].character = 0x11_0000u32.into();
+		assert!(check(&invalid).is_err());
+	}
+}
@@ -0,0 +1,112 @@
+//! Owned copies of the database views.
+//!
+//! The views in [data](super::data) borrow from the database. The structures
+//! here own their data, so they can be stored, sent across threads or
+//! serialized (with the `serde` feature) independently of the database.
+
+#[cfg(feature = "serde")]
+use serde::{Deserialize, Serialize};
+
+use super::{Kanji, Tag, Term};
+
+/// Owned copy of a [Tag].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OwnedTag {
+	pub name: String,
+	pub category: String,
+	pub notes: String,
+	pub order: i32,
+}
+
+/// Owned copy of a [Term].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OwnedTerm {
+	pub index: usize,
+	pub expression: String,
+	pub reading: String,
+	pub search_key: String,
+	pub score: i32,
+	pub sequence: u32,
+	pub frequency: Option<u32>,
+	pub source: String,
+	pub glossary: Vec<String>,
+	pub rules: Vec<OwnedTag>,
+	pub term_tags: Vec<OwnedTag>,
+	pub definition_tags: Vec<OwnedTag>,
+}
+
+/// Owned copy of a [Kanji].
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(Serialize, Deserialize))]
+pub struct OwnedKanji {
+	pub character: char,
+	pub frequency: Option<u32>,
+	pub source: String,
+	pub meanings: Vec<String>,
+	pub onyomi: Vec<String>,
+	pub kunyomi: Vec<String>,
+	pub tags: Vec<OwnedTag>,
+	/// Additional information as `(stat name, info)` pairs.
+	pub stats: Vec<(String, String)>,
+}
+
+impl<'db, 'a: 'db> Tag<'db, 'a> {
+	/// Returns an owned copy of the tag.
+	pub fn to_owned_tag(&self) -> OwnedTag {
+		OwnedTag {
+			name: self.name().to_string(),
+			category: self.category().to_string(),
+			notes: self.notes().to_string(),
+			order: self.order(),
+		}
+	}
+}
+
+impl<'db, 'a: 'db> Term<'db, 'a> {
+	/// Returns an owned copy of the term, including its tags.
+	pub fn to_owned_term(&self) -> OwnedTerm {
+		let data = self.data;
+		let strings = |vec| data.get_strs(vec).map(|x| x.to_string()).collect();
+		let tags = |vec| data.get_tags(vec).map(|x| x.to_owned_tag()).collect();
+		OwnedTerm {
+			index: self.index(),
+			expression: self.expression().to_string(),
+			reading: self.reading().to_string(),
+			search_key: self.search_key().to_string(),
+			score: self.score(),
+			sequence: self.sequence(),
+			frequency: self.frequency(),
+			source: self.source().to_string(),
+			glossary: strings(self.item.glossary),
+			rules: tags(self.item.rules),
+			term_tags: tags(self.item.term_tags),
+			definition_tags: tags(self.item.definition_tags),
+		}
+	}
+}
+
+impl<'db, 'a: 'db> Kanji<'db, 'a> {
+	/// Returns an owned copy of the kanji, including its tags.
+	pub fn to_owned_kanji(&self) -> OwnedKanji {
+		let data = self.data;
+		let strings = |vec| data.get_strs(vec).map(|x| x.to_string()).collect();
+		OwnedKanji {
+			character: self.character(),
+			frequency: self.frequency(),
+			source: self.source().to_string(),
+			meanings: strings(self.item.meanings),
+			onyomi: strings(self.item.onyomi),
+			kunyomi: strings(self.item.kunyomi),
+			tags: data
+				.get_tags(self.item.tags)
+				.map(|x| x.to_owned_tag())
+				.collect(),
+			stats: data
+				.get_stats(self.item.stats)
+				.map(|(tag, info)| (tag.name().to_string(), info.to_string()))
+				.collect(),
+		}
+	}
+}
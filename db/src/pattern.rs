@@ -0,0 +1,298 @@
+//! Wildcard pattern search for the database.
+
+use super::ResultSet;
+use super::DB;
+
+/// Parsed wildcard pattern for [search_pattern](DB::search_pattern).
+///
+/// In a pattern, `?` matches any single character and `*` matches any
+/// sequence of characters (including an empty one). The full-width `？` and
+/// `＊` are also accepted as wildcards.
+pub struct Pattern {
+	tokens: Vec<Token>,
+}
+
+#[derive(Copy, Clone, PartialEq)]
+enum Token {
+	Char(char),
+	Any,
+	Many,
+}
+
+impl Pattern {
+	/// Parses a pattern string.
+	pub fn parse<S: AsRef<str>>(pattern: S) -> Pattern {
+		let mut tokens = Vec::new();
+		for chr in pattern.as_ref().chars() {
+			let token = match chr {
+				'?' | '？' => Token::Any,
+				'*' | '＊' => Token::Many,
+				_ => Token::Char(chr),
+			};
+
+			// Consecutive `*` are redundant
+			if token == Token::Many && tokens.last() == Some(&Token::Many) {
+				continue;
+			}
+			tokens.push(token);
+		}
+		Pattern { tokens }
+	}
+
+	/// Returns true if the pattern contains no wildcards.
+	pub fn is_literal(&self) -> bool {
+		self.tokens.iter().all(|it| !it.is_wildcard())
+	}
+
+	/// Literal text at the start of the pattern, before any wildcard.
+	pub fn prefix(&self) -> String {
+		self.tokens
+			.iter()
+			.take_while(|it| !it.is_wildcard())
+			.map(|it| it.char())
+			.collect()
+	}
+
+	/// Literal text at the end of the pattern, after the last wildcard.
+	pub fn suffix(&self) -> String {
+		let mut suffix: Vec<char> = self
+			.tokens
+			.iter()
+			.rev()
+			.take_while(|it| !it.is_wildcard())
+			.map(|it| it.char())
+			.collect();
+		suffix.reverse();
+		suffix.into_iter().collect()
+	}
+
+	/// Returns true if the given text matches the whole pattern.
+	pub fn is_match<S: AsRef<str>>(&self, text: S) -> bool {
+		let text: Vec<char> = text.as_ref().chars().collect();
+		let tokens = &self.tokens;
+
+		// Position of the last `*` in the pattern and the text position it
+		// is currently matched to, for backtracking.
+		let mut last_many: Option<(usize, usize)> = None;
+
+		let (mut p, mut t) = (0, 0);
+		while t < text.len() {
+			match tokens.get(p) {
+				Some(Token::Char(chr)) if *chr == text[t] => {
+					p += 1;
+					t += 1;
+				}
+				Some(Token::Any) => {
+					p += 1;
+					t += 1;
+				}
+				Some(Token::Many) => {
+					last_many = Some((p, t));
+					p += 1;
+				}
+				_ => {
+					// Mismatch, backtrack by extending the last `*` match by
+					// one character
+					if let Some((many_p, many_t)) = last_many {
+						last_many = Some((many_p, many_t + 1));
+						p = many_p + 1;
+						t = many_t + 1;
+					} else {
+						return false;
+					}
+				}
+			}
+		}
+
+		tokens[p..].iter().all(|it| it == &Token::Many)
+	}
+}
+
+impl Token {
+	fn is_wildcard(&self) -> bool {
+		*self == Token::Any || *self == Token::Many
+	}
+
+	fn char(&self) -> char {
+		match self {
+			Token::Char(chr) => *chr,
+			_ => unreachable!(),
+		}
+	}
+}
+
+impl<'a> DB<'a> {
+	/// Search for terms with an expression or reading matching the given
+	/// wildcard pattern (e.g. `?け?`, `*的` or `食*る`) inserting the found
+	/// term indexes into the `out` result set. See [Pattern] for the syntax.
+	///
+	/// Returns the number of matches.
+	pub fn search_pattern<S: AsRef<str>>(&self, pattern: S, out: &mut ResultSet) -> usize {
+		let pattern = Pattern::parse(pattern);
+		if pattern.tokens.is_empty() {
+			return 0;
+		}
+
+		let start_count = out.len();
+		let mut check = |index: usize| {
+			let term = &self.terms[index];
+			let expression = self.get_str(term.expression);
+			let reading = self.get_str(term.reading);
+			if pattern.is_match(expression) || (!reading.is_empty() && pattern.is_match(reading)) {
+				out.indexes.insert(index);
+			}
+		};
+
+		// Anchored patterns are narrowed using the prefix or suffix index,
+		// choosing whichever gives the smallest range.
+		let prefix = pattern.prefix();
+		let suffix = pattern.suffix();
+		let full_match = pattern.is_literal();
+		let prefix_range = self.do_search_index_range(&prefix, full_match, self.index_prefix_jp);
+		let suffix_range = self.do_search_suffix_range(&suffix);
+
+		// A non-empty anchor without matches in the index means the pattern
+		// cannot match anything
+		let no_prefix = !prefix.is_empty() && prefix_range.is_none();
+		let no_suffix = !suffix.is_empty() && suffix_range.is_none();
+		if no_prefix || no_suffix {
+			return 0;
+		}
+
+		let range = match (prefix_range, suffix_range) {
+			(Some(a), Some(b)) if b.1 - b.0 < a.1 - a.0 => Some((self.index_suffix_jp, b)),
+			(Some(a), _) => Some((self.index_prefix_jp, a)),
+			(None, Some(b)) => Some((self.index_suffix_jp, b)),
+			(None, None) => None,
+		};
+
+		if let Some((index, (sta, end))) = range {
+			for row in index[sta..=end].iter() {
+				check(row.term.into());
+			}
+			return out.len() - start_count;
+		}
+
+		// For unanchored patterns use the character index for the literal
+		// character with the fewest terms.
+		let candidates = pattern
+			.tokens
+			.iter()
+			.filter_map(|it| match it {
+				Token::Char(chr) => Some(self.get_char_index(*chr)),
+				_ => None,
+			})
+			.min_by_key(|it| it.len());
+
+		if let Some(candidates) = candidates {
+			for &index in candidates {
+				check(index.into());
+			}
+		} else {
+			// Pattern is only wildcards, so we need to check every term
+			for index in 0..self.terms.len() {
+				check(index);
+			}
+		}
+
+		out.len() - start_count
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::testing::*;
+	use super::super::ResultSet;
+	use super::Pattern;
+
+	#[test]
+	fn is_match() {
+		let pattern = Pattern::parse("食*る");
+		assert!(pattern.is_match("食べる"));
+		assert!(pattern.is_match("食る"));
+		assert!(!pattern.is_match("食べ"));
+		assert!(!pattern.is_match("べる"));
+
+		let pattern = Pattern::parse("?け?");
+		assert!(pattern.is_match("あけぼ"));
+		assert!(!pattern.is_match("けけ"));
+		assert!(!pattern.is_match("あけぼの"));
+
+		// Backtracking over repeated characters.
+		let pattern = Pattern::parse("*ab*abc");
+		assert!(pattern.is_match("aababcabc"));
+		assert!(!pattern.is_match("ababab"));
+
+		let pattern = Pattern::parse("＊的");
+		assert!(pattern.is_match("目的"));
+		assert!(!pattern.is_match("的確"));
+	}
+
+	#[test]
+	fn parse() {
+		let pattern = Pattern::parse("食**?る");
+		assert_eq!(pattern.tokens.len(), 4);
+		assert!(!pattern.is_literal());
+		assert_eq!(pattern.prefix(), "食");
+		assert_eq!(pattern.suffix(), "る");
+
+		let pattern = Pattern::parse("食べる");
+		assert!(pattern.is_literal());
+		assert_eq!(pattern.prefix(), "食べる");
+		assert_eq!(pattern.suffix(), "食べる");
+	}
+
+	#[test]
+	fn search_pattern() {
+		let mut w = writer(&[]);
+		let terms = [
+			("食べる", "たべる"),
+			("食する", "しょくする"),
+			("食堂", "しょくどう"),
+			("目的", "もくてき"),
+			("的", "まと"),
+			("酒", "さけ"),
+			("竹", "たけ"),
+			("下げる", "さげる"),
+		];
+		for &(expression, reading) in terms.iter() {
+			let term = term(&mut w, expression, reading, "", 0);
+			w.push_term(term);
+		}
+		let db = TestDB::new(w);
+		let db = db.db();
+
+		let search = |pattern: &str| {
+			let mut set = ResultSet::default();
+			let count = db.search_pattern(pattern, &mut set);
+			assert_eq!(count, set.len());
+			let mut out: Vec<_> = set
+				.iter()
+				.map(|x| db.term(x).unwrap().expression())
+				.collect();
+			out.sort();
+			out
+		};
+
+		// Anchored at the start, end or both.
+		assert_eq!(search("食*"), ["食する", "食べる", "食堂"]);
+		assert_eq!(search("*的"), ["的", "目的"]);
+		assert_eq!(search("食*る"), ["食する", "食べる"]);
+		assert_eq!(search("しょく?う"), ["食堂"]);
+		assert_eq!(search("的"), ["的"]);
+		assert!(search("x*").is_empty());
+		assert!(search("*x").is_empty());
+
+		// Unanchored patterns.
+		assert_eq!(search("?け"), ["竹", "酒"]);
+		assert_eq!(search("*げ*"), ["下げる"]);
+		assert_eq!(search("?ょく*"), ["食する", "食堂"]);
+		assert!(search("*x*").is_empty());
+
+		// Only wildcards.
+		assert_eq!(search("*").len(), terms.len());
+		assert_eq!(search("?"), ["的", "竹", "酒"]);
+		assert_eq!(search("??"), ["的", "目的", "竹", "酒", "食堂"]);
+		assert!(search("").is_empty());
+	}
+}
@@ -0,0 +1,464 @@
+//! Search queries with filters.
+
+use std::collections::BTreeSet;
+
+use super::kana;
+use super::PartOfSpeech;
+use super::ResultSet;
+use super::TermIndex;
+use super::VecHandle;
+use super::DB;
+
+/// How the [Query] text is matched against the term keys (expression,
+/// reading and romaji key).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum QueryMode {
+	/// Full key match. See [search_term](DB::search_term).
+	Exact,
+	/// Keys starting with the text. See [search_prefix](DB::search_prefix).
+	Prefix,
+	/// Keys ending with the text. See [search_suffix](DB::search_suffix).
+	Suffix,
+}
+
+/// Search query with filters for the results, built with the methods below
+/// and run with [DB::query].
+///
+/// Filters are checked for each term as the index is scanned, so only the
+/// matching terms are collected, and at most `limit` of them are kept.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Query {
+	text: String,
+	mode: QueryMode,
+	terms: bool,
+	kanji: bool,
+	required_tags: Vec<String>,
+	excluded_tags: Vec<String>,
+	sources: Vec<String>,
+	parts_of_speech: Vec<PartOfSpeech>,
+	common: bool,
+	min_frequency: u32,
+	limit: usize,
+}
+
+/// Results for a [Query].
+#[derive(Default)]
+pub struct QueryResult {
+	/// Matching term indexes, sorted by frequency.
+	pub terms: ResultSet,
+	/// Matching kanji, in order of the characters in the query text.
+	pub kanji: Vec<char>,
+}
+
+impl Query {
+	pub fn new<S: Into<String>>(text: S, mode: QueryMode) -> Query {
+		Query {
+			text: text.into(),
+			mode,
+			terms: true,
+			kanji: true,
+			required_tags: Vec::new(),
+			excluded_tags: Vec::new(),
+			sources: Vec::new(),
+			parts_of_speech: Vec::new(),
+			common: false,
+			min_frequency: 0,
+			limit: 0,
+		}
+	}
+
+	/// Query for the exact text.
+	pub fn exact<S: Into<String>>(text: S) -> Query {
+		Query::new(text, QueryMode::Exact)
+	}
+
+	/// Query for the given prefix.
+	pub fn prefix<S: Into<String>>(text: S) -> Query {
+		Query::new(text, QueryMode::Prefix)
+	}
+
+	/// Query for the given suffix.
+	pub fn suffix<S: Into<String>>(text: S) -> Query {
+		Query::new(text, QueryMode::Suffix)
+	}
+
+	/// Only returns terms (and kanji) with the given tag. Can be used multiple
+	/// times to require all the tags.
+	pub fn with_tag<S: Into<String>>(mut self, name: S) -> Query {
+		self.required_tags.push(name.into());
+		self
+	}
+
+	/// Excludes terms (and kanji) with the given tag.
+	pub fn without_tag<S: Into<String>>(mut self, name: S) -> Query {
+		self.excluded_tags.push(name.into());
+		self
+	}
+
+	/// Only returns results from the given source dictionary. Can be used
+	/// multiple times to allow any of the sources.
+	pub fn source<S: Into<String>>(mut self, name: S) -> Query {
+		self.sources.push(name.into());
+		self
+	}
+
+	/// Only returns terms with the given part of speech (see
+	/// [Term::parts_of_speech](super::Term::parts_of_speech)). Can be used
+	/// multiple times to allow any of them.
+	///
+	/// This filter does not apply to kanji.
+	pub fn part_of_speech(mut self, pos: PartOfSpeech) -> Query {
+		self.parts_of_speech.push(pos);
+		self
+	}
+
+	/// Only returns common terms (see [Term::is_common](super::Term::is_common)).
+	///
+	/// This filter does not apply to kanji.
+	pub fn common(mut self) -> Query {
+		self.common = true;
+		self
+	}
+
+	/// Only returns results with at least the given frequency (see
+	/// [Term::frequency](super::Term::frequency)). Results without frequency
+	/// information are excluded.
+	pub fn min_frequency(mut self, frequency: u32) -> Query {
+		self.min_frequency = frequency;
+		self
+	}
+
+	/// Only returns terms.
+	pub fn terms_only(mut self) -> Query {
+		self.terms = true;
+		self.kanji = false;
+		self
+	}
+
+	/// Only returns the kanji entries for the kanji characters in the query
+	/// text.
+	pub fn kanji_only(mut self) -> Query {
+		self.terms = false;
+		self.kanji = true;
+		self
+	}
+
+	/// Maximum number of terms (and kanji) to return. Since terms are sorted
+	/// by frequency, these are the most frequent matches. Zero means no limit.
+	pub fn limit(mut self, limit: usize) -> Query {
+		self.limit = limit;
+		self
+	}
+}
+
+/// Filters from a [Query] with the tag names resolved to tag indexes.
+struct Filter<'q> {
+	query: &'q Query,
+	/// For each required tag, the indexes of the tags with that name. This is
+	/// empty for a required tag that is not in the database.
+	required: Vec<Vec<usize>>,
+	excluded: Vec<usize>,
+}
+
+impl<'db> DB<'db> {
+	/// Runs a search query, returning the matching terms and kanji.
+	pub fn query<'a: 'db>(&'a self, query: &Query) -> QueryResult {
+		let tag_indexes = |name: &String| -> Vec<usize> {
+			self.tags
+				.iter()
+				.enumerate()
+				.filter(|(_, tag)| self.get_str(tag.name) == name)
+				.map(|(index, _)| index)
+				.collect()
+		};
+		let filter = Filter {
+			query,
+			required: query.required_tags.iter().map(tag_indexes).collect(),
+			excluded: query.excluded_tags.iter().flat_map(tag_indexes).collect(),
+		};
+
+		let mut result = QueryResult::default();
+		if query.terms {
+			let (index, range) = match query.mode {
+				QueryMode::Exact => (
+					self.index_prefix_jp,
+					self.do_search_index_range(&query.text, true, self.index_prefix_jp),
+				),
+				QueryMode::Prefix => (
+					self.index_prefix_jp,
+					self.do_search_index_range(&query.text, false, self.index_prefix_jp),
+				),
+				QueryMode::Suffix => (
+					self.index_suffix_jp,
+					self.do_search_suffix_range(&query.text),
+				),
+			};
+			if let Some((sta, end)) = range {
+				self.query_range(&filter, &index[sta..=end], &mut result.terms.indexes);
+			}
+		}
+
+		if query.kanji {
+			for chr in query.text.chars().filter(|&x| kana::is_kanji(x)) {
+				if query.limit > 0 && result.kanji.len() >= query.limit {
+					break;
+				}
+				if result.kanji.contains(&chr) {
+					continue;
+				}
+				if let Some(kanji) = self.kanji(chr) {
+					let item = kanji.item;
+					let frequency: u32 = item.frequency.into();
+					let matches = filter.check_tags(self, &[item.tags])
+						&& filter.check_source(self.get_str(item.source))
+						&& frequency >= query.min_frequency;
+					if matches {
+						result.kanji.push(chr);
+					}
+				}
+			}
+		}
+
+		result
+	}
+
+	/// Inserts the terms from the index rows matching the filter into `out`,
+	/// keeping only the first `limit` term indexes.
+	fn query_range<'a: 'db>(
+		&'a self,
+		filter: &Filter,
+		rows: &[TermIndex],
+		out: &mut BTreeSet<usize>,
+	) {
+		let limit = filter.query.limit;
+		for row in rows {
+			let index: usize = row.term.into();
+			if out.contains(&index) {
+				continue;
+			}
+
+			// Once the limit is reached, only a more frequent term can change
+			// the result.
+			let full = limit > 0 && out.len() >= limit;
+			if full
+				&& out
+					.iter()
+					.next_back()
+					.map(|&last| index > last)
+					.unwrap_or(false)
+			{
+				continue;
+			}
+
+			if self.check_term(filter, index) {
+				out.insert(index);
+				if limit > 0 && out.len() > limit {
+					let last = *out.iter().next_back().unwrap();
+					out.remove(&last);
+				}
+			}
+		}
+	}
+
+	fn check_term<'a: 'db>(&'a self, filter: &Filter, index: usize) -> bool {
+		let query = filter.query;
+		let item = &self.terms[index];
+
+		let frequency: u32 = item.frequency.into();
+		if frequency < query.min_frequency {
+			return false;
+		}
+		if !filter.check_source(self.get_str(item.source)) {
+			return false;
+		}
+
+		if !filter.check_tags(self, &[item.term_tags, item.definition_tags]) {
+			return false;
+		}
+
+		if query.common || !query.parts_of_speech.is_empty() {
+			let term = self.term(index).unwrap();
+			if query.common && !term.is_common() {
+				return false;
+			}
+			if !query.parts_of_speech.is_empty() {
+				let pos = term.parts_of_speech();
+				if !query.parts_of_speech.iter().any(|x| pos.contains(x)) {
+					return false;
+				}
+			}
+		}
+
+		true
+	}
+}
+
+impl<'q> Filter<'q> {
+	fn check_source(&self, source: &str) -> bool {
+		let sources = &self.query.sources;
+		sources.is_empty() || sources.iter().any(|x| x == source)
+	}
+
+	/// Checks the required and excluded tags against the tag lists.
+	fn check_tags(&self, db: &DB, tags: &[VecHandle]) -> bool {
+		let has_tag = |tag: usize| {
+			tags.iter().any(|list| {
+				let (sta, end) = list.range();
+				db.vector_data[sta..end].iter().any(|&it| {
+					let index: usize = it.into();
+					index == tag
+				})
+			})
+		};
+		let required = self
+			.required
+			.iter()
+			.all(|indexes| indexes.iter().any(|&tag| has_tag(tag)));
+		required && !self.excluded.iter().any(|&tag| has_tag(tag))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::testing::*;
+	use super::super::{TagData, VerbClass, VerbType};
+	use super::{PartOfSpeech, Query};
+
+	fn test_db() -> TestDB {
+		let mut w = writer(&[
+			("P", "popular"),
+			("n", "partOfSpeech"),
+			("v1", "partOfSpeech"),
+			("arch", "archaism"),
+		]);
+		// A second tag with the same name, from a different dictionary.
+		let tag = TagData {
+			name: w.intern("P".to_string()),
+			category: w.intern("popular".to_string()),
+			order: 0,
+			notes: w.intern("common (other)".to_string()),
+		};
+		w.push_tag(tag);
+		let (p1, p2) = (0, 4);
+
+		let terms = [
+			("東西", "とうざい", 800, &[p1][..], &["n"][..]),
+			("東京", "とうきょう", 500, &[p2], &["n"]),
+			("東", "ひがし", 100, &[], &["n"]),
+			("東北", "とうほく", 50, &[], &[]),
+			("東側", "ひがしがわ", 10, &[], &["n", "arch"]),
+			("東洋", "とうよう", 0, &[], &["v1"]),
+		];
+		for &(expression, reading, frequency, term_tags, definition_tags) in terms.iter() {
+			let mut item = term(&mut w, expression, reading, "", frequency);
+			item.term_tags = term_tags.to_vec();
+			item.definition_tags = w.get_tags(definition_tags.iter());
+			if expression == "東北" {
+				item.source = w.intern("JMnedict".to_string());
+			}
+			w.push_term(item);
+		}
+
+		let mut item = kanji(&mut w, '東', &["トウ"], &["ひがし"]);
+		item.frequency = 500;
+		item.tags = vec![p1];
+		w.push_kanji(item);
+		let item = kanji(&mut w, '京', &["キョウ"], &[]);
+		w.push_kanji(item);
+		TestDB::new(w)
+	}
+
+	#[test]
+	fn query_filters() {
+		let data = test_db();
+		let db = data.db();
+		let run = |query: Query| {
+			let result = db.query(&query.terms_only());
+			result
+				.terms
+				.iter()
+				.map(|x| db.term(x).unwrap().expression())
+				.collect::<Vec<_>>()
+		};
+		let prefix = || Query::prefix("東");
+
+		let all = ["東西", "東京", "東", "東北", "東側", "東洋"];
+		assert_eq!(run(prefix()), all);
+		assert_eq!(run(Query::exact("東")), ["東"]);
+		assert_eq!(run(Query::suffix("京")), ["東京"]);
+
+		// Both tags named `P` are required and excluded by name.
+		assert_eq!(run(prefix().with_tag("P")), ["東西", "東京"]);
+		assert_eq!(run(prefix().with_tag("P").with_tag("n")), ["東西", "東京"]);
+		assert_eq!(
+			run(prefix().without_tag("P")),
+			["東", "東北", "東側", "東洋"]
+		);
+		assert_eq!(
+			run(prefix().with_tag("n").without_tag("arch")),
+			["東西", "東京", "東"]
+		);
+		assert!(run(prefix().with_tag("unknown")).is_empty());
+		assert_eq!(run(prefix().without_tag("unknown")), all);
+
+		assert_eq!(run(prefix().source("JMnedict")), ["東北"]);
+		assert_eq!(run(prefix().source("JMdict").source("JMnedict")), all);
+		assert!(run(prefix().source("other")).is_empty());
+
+		assert_eq!(run(prefix().min_frequency(100)), ["東西", "東京", "東"]);
+		assert_eq!(run(prefix().common()), ["東西", "東京"]);
+		let v1 = PartOfSpeech::Verb(VerbClass::Ichidan, VerbType::Regular);
+		assert_eq!(run(prefix().part_of_speech(v1)), ["東洋"]);
+		assert_eq!(
+			run(prefix()
+				.part_of_speech(PartOfSpeech::Noun)
+				.min_frequency(50)),
+			["東西", "東京", "東"]
+		);
+	}
+
+	#[test]
+	fn query_limit() {
+		let data = test_db();
+		let db = data.db();
+		let run = |query: Query| {
+			let result = db.query(&query);
+			result
+				.terms
+				.iter()
+				.map(|x| db.term(x).unwrap().expression())
+				.collect::<Vec<_>>()
+		};
+
+		// The prefix index rows are sorted by key, so the most frequent term
+		// (`東西`) is the last row in the range.
+		assert_eq!(run(Query::prefix("東").limit(1)), ["東西"]);
+		assert_eq!(run(Query::prefix("東").limit(2)), ["東西", "東京"]);
+		assert_eq!(
+			run(Query::prefix("東").without_tag("P").limit(2)),
+			["東", "東北"]
+		);
+		assert_eq!(run(Query::prefix("東").limit(10)).len(), 6);
+	}
+
+	#[test]
+	fn query_kanji() {
+		let data = test_db();
+		let db = data.db();
+		let kanji = |query: Query| db.query(&query).kanji;
+
+		assert_eq!(kanji(Query::exact("東京")), ['東', '京']);
+		assert_eq!(kanji(Query::exact("東京").limit(1)), ['東']);
+		assert_eq!(kanji(Query::exact("京東京").kanji_only()), ['京', '東']);
+		assert!(kanji(Query::exact("東京").terms_only()).is_empty());
+		assert_eq!(kanji(Query::exact("東京").with_tag("P")), ['東']);
+		assert_eq!(kanji(Query::exact("東京").without_tag("P")), ['京']);
+		assert_eq!(kanji(Query::exact("東京").min_frequency(1)), ['東']);
+		assert!(kanji(Query::exact("東京").source("JMdict")).is_empty());
+
+		let result = db.query(&Query::exact("東京").kanji_only());
+		assert!(result.terms.iter().next().is_none());
+		let result = db.query(&Query::exact("東京"));
+		assert_eq!(result.terms.iter().collect::<Vec<_>>(), [1]);
+	}
+}
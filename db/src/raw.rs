@@ -78,7 +78,7 @@ impl StrHandle {
 	pub fn range(&self) -> (usize, usize) {
 		let offset: u32 = self.offset.into();
 		let length: u32 = self.length.into();
-		(offset as usize, (offset + length) as usize)
+		(offset as usize, offset.saturating_add(length) as usize)
 	}
 }
 
@@ -102,7 +102,7 @@ impl VecHandle {
 	pub fn range(&self) -> (usize, usize) {
 		let offset: u32 = self.offset.into();
 		let length: u32 = self.length.into();
-		(offset as usize, (offset + length) as usize)
+		(offset as usize, offset.saturating_add(length) as usize)
 	}
 }
 
@@ -159,3 +159,63 @@ pub struct CharIndex {
 	pub character: RawUint32,
 	pub indexes: VecHandle,
 }
+
+/// Serialized row in the sequence index.
+///
+/// Maps the `(source, sequence)` pair of a term, which is stable across
+/// database builds, to the term index.
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
+pub struct SequenceIndex {
+	pub source: RawUint32,
+	pub sequence: RawUint32,
+	pub term: RawUint32,
+}
+
+/// Serialized row in the kanji stat index.
+///
+/// Maps a kanji stat, as the stat tag index and value string, to the kanji
+/// index. Rows are sorted by the stat tag name, value and kanji index.
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
+pub struct KanjiStatIndex {
+	pub stat: RawUint32,
+	pub value: RawUint32,
+	pub kanji: RawUint32,
+}
+
+/// Serialized row in the suggestion index.
+///
+/// The prefix for the row is stored as a `key` starting with the prefix and
+/// the prefix `length` in bytes. The `keys` vector has the best keys for the
+/// prefix, as interleaved `(key, term)` pairs with the key string and the
+/// index of its most frequent term.
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
+pub struct SuggestIndex {
+	pub key: RawUint32,
+	pub length: RawUint32,
+	pub keys: VecHandle,
+}
+
+/// Serialized node in the search key trie.
+///
+/// The children of a node are stored contiguously in the node list and are
+/// sorted by character. The root node is always the first node in the list.
+#[repr(C, packed)]
+#[derive(Copy, Clone)]
+pub struct TrieNode {
+	pub character: RawUint32,
+	pub first_child: RawUint32,
+	pub child_count: RawUint32,
+	pub terms: VecHandle,
+}
+
+impl TrieNode {
+	/// Returns the `(start, end)` range of the node children in the node list.
+	pub fn children(&self) -> (usize, usize) {
+		let first: u32 = self.first_child.into();
+		let count: u32 = self.child_count.into();
+		(first as usize, first.saturating_add(count) as usize)
+	}
+}
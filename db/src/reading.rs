@@ -0,0 +1,206 @@
+//! Kana reading generation for Japanese text.
+
+use super::kana;
+use super::DB;
+
+/// Maximum length in characters of a dictionary match when segmenting text.
+const MAX_MATCH_LENGTH: usize = 24;
+
+/// Best-guess kana reading for a text. See [to_reading](DB::to_reading).
+pub struct Reading<'t> {
+	/// Full kana reading for the text.
+	pub reading: String,
+	/// Segments of the source text, each with its own reading.
+	pub segments: Vec<ReadingSegment<'t>>,
+}
+
+/// Single segment in a [Reading].
+pub struct ReadingSegment<'t> {
+	/// Segment of the source text.
+	pub text: &'t str,
+	/// Kana reading for the segment.
+	pub reading: String,
+	/// Index of the dictionary term used for the reading. This is `None` for
+	/// segments that did not match any dictionary term.
+	pub term: Option<usize>,
+}
+
+impl<'a> DB<'a> {
+	/// Generates a best-guess kana reading for the given text.
+	///
+	/// The text is segmented using the longest dictionary match at each
+	/// position, using the reading of the most frequent term matching the
+	/// segment. Kanji that do not match any term fall back to their kanji
+	/// reading, while any other characters are used as is.
+	pub fn to_reading<'t>(&self, text: &'t str) -> Reading<'t> {
+		let mut reading = String::new();
+		let mut segments = Vec::new();
+
+		let mut text = text;
+		while let Some(chr) = text.chars().next() {
+			let segment = if let Some((length, index)) = self.match_longest(text) {
+				let term = &self.terms[index];
+				let term_reading = self.get_str(term.reading);
+				let segment = &text[..length];
+				ReadingSegment {
+					text: segment,
+					reading: if !term_reading.is_empty() && segment != term_reading {
+						term_reading.to_string()
+					} else {
+						segment.to_string()
+					},
+					term: Some(index),
+				}
+			} else {
+				let segment = &text[..chr.len_utf8()];
+				ReadingSegment {
+					text: segment,
+					reading: self
+						.kanji_reading(chr)
+						.unwrap_or_else(|| segment.to_string()),
+					term: None,
+				}
+			};
+			reading.push_str(&segment.reading);
+			text = &text[segment.text.len()..];
+			segments.push(segment);
+		}
+
+		Reading { reading, segments }
+	}
+
+	/// Finds the longest prefix of `text` that matches a term expression or
+	/// reading exactly.
+	///
+	/// Returns the length in bytes of the match and the index of the most
+	/// relevant matching term, preferring terms matching by expression.
+	pub(super) fn match_longest(&self, text: &str) -> Option<(usize, usize)> {
+		let ends: Vec<usize> = text
+			.char_indices()
+			.skip(1)
+			.map(|(pos, _)| pos)
+			.chain(std::iter::once(text.len()))
+			.take(MAX_MATCH_LENGTH)
+			.collect();
+
+		for &end in ends.iter().rev() {
+			let key = &text[..end];
+			if let Some(index) = self.match_exact(key) {
+				return Some((end, index));
+			}
+		}
+
+		None
+	}
+
+	/// Returns the most relevant term with an expression or reading equal to
+	/// the given key, preferring terms matching by expression.
+	pub(super) fn match_exact(&self, key: &str) -> Option<usize> {
+		self.match_exact_by(key, |_| true)
+	}
+
+	/// Same as [match_exact](DB::match_exact), but only considers the terms
+	/// accepted by `filter`.
+	pub(super) fn match_exact_by<F: Fn(usize) -> bool>(
+		&self,
+		key: &str,
+		filter: F,
+	) -> Option<usize> {
+		let (sta, end) = self.do_search_index_range(key, true, self.index_prefix_jp)?;
+		let mut by_expression = None;
+		let mut by_reading = None;
+		for row in self.index_prefix_jp[sta..=end].iter() {
+			let index: usize = row.term.into();
+			if !filter(index) {
+				continue;
+			}
+			let term = &self.terms[index];
+			if self.get_str(term.expression) == key {
+				by_expression = Some(by_expression.map_or(index, |x| std::cmp::min(x, index)));
+			} else if self.get_str(term.reading) == key {
+				by_reading = Some(by_reading.map_or(index, |x| std::cmp::min(x, index)));
+			}
+		}
+		by_expression.or(by_reading)
+	}
+
+	/// Returns a kana reading for a single kanji, preferring kunyomi.
+	fn kanji_reading(&self, chr: char) -> Option<String> {
+		if !kana::is_kanji(chr) {
+			return None;
+		}
+		let kanji = self.kanji(chr)?;
+		let reading = kanji.kunyomi().next().or_else(|| kanji.onyomi().next())?;
+		Some(kana::kanji_reading(reading))
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::testing::*;
+	use super::MAX_MATCH_LENGTH;
+
+	#[test]
+	fn to_reading() {
+		let mut w = writer(&[]);
+		let long = "長".repeat(MAX_MATCH_LENGTH);
+		let terms = [
+			("日本", "にほん", 100),
+			("日本語", "にほんご", 50),
+			("語", "ご", 40),
+			("食べる", "たべる", 30),
+			("一ヶ月", "いっかげつ", 20),
+			("ひらがな", "", 10),
+			(long.as_str(), "ながい", 0),
+		];
+		for &(expression, reading, frequency) in terms.iter() {
+			let term = term(&mut w, expression, reading, "", frequency);
+			w.push_term(term);
+		}
+		let characters = [
+			('山', &["サン"][..], &["やま"][..]),
+			('林', &["リン"][..], &[][..]),
+			('食', &["ショク"][..], &["た.べる", "-く.う"][..]),
+			('達', &["タツ"][..], &["-たち"][..]),
+		];
+		for &(character, onyomi, kunyomi) in characters.iter() {
+			let kanji = kanji(&mut w, character, onyomi, kunyomi);
+			w.push_kanji(kanji);
+		}
+		let db = TestDB::new(w);
+		let db = db.db();
+		let reading = |text: &str| db.to_reading(text).reading;
+
+		// Longest dictionary match at each position.
+		let result = db.to_reading("日本語を食べる");
+		assert_eq!(result.reading, "にほんごをたべる");
+		let segments: Vec<_> = result
+			.segments
+			.iter()
+			.map(|x| (x.text, x.reading.as_str(), x.term.is_some()))
+			.collect();
+		assert_eq!(
+			segments,
+			[
+				("日本語", "にほんご", true),
+				("を", "を", false),
+				("食べる", "たべる", true),
+			]
+		);
+		assert_eq!(reading("日本だ"), "にほんだ");
+		assert_eq!(reading("ひらがな"), "ひらがな");
+		assert_eq!(reading(&long), "ながい");
+
+		// Kanji fall back to their kunyomi or onyomi, without okurigana or
+		// prefix markers.
+		assert_eq!(reading("山林"), "やまりん");
+		assert_eq!(reading("食"), "た");
+		assert_eq!(reading("友達"), "友たち");
+
+		// `ヶ` is part of the term, or kept as is.
+		assert_eq!(reading("一ヶ月"), "いっかげつ");
+		assert_eq!(reading("ヶ"), "ヶ");
+
+		assert_eq!(reading(""), "");
+	}
+}
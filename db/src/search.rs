@@ -1,12 +1,14 @@
 use std::collections::BTreeSet;
 
+use unicode_segmentation::UnicodeSegmentation;
+
 use super::TermIndex;
 use super::DB;
 
 /// Store the search results for a DB.
 #[derive(Default)]
 pub struct ResultSet {
-	indexes: BTreeSet<usize>,
+	pub(super) indexes: BTreeSet<usize>,
 }
 
 impl ResultSet {
@@ -41,6 +43,95 @@ impl<'a> std::iter::Iterator for ResultSetIter<'a> {
 	}
 }
 
+/// Lazy iterator over the terms matching a key or prefix, in the order of
+/// the matching keys. See [iter_prefix](DB::iter_prefix).
+///
+/// Each term is returned once, even if more than one of its keys match.
+/// This is done without keeping track of the returned terms, so the
+/// iterator uses constant memory.
+pub struct SearchIter<'db, 'a: 'db> {
+	data: &'a DB<'db>,
+	keyword: String,
+	full_match: bool,
+	/// Position in the prefix index for the next row.
+	pos: usize,
+}
+
+impl<'db, 'a: 'db> SearchIter<'db, 'a> {
+	/// Opaque position of the iterator, which can be used to resume the
+	/// search later with [seek](SearchIter::seek).
+	pub fn cursor(&self) -> usize {
+		self.pos
+	}
+
+	/// Resumes the search from a [cursor](SearchIter::cursor) returned by
+	/// a previous iterator for the same search.
+	pub fn seek(mut self, cursor: usize) -> SearchIter<'db, 'a> {
+		let index = self.data.index_prefix_jp;
+		self.pos = std::cmp::max(self.pos, std::cmp::min(cursor, index.len()));
+		self
+	}
+
+	fn matches(&self, key: &str) -> bool {
+		if self.full_match {
+			key == self.keyword
+		} else {
+			key.starts_with(&self.keyword)
+		}
+	}
+
+	/// Returns true if the row at `pos` is the first one for its term. Rows
+	/// are sorted by key, so that is the row for the smallest matching key
+	/// of the term.
+	fn is_first(&self, pos: usize) -> bool {
+		let db = self.data;
+		let row = &db.index_prefix_jp[pos];
+		let key = db.get_str(row.key);
+		let index: usize = row.term.into();
+		let term = &db.terms[index];
+
+		let keys = [term.expression, term.reading, term.search_key];
+		let smaller = keys.iter().any(|&other| {
+			let other = db.get_str(other);
+			!other.is_empty() && other < key && self.matches(other)
+		});
+		if smaller {
+			return false;
+		}
+
+		// Equal keys for the same term are adjacent in the index.
+		if pos > 0 {
+			let prev = &db.index_prefix_jp[pos - 1];
+			let prev_index: usize = prev.term.into();
+			if prev_index == index && db.get_str(prev.key) == key {
+				return false;
+			}
+		}
+		true
+	}
+}
+
+impl<'db, 'a: 'db> std::iter::Iterator for SearchIter<'db, 'a> {
+	type Item = usize;
+
+	fn next(&mut self) -> Option<usize> {
+		let index = self.data.index_prefix_jp;
+		while self.pos < index.len() {
+			let pos = self.pos;
+			let row = &index[pos];
+			if !self.matches(self.data.get_str(row.key)) {
+				self.pos = index.len();
+				break;
+			}
+			self.pos += 1;
+			if self.is_first(pos) {
+				return Some(row.term.into());
+			}
+		}
+		None
+	}
+}
+
 impl<'a> DB<'a> {
 	/// Search for an exact term in the database inserting the found term
 	/// indexes into the `out` result set.
@@ -58,6 +149,73 @@ impl<'a> DB<'a> {
 		self.do_search_index(prefix, false, self.index_prefix_jp, out)
 	}
 
+	/// Lazy version of [search_term](DB::search_term). See [SearchIter].
+	pub fn iter_term<'b: 'a, S: AsRef<str>>(&'b self, term: S) -> SearchIter<'a, 'b> {
+		self.search_iter(term.as_ref(), true)
+	}
+
+	/// Lazy version of [search_prefix](DB::search_prefix), returning the terms
+	/// in the order of their keys instead of by frequency. See [SearchIter].
+	///
+	/// Unlike `search_prefix`, the cost of this is proportional to the number
+	/// of terms consumed from the iterator, so it can be used with broad
+	/// prefixes (e.g. a single kana) when only a few results are needed.
+	pub fn iter_prefix<'b: 'a, S: AsRef<str>>(&'b self, prefix: S) -> SearchIter<'a, 'b> {
+		self.search_iter(prefix.as_ref(), false)
+	}
+
+	fn search_iter<'b: 'a>(&'b self, keyword: &str, full_match: bool) -> SearchIter<'a, 'b> {
+		let index = self.index_prefix_jp;
+		let pos = if !keyword.is_empty() {
+			index.partition_point(|row| self.get_str(row.key) < keyword)
+		} else {
+			index.len()
+		};
+		SearchIter {
+			data: self,
+			keyword: keyword.to_string(),
+			full_match,
+			pos,
+		}
+	}
+
+	/// Search for terms in the database by the given suffix inserting the
+	/// found term indexes into the `out` result set.
+	///
+	/// Returns the number of matches.
+	pub fn search_suffix<S: AsRef<str>>(&self, suffix: S, out: &mut ResultSet) -> usize {
+		let range = self.do_search_suffix_range(suffix);
+		self.insert_range(self.index_suffix_jp, range, out)
+	}
+
+	/// Search for terms with a glossary entry containing the given text as
+	/// whole words (case insensitive, e.g. `friend` matches `close friend`
+	/// but not `friendly`) inserting the found term indexes into the `out`
+	/// result set.
+	///
+	/// There is no index for the glossary, so this is a linear scan over all
+	/// terms and is much slower than the other searches.
+	///
+	/// Returns the number of matches.
+	pub fn search_glossary<S: AsRef<str>>(&self, text: S, out: &mut ResultSet) -> usize {
+		let text = text.as_ref().trim().to_lowercase();
+		if text.is_empty() {
+			return 0;
+		}
+
+		let start_count = out.len();
+		for (index, term) in self.terms.iter().enumerate() {
+			let (sta, end) = term.glossary.range();
+			let found = self.vector_data[sta..end]
+				.iter()
+				.any(|&it| contains_words(&self.get_str(it).to_lowercase(), &text));
+			if found {
+				out.indexes.insert(index);
+			}
+		}
+		out.len() - start_count
+	}
+
 	fn do_search_index<S: AsRef<str>>(
 		&self,
 		keyword: S,
@@ -65,10 +223,20 @@ impl<'a> DB<'a> {
 		index: &[TermIndex],
 		out: &mut ResultSet,
 	) -> usize {
-		if let Some((sta, end)) = self.do_search_index_range(keyword, full_match, index) {
+		let range = self.do_search_index_range(keyword, full_match, index);
+		self.insert_range(index, range, out)
+	}
+
+	fn insert_range(
+		&self,
+		index: &[TermIndex],
+		range: Option<(usize, usize)>,
+		out: &mut ResultSet,
+	) -> usize {
+		if let Some((sta, end)) = range {
 			let start_count = out.len();
-			for index in sta..=end {
-				let index: usize = self.index_prefix_jp[index].term.into();
+			for row in index[sta..=end].iter() {
+				let index: usize = row.term.into();
 				out.indexes.insert(index);
 			}
 			out.len() - start_count
@@ -77,9 +245,51 @@ impl<'a> DB<'a> {
 		}
 	}
 
+	/// Searches the given suffix in the suffix index.
+	///
+	/// Keys in the suffix index are sorted by their reversed string, so the
+	/// search is just a prefix search using the reversed suffix.
+	pub(super) fn do_search_suffix_range<S: AsRef<str>>(
+		&self,
+		suffix: S,
+	) -> Option<(usize, usize)> {
+		let suffix: String = suffix.as_ref().graphemes(true).rev().collect();
+		let index = self.index_suffix_jp;
+		let rev_key =
+			|row: &TermIndex| -> String { self.get_str(row.key).graphemes(true).rev().collect() };
+
+		if !suffix.is_empty() {
+			let cmp = |it: &TermIndex| {
+				let other = rev_key(it);
+				if other.starts_with(&suffix) {
+					std::cmp::Ordering::Equal
+				} else {
+					other.cmp(&suffix)
+				}
+			};
+
+			if let Ok(pos) = index.binary_search_by(cmp) {
+				let last = index.len() - 1;
+				let mut sta = pos;
+				let mut end = pos;
+				while sta > 0 && rev_key(&index[sta - 1]).starts_with(&suffix) {
+					sta -= 1;
+				}
+				while end < last && rev_key(&index[end + 1]).starts_with(&suffix) {
+					end += 1;
+				}
+				Some((sta, end))
+			} else {
+				None
+			}
+		} else {
+			None
+		}
+	}
+
 	/// Searches the given keyword in the provided index. If `full_match` is
 	/// true, only matches the full term, otherwise does a prefix search.
-	fn do_search_index_range<S: AsRef<str>>(
+	pub(super) fn do_search_index_range<S: AsRef<str>>(
 		&self,
 		keyword: S,
 		full_match: bool,
@@ -113,15 +323,21 @@ impl<'a> DB<'a> {
 				let mut sta = pos;
 				let mut end = pos;
 
-				// In prefix mode, expand the result range to include all
-				// prefixed results
-				if !full_match {
-					while sta > 0 && self.get_str(index[sta - 1].key).starts_with(keyword) {
-						sta -= 1;
-					}
-					while end < last && self.get_str(index[end + 1].key).starts_with(keyword) {
-						end += 1;
+				// Expand the result range to include all matching results (in
+				// prefix mode, all prefixed results)
+				let matches = |row: &TermIndex| {
+					let other = self.get_str(row.key);
+					if full_match {
+						other == keyword
+					} else {
+						other.starts_with(keyword)
 					}
+				};
+				while sta > 0 && matches(&index[sta - 1]) {
+					sta -= 1;
+				}
+				while end < last && matches(&index[end + 1]) {
+					end += 1;
 				}
 
 				Some((sta, end))
@@ -133,3 +349,105 @@ impl<'a> DB<'a> {
 		}
 	}
 }
+
+/// Returns true if `text` contains `words` delimited by non-alphanumeric
+/// characters or the text boundaries.
+fn contains_words(text: &str, words: &str) -> bool {
+	let is_word = |chr: Option<char>| chr.map(|x| x.is_alphanumeric()).unwrap_or(false);
+	let mut offset = 0;
+	while let Some(pos) = text[offset..].find(words) {
+		let sta = offset + pos;
+		let end = sta + words.len();
+		if !is_word(text[..sta].chars().next_back()) && !is_word(text[end..].chars().next()) {
+			return true;
+		}
+		offset = sta + text[sta..].chars().next().unwrap().len_utf8();
+	}
+	false
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::testing::*;
+	use super::ResultSet;
+
+	fn test_db() -> TestDB {
+		let mut w = writer(&[]);
+		let terms = [
+			("東京", "とうきょう", "toukyou", 100),
+			("京都", "きょうと", "kyouto", 50),
+			("東", "ひがし", "higashi", 20),
+			("お茶", "おちゃ", "ocha", 10),
+			("お菓子", "おかし", "okashi", 5),
+			("とうきょう", "とうきょう", "toukyou", 2),
+			("東口", "ひがしぐち", "higashiguchi", 0),
+		];
+		for &(expression, reading, search_key, frequency) in terms.iter() {
+			let term = term(&mut w, expression, reading, search_key, frequency);
+			w.push_term(term);
+		}
+		TestDB::new(w)
+	}
+
+	#[test]
+	fn search_iter() {
+		let data = test_db();
+		let db = data.db();
+		let expressions = |iter: super::SearchIter| -> Vec<&str> {
+			iter.map(|x| db.term(x).unwrap().expression()).collect()
+		};
+		let prefix = |text: &str| expressions(db.iter_prefix(text));
+		let exact = |text: &str| expressions(db.iter_term(text));
+		let none: Vec<&str> = Vec::new();
+
+		// Terms are in the order of the keys, each only once.
+		assert_eq!(prefix("東"), ["東", "東京", "東口"]);
+		assert_eq!(prefix("と"), ["東京", "とうきょう"]);
+		assert_eq!(prefix("tou"), ["東京", "とうきょう"]);
+		assert_eq!(prefix("お"), ["お菓子", "お茶"]);
+		assert_eq!(prefix("ひがし"), ["東", "東口"]);
+		assert_eq!(prefix("x"), none);
+		assert_eq!(prefix(""), none);
+
+		assert_eq!(exact("東"), ["東"]);
+		assert_eq!(exact("とうきょう"), ["東京", "とうきょう"]);
+		assert_eq!(exact("おち"), none);
+
+		// Same terms as the eager search.
+		for prefix in ["東", "と", "t", "o", "お", "ひ", "きょう", "k"].iter() {
+			let mut set = ResultSet::default();
+			db.search_prefix(prefix, &mut set);
+			let mut found: Vec<usize> = db.iter_prefix(prefix).collect();
+			found.sort();
+			assert_eq!(found, set.iter().collect::<Vec<_>>(), "prefix {}", prefix);
+		}
+	}
+
+	#[test]
+	fn cursor() {
+		let data = test_db();
+		let db = data.db();
+
+		for prefix in ["東", "と", "t", "お", "o", "ひがし"].iter() {
+			let all: Vec<usize> = db.iter_prefix(prefix).collect();
+			assert!(all.len() > 1);
+			for count in 0..=all.len() {
+				let mut iter = db.iter_prefix(prefix);
+				let mut found: Vec<usize> = iter.by_ref().take(count).collect();
+				let cursor = iter.cursor();
+				found.extend(db.iter_prefix(prefix).seek(cursor));
+				assert_eq!(found, all, "prefix {} after {}", prefix, count);
+			}
+		}
+
+		// An exhausted iterator resumes as empty.
+		let mut iter = db.iter_prefix("東");
+		assert_eq!(iter.by_ref().count(), 3);
+		assert_eq!(db.iter_prefix("東").seek(iter.cursor()).count(), 0);
+
+		// Cursors never move the search before its first row or past the end.
+		let all: Vec<usize> = db.iter_prefix("東").collect();
+		assert_eq!(db.iter_prefix("東").seek(0).collect::<Vec<_>>(), all);
+		assert_eq!(db.iter_prefix("東").seek(usize::MAX).count(), 0);
+	}
+}
@@ -0,0 +1,140 @@
+//! Serialization of the database views (requires the `serde` feature).
+//!
+//! The views serialize to the same schema as their owned counterparts (see
+//! [OwnedTerm](super::OwnedTerm), [OwnedKanji](super::OwnedKanji) and
+//! [OwnedTag](super::OwnedTag)), without copying the data:
+//!
+//! - Tag: `name`, `category`, `notes` and `order`.
+//! - Term: `index`, `expression`, `reading`, `search_key`, `score`,
+//!   `sequence`, `frequency` (or null), `source`, `glossary` (list of
+//!   strings), `rules`, `term_tags` and `definition_tags` (lists of tags).
+//! - Kanji: `character`, `frequency` (or null), `source`, `meanings`,
+//!   `onyomi`, `kunyomi` (lists of strings), `tags` (list of tags) and
+//!   `stats` (list of `[name, info]` pairs).
+//! - ResultSet: list of term indexes.
+
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use super::{Kanji, ResultSet, Tag, Term};
+
+/// Serializes the items from an iterator as a sequence.
+struct Seq<F>(F);
+
+impl<F, I> Serialize for Seq<F>
+where
+	F: Fn() -> I,
+	I: Iterator,
+	I::Item: Serialize,
+{
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_seq((self.0)())
+	}
+}
+
+impl<'db, 'a: 'db> Serialize for Tag<'db, 'a> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let mut out = serializer.serialize_struct("Tag", 4)?;
+		out.serialize_field("name", self.name())?;
+		out.serialize_field("category", self.category())?;
+		out.serialize_field("notes", self.notes())?;
+		out.serialize_field("order", &self.order())?;
+		out.end()
+	}
+}
+
+impl<'db, 'a: 'db> Serialize for Term<'db, 'a> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let data = self.data;
+		let item = self.item;
+		let mut out = serializer.serialize_struct("Term", 12)?;
+		out.serialize_field("index", &self.index())?;
+		out.serialize_field("expression", self.expression())?;
+		out.serialize_field("reading", self.reading())?;
+		out.serialize_field("search_key", self.search_key())?;
+		out.serialize_field("score", &self.score())?;
+		out.serialize_field("sequence", &self.sequence())?;
+		out.serialize_field("frequency", &self.frequency())?;
+		out.serialize_field("source", self.source())?;
+		out.serialize_field("glossary", &Seq(|| data.get_strs(item.glossary)))?;
+		out.serialize_field("rules", &Seq(|| data.get_tags(item.rules)))?;
+		out.serialize_field("term_tags", &Seq(|| data.get_tags(item.term_tags)))?;
+		out.serialize_field(
+			"definition_tags",
+			&Seq(|| data.get_tags(item.definition_tags)),
+		)?;
+		out.end()
+	}
+}
+
+impl<'db, 'a: 'db> Serialize for Kanji<'db, 'a> {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		let data = self.data;
+		let item = self.item;
+		let mut out = serializer.serialize_struct("Kanji", 8)?;
+		out.serialize_field("character", &self.character())?;
+		out.serialize_field("frequency", &self.frequency())?;
+		out.serialize_field("source", self.source())?;
+		out.serialize_field("meanings", &Seq(|| data.get_strs(item.meanings)))?;
+		out.serialize_field("onyomi", &Seq(|| data.get_strs(item.onyomi)))?;
+		out.serialize_field("kunyomi", &Seq(|| data.get_strs(item.kunyomi)))?;
+		out.serialize_field("tags", &Seq(|| data.get_tags(item.tags)))?;
+		out.serialize_field(
+			"stats",
+			&Seq(|| {
+				data.get_stats(item.stats)
+					.map(|(tag, info)| (tag.name(), info))
+			}),
+		)?;
+		out.end()
+	}
+}
+
+impl Serialize for ResultSet {
+	fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+		serializer.collect_seq(self.iter())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::testing::*;
+	use serde_json::to_value;
+
+	fn test_db() -> TestDB {
+		let mut w = writer(&[("P", "popular"), ("n", "partOfSpeech"), ("grade", "misc")]);
+		let mut item = term(&mut w, "東京", "とうきょう", "toukyou", 100);
+		item.term_tags = w.get_tags(["P"]);
+		item.definition_tags = w.get_tags(["n"]);
+		w.push_term(item);
+
+		let mut item = kanji(&mut w, '東', &["トウ"], &["ひがし"]);
+		item.frequency = 500;
+		item.tags = w.get_tags(["P"]);
+		item.stats = vec![(w.get_tag("grade"), w.intern("2".to_string()))];
+		w.push_kanji(item);
+		TestDB::new(w)
+	}
+
+	#[test]
+	fn owned_schema() {
+		let data = test_db();
+		let db = data.db();
+
+		let term = db.term(0).unwrap();
+		assert_eq!(
+			to_value(&term).unwrap(),
+			to_value(term.to_owned_term()).unwrap()
+		);
+
+		let kanji = db.kanji('東').unwrap();
+		let value = to_value(&kanji).unwrap();
+		assert_eq!(value, to_value(kanji.to_owned_kanji()).unwrap());
+		assert_eq!(value["stats"], serde_json::json!([["grade", "2"]]));
+
+		let tag = db.tags().find(|x| x.name() == "P").unwrap();
+		assert_eq!(
+			to_value(&tag).unwrap(),
+			to_value(tag.to_owned_tag()).unwrap()
+		);
+	}
+}
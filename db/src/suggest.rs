@@ -0,0 +1,157 @@
+//! Search suggestions for partial input.
+
+use super::SuggestIndex;
+use super::DB;
+
+/// Key suggested for a search prefix. See [suggest](DB::suggest).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Suggestion<'db> {
+	/// Expression, reading or romaji key starting with the prefix.
+	pub key: &'db str,
+	/// Index of the most frequent term with this key.
+	pub term: usize,
+}
+
+impl<'db> DB<'db> {
+	/// Returns up to `count` distinct keys (expressions, readings and romaji
+	/// keys) starting with `prefix`, ranked by their most frequent term.
+	///
+	/// Suggestions for short prefixes, which would require scanning a large
+	/// part of the index, are precomputed by the [Writer](super::Writer) for
+	/// up to 20 keys. A larger `count` for those prefixes is supported but
+	/// slower.
+	pub fn suggest<S: AsRef<str>>(&self, prefix: S, count: usize) -> Vec<Suggestion<'db>> {
+		let prefix = prefix.as_ref();
+		if prefix.is_empty() || count == 0 {
+			return Vec::new();
+		}
+
+		let row = self
+			.index_suggest
+			.binary_search_by(|row| self.get_suggest_prefix(row).cmp(prefix));
+		if let Ok(row) = row {
+			let (sta, end) = self.index_suggest[row].keys.range();
+			if count <= (end - sta) / 2 {
+				return self.vector_data[sta..end]
+					.chunks(2)
+					.take(count)
+					.map(|pair| Suggestion {
+						key: self.get_str(pair[0]),
+						term: pair[1].into(),
+					})
+					.collect();
+			}
+		}
+
+		// Keys in the prefix index are sorted, so equal keys are adjacent.
+		let mut keys: Vec<(usize, Suggestion<'db>)> = Vec::new();
+		if let Some((sta, end)) = self.do_search_index_range(prefix, false, self.index_prefix_jp) {
+			for row in self.index_prefix_jp[sta..=end].iter() {
+				let key = self.get_str(row.key);
+				let term: usize = row.term.into();
+				match keys.last_mut() {
+					Some(last) if last.1.key == key => {
+						last.1.term = std::cmp::min(last.1.term, term);
+					}
+					_ => keys.push((keys.len(), Suggestion { key, term })),
+				}
+			}
+		}
+
+		keys.sort_by_key(|x| (x.1.term, x.0));
+		keys.into_iter().take(count).map(|x| x.1).collect()
+	}
+
+	fn get_suggest_prefix(&self, row: &SuggestIndex) -> &'db str {
+		let length: usize = row.length.into();
+		&self.get_str(row.key)[..length]
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::testing::*;
+	use super::super::writer::{SUGGEST_MIN_ROWS, SUGGEST_SIZE};
+	use super::super::DB;
+	use super::Suggestion;
+
+	/// Returns a database with enough keys starting with `あ` and `a` for
+	/// those prefixes to be precomputed, and a few starting with `い`.
+	fn test_db() -> TestDB {
+		let mut w = writer(&[]);
+		for i in 0..SUGGEST_MIN_ROWS + 100 {
+			let expression = format!("あ{:04}", i);
+			let search_key = format!("a{:04}", i);
+			let term = term(&mut w, &expression, "", &search_key, i as u32);
+			w.push_term(term);
+		}
+
+		// Duplicated keys are suggested once, for the most frequent term.
+		let duplicate = term(&mut w, "あ0005", "", "", 5000);
+		w.push_term(duplicate);
+		for i in 0..SUGGEST_SIZE + 10 {
+			let expression = format!("い{:02}", i);
+			let term = term(&mut w, &expression, "", "", (i % 7) as u32);
+			w.push_term(term);
+		}
+		TestDB::new(w)
+	}
+
+	/// Returns the expected suggestions by checking every term.
+	fn expected<'db>(db: &DB<'db>, prefix: &str, count: usize) -> Vec<Suggestion<'db>> {
+		let mut keys: Vec<Suggestion> = Vec::new();
+		for index in 0..db.term_count() {
+			let term = &db.terms[index];
+			for &key in [term.expression, term.reading, term.search_key].iter() {
+				let key = db.get_str(key);
+				if key.is_empty() || !key.starts_with(prefix) {
+					continue;
+				}
+				match keys.iter_mut().find(|x| x.key == key) {
+					Some(found) => found.term = std::cmp::min(found.term, index),
+					None => keys.push(Suggestion { key, term: index }),
+				}
+			}
+		}
+		keys.sort_by_key(|x| (x.term, x.key));
+		keys.truncate(count);
+		keys
+	}
+
+	fn is_precomputed(db: &DB, prefix: &str) -> bool {
+		db.index_suggest
+			.iter()
+			.any(|row| db.get_suggest_prefix(row) == prefix)
+	}
+
+	#[test]
+	fn suggest() {
+		let data = test_db();
+		let db = data.db();
+
+		assert!(is_precomputed(&db, "あ"));
+		assert!(is_precomputed(&db, "a"));
+		assert!(is_precomputed(&db, "あ0"));
+		assert!(!is_precomputed(&db, "あ1"));
+		assert!(!is_precomputed(&db, "い"));
+
+		// Counts up to the precomputed size use the suggestion index, larger
+		// ones and prefixes not in the index scan the prefix index.
+		let counts = [1, 2, SUGGEST_SIZE - 1, SUGGEST_SIZE, SUGGEST_SIZE + 1, 100];
+		for &prefix in ["あ", "a", "あ0", "あ1", "あ00", "い", "い1"].iter() {
+			for &count in counts.iter() {
+				let found = db.suggest(prefix, count);
+				assert_eq!(found, expected(&db, prefix, count), "{} {}", prefix, count);
+			}
+		}
+
+		let found = db.suggest("あ", 2);
+		assert_eq!(found[0].key, "あ0005");
+		assert_eq!(found[1].key, "あ1099");
+		assert_eq!(db.suggest("い", 100).len(), SUGGEST_SIZE + 10);
+
+		assert!(db.suggest("あ", 0).is_empty());
+		assert!(db.suggest("", 10).is_empty());
+		assert!(db.suggest("う", 10).is_empty());
+	}
+}
@@ -0,0 +1,420 @@
+//! Typed classification of the dictionary tags.
+//!
+//! The tag names and categories are the ones used by the Yomichan JMdict and
+//! KANJIDIC dictionaries. Tags that are not recognized are kept as `Other`
+//! with the original name.
+
+use super::Tag;
+use super::Term;
+
+/// Category for a [Tag] (see [Tag::category]).
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum TagCategory {
+	/// Part of speech tags (e.g. `n` or `v5k`).
+	PartOfSpeech,
+	/// Common terms (the `P` tag).
+	Popular,
+	/// Frequently used terms.
+	Frequent,
+	/// Archaic or obsolete terms.
+	Archaism,
+	/// Expressions (e.g. `exp`).
+	Expression,
+	/// Name dictionary tags.
+	Name,
+	/// Dictionary references (e.g. in KANJIDIC).
+	Dictionary,
+	/// Frequency information.
+	Frequency,
+	/// Search tags.
+	Search,
+	/// Tags without a category.
+	Unspecified,
+	/// Any other category, with the original name.
+	Other(String),
+}
+
+impl TagCategory {
+	pub fn parse(category: &str) -> TagCategory {
+		match category {
+			"partOfSpeech" => TagCategory::PartOfSpeech,
+			"popular" => TagCategory::Popular,
+			"frequent" => TagCategory::Frequent,
+			"archaism" => TagCategory::Archaism,
+			"expression" => TagCategory::Expression,
+			"name" => TagCategory::Name,
+			"dictionary" => TagCategory::Dictionary,
+			"frequency" => TagCategory::Frequency,
+			"search" => TagCategory::Search,
+			"" => TagCategory::Unspecified,
+			other => TagCategory::Other(other.to_string()),
+		}
+	}
+}
+
+/// Part of speech for a term, from the JMdict tags.
+#[derive(Clone, Debug, PartialEq, Eq, Hash)]
+pub enum PartOfSpeech {
+	/// Noun (`n`).
+	Noun,
+	/// Adverbial noun (`n-adv`).
+	AdverbialNoun,
+	/// Temporal noun (`n-t`).
+	TemporalNoun,
+	/// Noun used as a prefix (`n-pref`).
+	NounPrefix,
+	/// Noun used as a suffix (`n-suf`).
+	NounSuffix,
+	/// Pronoun (`pn`).
+	Pronoun,
+	/// Verb (`v1`, `v5k`, `vs`...).
+	Verb(VerbClass, VerbType),
+	/// Transitive verb (`vt`).
+	TransitiveVerb,
+	/// Intransitive verb (`vi`).
+	IntransitiveVerb,
+	/// Adjective (`adj-i`, `adj-na`...).
+	Adjective(AdjectiveClass),
+	/// Adverb (`adv`).
+	Adverb,
+	/// Adverb taking the `と` particle (`adv-to`).
+	AdverbTo,
+	/// Auxiliary (`aux`).
+	Auxiliary,
+	/// Auxiliary verb (`aux-v`).
+	AuxiliaryVerb,
+	/// Auxiliary adjective (`aux-adj`).
+	AuxiliaryAdjective,
+	/// Conjunction (`conj`).
+	Conjunction,
+	/// Copula (`cop`, `cop-da`).
+	Copula,
+	/// Counter (`ctr`).
+	Counter,
+	/// Expression (`exp`).
+	Expression,
+	/// Interjection (`int`).
+	Interjection,
+	/// Numeric (`num`).
+	Numeric,
+	/// Particle (`prt`).
+	Particle,
+	/// Prefix (`pref`).
+	Prefix,
+	/// Suffix (`suf`).
+	Suffix,
+	/// Unclassified (`unc`).
+	Unclassified,
+	/// Any other tag, with the original name.
+	Other(String),
+}
+
+/// Conjugation class for a verb.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum VerbClass {
+	/// Ichidan verb (`v1`).
+	Ichidan,
+	/// Godan verb (`v5*`), with the dictionary form ending (e.g. `く` for
+	/// `v5k`). This includes the `vs-c` verbs (e.g. 略す), which conjugate as
+	/// godan verbs ending in `す`.
+	Godan(char),
+	/// The classical 得る read as `うる` (`v5uru`). Other than the dictionary
+	/// form, it conjugates as the ichidan 得る (`える`).
+	Uru,
+	/// Suru verb (`vs*`).
+	Suru,
+	/// Kuru verb (`vk`).
+	Kuru,
+	/// Zuru verb (`vz`).
+	Zuru,
+	/// Archaic nidan and yodan verbs (`v2*` and `v4*`).
+	Archaic,
+	/// Irregular nu and ru verbs (`vn` and `vr`) and unspecified verbs
+	/// (`v-unspec`).
+	Irregular,
+}
+
+/// Conjugation type within a [VerbClass].
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum VerbType {
+	/// Regular conjugation for the class.
+	Regular,
+	/// Special conjugation: `v1-s` (e.g. くれる), `v5k-s` (行く), `v5r-i`
+	/// (ある), `v5u-s` (e.g. 問う), `v5aru` (e.g. ござる) and `vs-s` (e.g.
+	/// 愛する).
+	Special,
+	/// Noun or participle taking `する` (`vs`). The term expression does not
+	/// include the `する`.
+	Noun,
+}
+
+/// Class for an adjective.
+#[derive(Copy, Clone, Debug, PartialEq, Eq, Hash)]
+pub enum AdjectiveClass {
+	/// I-adjective (`adj-i`).
+	I,
+	/// The irregular いい/よい adjective class (`adj-ix`).
+	Ii,
+	/// Na-adjective (`adj-na`).
+	Na,
+	/// Noun taking the `の` particle (`adj-no`).
+	No,
+	/// Pre-noun adjectival (`adj-pn`).
+	PreNoun,
+	/// Taru adjective (`adj-t`).
+	Taru,
+	/// Noun or verb acting prenominally (`adj-f`).
+	Prenominal,
+	/// Archaic ku, shiku and nari adjectives (`adj-ku`, `adj-shiku` and
+	/// `adj-nari`).
+	Archaic,
+}
+
+impl PartOfSpeech {
+	/// Parses a part of speech tag name. Tags that are not recognized are
+	/// returned as `Other`.
+	pub fn parse(name: &str) -> PartOfSpeech {
+		match name {
+			"n" => PartOfSpeech::Noun,
+			"n-adv" => PartOfSpeech::AdverbialNoun,
+			"n-t" => PartOfSpeech::TemporalNoun,
+			"n-pref" => PartOfSpeech::NounPrefix,
+			"n-suf" => PartOfSpeech::NounSuffix,
+			"pn" => PartOfSpeech::Pronoun,
+			"vt" => PartOfSpeech::TransitiveVerb,
+			"vi" => PartOfSpeech::IntransitiveVerb,
+			"adj-i" => PartOfSpeech::Adjective(AdjectiveClass::I),
+			"adj-ix" => PartOfSpeech::Adjective(AdjectiveClass::Ii),
+			"adj-na" => PartOfSpeech::Adjective(AdjectiveClass::Na),
+			"adj-no" => PartOfSpeech::Adjective(AdjectiveClass::No),
+			"adj-pn" => PartOfSpeech::Adjective(AdjectiveClass::PreNoun),
+			"adj-t" => PartOfSpeech::Adjective(AdjectiveClass::Taru),
+			"adj-f" => PartOfSpeech::Adjective(AdjectiveClass::Prenominal),
+			"adj-ku" | "adj-shiku" | "adj-nari" => PartOfSpeech::Adjective(AdjectiveClass::Archaic),
+			"adv" => PartOfSpeech::Adverb,
+			"adv-to" => PartOfSpeech::AdverbTo,
+			"aux" => PartOfSpeech::Auxiliary,
+			"aux-v" => PartOfSpeech::AuxiliaryVerb,
+			"aux-adj" => PartOfSpeech::AuxiliaryAdjective,
+			"conj" => PartOfSpeech::Conjunction,
+			"cop" | "cop-da" => PartOfSpeech::Copula,
+			"ctr" => PartOfSpeech::Counter,
+			"exp" => PartOfSpeech::Expression,
+			"int" => PartOfSpeech::Interjection,
+			"num" => PartOfSpeech::Numeric,
+			"prt" => PartOfSpeech::Particle,
+			"pref" => PartOfSpeech::Prefix,
+			"suf" => PartOfSpeech::Suffix,
+			"unc" => PartOfSpeech::Unclassified,
+			_ => match parse_verb(name) {
+				Some((class, kind)) => PartOfSpeech::Verb(class, kind),
+				None => PartOfSpeech::Other(name.to_string()),
+			},
+		}
+	}
+
+	/// Returns true for the `Other` variant.
+	pub fn is_other(&self) -> bool {
+		matches!(self, PartOfSpeech::Other(_))
+	}
+
+	/// Returns true for any verb.
+	pub fn is_verb(&self) -> bool {
+		matches!(
+			self,
+			PartOfSpeech::Verb(..)
+				| PartOfSpeech::TransitiveVerb
+				| PartOfSpeech::IntransitiveVerb
+				| PartOfSpeech::AuxiliaryVerb
+		)
+	}
+}
+
+fn parse_verb(name: &str) -> Option<(VerbClass, VerbType)> {
+	let verb = match name {
+		"v1" => (VerbClass::Ichidan, VerbType::Regular),
+		"v1-s" => (VerbClass::Ichidan, VerbType::Special),
+		"v5k-s" => (VerbClass::Godan('く'), VerbType::Special),
+		"v5r-i" => (VerbClass::Godan('る'), VerbType::Special),
+		"v5u-s" => (VerbClass::Godan('う'), VerbType::Special),
+		"v5aru" => (VerbClass::Godan('る'), VerbType::Special),
+		"v5uru" => (VerbClass::Uru, VerbType::Regular),
+		"vs" => (VerbClass::Suru, VerbType::Noun),
+		"vs-i" => (VerbClass::Suru, VerbType::Regular),
+		"vs-s" => (VerbClass::Suru, VerbType::Special),
+		"vs-c" => (VerbClass::Godan('す'), VerbType::Regular),
+		"vk" => (VerbClass::Kuru, VerbType::Regular),
+		"vz" => (VerbClass::Zuru, VerbType::Regular),
+		"vn" | "vr" | "v-unspec" => (VerbClass::Irregular, VerbType::Regular),
+		_ if name.starts_with("v2") || name.starts_with("v4") => {
+			(VerbClass::Archaic, VerbType::Regular)
+		}
+		_ if name.starts_with("v5") && name.len() == 3 => {
+			let ending = match &name[2..] {
+				"b" => 'ぶ',
+				"g" => 'ぐ',
+				"k" => 'く',
+				"m" => 'む',
+				"n" => 'ぬ',
+				"r" => 'る',
+				"s" => 'す',
+				"t" => 'つ',
+				"u" => 'う',
+				_ => return None,
+			};
+			(VerbClass::Godan(ending), VerbType::Regular)
+		}
+		_ => return None,
+	};
+	Some(verb)
+}
+
+impl<'db, 'a: 'db> Tag<'db, 'a> {
+	/// Typed tag category. See [Tag::category].
+	pub fn category_type(&self) -> TagCategory {
+		TagCategory::parse(self.category())
+	}
+
+	/// Part of speech for the tag, if the tag is in the part of speech
+	/// category or is a known part of speech tag.
+	pub fn part_of_speech(&self) -> Option<PartOfSpeech> {
+		let pos = PartOfSpeech::parse(self.name());
+		if !pos.is_other() || self.category_type() == TagCategory::PartOfSpeech {
+			Some(pos)
+		} else {
+			None
+		}
+	}
+}
+
+impl<'db, 'a: 'db> Term<'db, 'a> {
+	/// Parts of speech for the term, from the term and definition tags,
+	/// without repetitions.
+	pub fn parts_of_speech(&'a self) -> Vec<PartOfSpeech> {
+		let mut out = Vec::new();
+		for tag in self.definition_tags().chain(self.term_tags()) {
+			if let Some(pos) = tag.part_of_speech() {
+				if !out.contains(&pos) {
+					out.push(pos);
+				}
+			}
+		}
+		out
+	}
+
+	/// Returns true for common terms, marked with a tag in the popular
+	/// category (the `P` tag in JMdict).
+	pub fn is_common(&'a self) -> bool {
+		self.term_tags()
+			.chain(self.definition_tags())
+			.any(|tag| tag.category_type() == TagCategory::Popular)
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::testing::*;
+	use super::{AdjectiveClass, PartOfSpeech, TagCategory, VerbClass, VerbType};
+
+	#[test]
+	fn tag_category() {
+		assert_eq!(
+			TagCategory::parse("partOfSpeech"),
+			TagCategory::PartOfSpeech
+		);
+		assert_eq!(TagCategory::parse("popular"), TagCategory::Popular);
+		assert_eq!(TagCategory::parse("archaism"), TagCategory::Archaism);
+		assert_eq!(TagCategory::parse(""), TagCategory::Unspecified);
+		assert_eq!(
+			TagCategory::parse("misc"),
+			TagCategory::Other("misc".to_string())
+		);
+	}
+
+	#[test]
+	fn part_of_speech() {
+		use super::PartOfSpeech::*;
+		let cases = [
+			("n", Noun),
+			("adj-i", Adjective(AdjectiveClass::I)),
+			("adj-shiku", Adjective(AdjectiveClass::Archaic)),
+			("v1", Verb(VerbClass::Ichidan, VerbType::Regular)),
+			("v5k", Verb(VerbClass::Godan('く'), VerbType::Regular)),
+			("v5k-s", Verb(VerbClass::Godan('く'), VerbType::Special)),
+			("v5aru", Verb(VerbClass::Godan('る'), VerbType::Special)),
+			("v5uru", Verb(VerbClass::Uru, VerbType::Regular)),
+			("vs", Verb(VerbClass::Suru, VerbType::Noun)),
+			("vs-s", Verb(VerbClass::Suru, VerbType::Special)),
+			("vs-c", Verb(VerbClass::Godan('す'), VerbType::Regular)),
+			("vk", Verb(VerbClass::Kuru, VerbType::Regular)),
+			("v2a-s", Verb(VerbClass::Archaic, VerbType::Regular)),
+			("v4r", Verb(VerbClass::Archaic, VerbType::Regular)),
+			("v-unspec", Verb(VerbClass::Irregular, VerbType::Regular)),
+			("v5x", Other("v5x".to_string())),
+			("v5", Other("v5".to_string())),
+			("P", Other("P".to_string())),
+		];
+		for (name, expected) in cases.iter() {
+			assert_eq!(&PartOfSpeech::parse(name), expected, "{}", name);
+		}
+
+		assert!(PartOfSpeech::parse("v1").is_verb());
+		assert!(PartOfSpeech::parse("vt").is_verb());
+		assert!(!PartOfSpeech::parse("n").is_verb());
+		assert!(PartOfSpeech::parse("v5x").is_other());
+	}
+
+	#[test]
+	fn term_tags() {
+		let mut w = writer(&[
+			("P", "popular"),
+			("n", "partOfSpeech"),
+			("vs", "partOfSpeech"),
+			("new-pos", "partOfSpeech"),
+			("v1", ""),
+			("arch", "archaism"),
+			("misc-tag", "misc"),
+		]);
+		let mut item = term(&mut w, "勉強", "べんきょう", "", 100);
+		item.term_tags = w.get_tags(["P", "n"]);
+		item.definition_tags = w.get_tags(["n", "vs", "new-pos", "misc-tag"]);
+		w.push_term(item);
+
+		let mut item = term(&mut w, "食べる", "たべる", "", 50);
+		item.definition_tags = w.get_tags(["v1", "arch"]);
+		w.push_term(item);
+
+		let data = TestDB::new(w);
+		let db = data.db();
+
+		let tag = |name: &str| db.tags().find(|x| x.name() == name).unwrap();
+		let pos = |name: &str| tag(name).part_of_speech();
+		assert_eq!(pos("n"), Some(PartOfSpeech::Noun));
+		assert_eq!(
+			pos("new-pos"),
+			Some(PartOfSpeech::Other("new-pos".to_string()))
+		);
+		assert_eq!(pos("misc-tag"), None);
+		assert_eq!(pos("P"), None);
+		// Known part of speech names are recognized in any category.
+		assert!(pos("v1").unwrap().is_verb());
+
+		let term = db.term(0).unwrap();
+		assert_eq!(
+			term.parts_of_speech(),
+			[
+				PartOfSpeech::Noun,
+				PartOfSpeech::Verb(VerbClass::Suru, VerbType::Noun),
+				PartOfSpeech::Other("new-pos".to_string()),
+			]
+		);
+		assert!(term.is_common());
+
+		let term = db.term(1).unwrap();
+		assert_eq!(
+			term.parts_of_speech(),
+			[PartOfSpeech::Verb(VerbClass::Ichidan, VerbType::Regular)]
+		);
+		assert!(!term.is_common());
+	}
+}
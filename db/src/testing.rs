@@ -0,0 +1,83 @@
+//! Helpers for building small databases in tests.
+
+use super::{KanjiData, OwnedDB, TagData, TermData, Writer, DB};
+
+/// Compiled test database.
+pub struct TestDB {
+	data: OwnedDB,
+}
+
+impl TestDB {
+	/// Writes the database from the given writer.
+	pub fn new(writer: Writer) -> TestDB {
+		let mut data = Vec::new();
+		writer.write(&mut data).unwrap();
+		TestDB {
+			data: OwnedDB::load(&data).unwrap(),
+		}
+	}
+
+	/// Returns the raw database bytes.
+	pub fn bytes(&self) -> &[u8] {
+		self.data.bytes()
+	}
+
+	/// Loads the database, checking it with [DB::try_load].
+	pub fn db(&self) -> DB<'_> {
+		DB::try_load(self.bytes()).unwrap()
+	}
+}
+
+/// Returns a new writer with the given tags, as `(name, category)` pairs.
+pub fn writer(tags: &[(&str, &str)]) -> Writer {
+	let mut w = Writer::new();
+	for &(name, category) in tags {
+		let tag = TagData {
+			name: w.intern(name.to_string()),
+			category: w.intern(category.to_string()),
+			order: 0,
+			notes: w.intern(String::new()),
+		};
+		w.push_tag(tag);
+	}
+	w
+}
+
+/// Returns the data for a term from the `JMdict` source with no tags. Any
+/// field can be changed before pushing the term to the writer.
+pub fn term(
+	w: &mut Writer,
+	expression: &str,
+	reading: &str,
+	search_key: &str,
+	frequency: u32,
+) -> TermData {
+	TermData {
+		expression: w.intern(expression.to_string()),
+		reading: w.intern(reading.to_string()),
+		search_key: w.intern(search_key.to_string()),
+		score: 0,
+		sequence: 0,
+		frequency,
+		glossary: vec![w.intern(format!("{} (glossary)", expression))],
+		rules: Vec::new(),
+		term_tags: Vec::new(),
+		definition_tags: Vec::new(),
+		source: w.intern("JMdict".to_string()),
+	}
+}
+
+/// Returns the data for a kanji from the `KANJIDIC` source with the given
+/// readings and no tags or stats.
+pub fn kanji(w: &mut Writer, character: char, onyomi: &[&str], kunyomi: &[&str]) -> KanjiData {
+	KanjiData {
+		character,
+		frequency: 0,
+		meanings: vec![w.intern(format!("{} (meaning)", character))],
+		onyomi: onyomi.iter().map(|x| w.intern(x.to_string())).collect(),
+		kunyomi: kunyomi.iter().map(|x| w.intern(x.to_string())).collect(),
+		tags: Vec::new(),
+		stats: Vec::new(),
+		source: w.intern("KANJIDIC".to_string()),
+	}
+}
@@ -0,0 +1,40 @@
+//! Platform helpers for debug output and timing.
+//!
+//! On `wasm32` there is no standard output and `std::time::Instant` is not
+//! available, so both are disabled on that target.
+
+use std::time::Duration;
+
+/// Prints debug output (e.g. database statistics). This is a no-op when
+/// targeting `wasm32`.
+macro_rules! debug_log {
+	($($arg:tt)*) => {
+		#[cfg(not(target_arch = "wasm32"))]
+		println!($($arg)*);
+		#[cfg(target_arch = "wasm32")]
+		let _ = format_args!($($arg)*);
+	};
+}
+
+/// Simple timer for debug output. Always returns a zero elapsed time when
+/// targeting `wasm32`.
+pub struct Timer {
+	#[cfg(not(target_arch = "wasm32"))]
+	start: std::time::Instant,
+}
+
+impl Timer {
+	pub fn start() -> Timer {
+		Timer {
+			#[cfg(not(target_arch = "wasm32"))]
+			start: std::time::Instant::now(),
+		}
+	}
+
+	pub fn elapsed(&self) -> Duration {
+		#[cfg(not(target_arch = "wasm32"))]
+		return self.start.elapsed();
+		#[cfg(target_arch = "wasm32")]
+		return Duration::from_secs(0);
+	}
+}
@@ -0,0 +1,278 @@
+//! Vocabulary extraction from Japanese text.
+
+use std::collections::HashMap;
+
+use super::kana;
+use super::Deinflector;
+use super::DB;
+
+/// Maximum length in characters of a word in the text, including any
+/// inflection.
+const MAX_WORD_LENGTH: usize = 24;
+
+/// Maximum length in characters of an inflected ending that is checked past
+/// the longest dictionary prefix (e.g. `させられなかった`).
+const MAX_INFLECTION_LENGTH: usize = 10;
+
+/// Dictionary term found in a text. See
+/// [extract_vocabulary](DB::extract_vocabulary).
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct VocabularyEntry {
+	/// Term index. Terms are sorted by frequency, so this is also the rank of
+	/// the term by frequency.
+	pub term: usize,
+	/// Number of occurrences for the term in the frequency database (see
+	/// [Term::frequency](super::Term::frequency)).
+	pub frequency: Option<u32>,
+	/// Number of occurrences of the term in the text.
+	pub count: usize,
+	/// Byte offset of the first occurrence of the term in the text.
+	pub offset: usize,
+	/// Distinct forms of the term as found in the text (e.g. `食べた` and
+	/// `食べない`), in order of first occurrence.
+	pub forms: Vec<String>,
+}
+
+/// Sort order for the entries from
+/// [extract_vocabulary](DB::extract_vocabulary).
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum VocabularyOrder {
+	/// Order of first occurrence in the text.
+	Text,
+	/// Most occurrences in the text first.
+	Count,
+	/// Least frequent terms first (e.g. to pre-study the hardest words).
+	Rarest,
+	/// Most frequent terms first.
+	Frequent,
+}
+
+impl VocabularyOrder {
+	/// Sorts the entries in this order.
+	pub fn sort(self, entries: &mut [VocabularyEntry]) {
+		match self {
+			VocabularyOrder::Text => entries.sort_by_key(|x| x.offset),
+			VocabularyOrder::Count => {
+				entries.sort_by(|a, b| b.count.cmp(&a.count).then(a.term.cmp(&b.term)))
+			}
+			VocabularyOrder::Rarest => entries.sort_by_key(|x| std::cmp::Reverse(x.term)),
+			VocabularyOrder::Frequent => entries.sort_by_key(|x| x.term),
+		}
+	}
+}
+
+impl<'db> DB<'db> {
+	/// Extracts the distinct dictionary terms in a text, with the number of
+	/// occurrences of each term. Entries are returned in order of first
+	/// occurrence (see [VocabularyOrder] for other orders).
+	///
+	/// The text is segmented using the longest dictionary match at each
+	/// position, including inflected verbs and adjectives (e.g. `食べなかった`
+	/// is counted as `食べる`). Characters that are not kana or kanji are
+	/// ignored.
+	pub fn extract_vocabulary<'a: 'db>(&'a self, text: &str) -> Vec<VocabularyEntry> {
+		let mut entries: Vec<VocabularyEntry> = Vec::new();
+		let mut by_term = HashMap::new();
+		self.for_each_word(text, |offset, length, term| {
+			let form = &text[offset..offset + length];
+			let entry = *by_term.entry(term).or_insert_with(|| {
+				entries.push(VocabularyEntry {
+					term,
+					frequency: self.term(term).unwrap().frequency(),
+					count: 0,
+					offset,
+					forms: Vec::new(),
+				});
+				entries.len() - 1
+			});
+			let entry = &mut entries[entry];
+			entry.count += 1;
+			if !entry.forms.iter().any(|x| x == form) {
+				entry.forms.push(form.to_string());
+			}
+		});
+		entries
+	}
+
+	/// Segments the text into dictionary words, calling `callback` with the
+	/// byte offset, the length in bytes and the term index of each word.
+	/// Characters that are not part of a word are skipped.
+	pub(super) fn for_each_word<'a: 'db, F: FnMut(usize, usize, usize)>(
+		&'a self,
+		text: &str,
+		mut callback: F,
+	) {
+		let deinflector = Deinflector::new();
+		let mut offset = 0;
+		while let Some(chr) = text[offset..].chars().next() {
+			let found = if is_japanese(chr) {
+				self.match_word(&deinflector, &text[offset..])
+			} else {
+				None
+			};
+
+			if let Some((length, term)) = found {
+				callback(offset, length, term);
+				offset += length;
+			} else {
+				offset += chr.len_utf8();
+			}
+		}
+	}
+
+	/// Finds the longest word at the start of `text`, either matching a term
+	/// exactly or as an inflected form of a term.
+	///
+	/// Returns the length in bytes of the match and the term index.
+	fn match_word<'a: 'db>(
+		&'a self,
+		deinflector: &Deinflector,
+		text: &str,
+	) -> Option<(usize, usize)> {
+		let ends: Vec<usize> = text
+			.char_indices()
+			.take_while(|&(_, chr)| is_japanese(chr))
+			.take(MAX_WORD_LENGTH)
+			.map(|(pos, chr)| pos + chr.len_utf8())
+			.collect();
+
+		// Number of prefixes of the text that are also a prefix of some term.
+		// No longer match is possible, except for the inflected ending.
+		let known = ends
+			.iter()
+			.take_while(|&&end| {
+				let range = self.do_search_index_range(&text[..end], false, self.index_prefix_jp);
+				range.is_some()
+			})
+			.count();
+		if known == 0 {
+			return None;
+		}
+
+		let count = std::cmp::min(ends.len(), known + MAX_INFLECTION_LENGTH);
+		for (i, &end) in ends[..count].iter().enumerate().rev() {
+			let key = &text[..end];
+			if i < known {
+				if let Some(index) = self.match_exact(key) {
+					return Some((end, index));
+				}
+			}
+
+			// All inflected endings are kana.
+			if !key.chars().last().map(kana::is_kana).unwrap_or(false) {
+				continue;
+			}
+			for it in deinflector.deinflect(key).into_iter().skip(1) {
+				let index = self.match_exact_by(&it.text, |index| {
+					let term = self.term(index).unwrap();
+					it.matches(&term)
+				});
+				if let Some(index) = index {
+					return Some((end, index));
+				}
+			}
+		}
+
+		None
+	}
+}
+
+fn is_japanese(chr: char) -> bool {
+	kana::is_kana(chr) || kana::is_kanji(chr)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::super::testing::*;
+	use super::VocabularyOrder;
+
+	fn test_db() -> TestDB {
+		let rules = ["v1", "v5", "vs", "vk", "adj-i"];
+		let tags: Vec<_> = rules.iter().map(|&x| (x, "partOfSpeech")).collect();
+		let mut w = writer(&tags);
+		let terms = [
+			("行く", "いく", 1000, "v5"),
+			("来る", "くる", 800, "vk"),
+			("食べる", "たべる", 500, "v1"),
+			("勉強", "べんきょう", 300, "vs"),
+			("高い", "たかい", 200, "adj-i"),
+			("本", "ほん", 100, ""),
+			("東京", "とうきょう", 50, ""),
+		];
+		for &(expression, reading, frequency, rule) in terms.iter() {
+			let mut item = term(&mut w, expression, reading, "", frequency);
+			if !rule.is_empty() {
+				item.rules = w.get_tags([rule]);
+			}
+			w.push_term(item);
+		}
+		TestDB::new(w)
+	}
+
+	#[test]
+	fn extract_vocabulary() {
+		let data = test_db();
+		let db = data.db();
+
+		let text = "東京に行った。本を食べなかった。本は高くない。勉強しました。行く。来った";
+		let entries = db.extract_vocabulary(text);
+		let words: Vec<_> = entries
+			.iter()
+			.map(|x| (db.term(x.term).unwrap().expression(), x.count))
+			.collect();
+		assert_eq!(
+			words,
+			[
+				("東京", 1),
+				("行く", 2),
+				("本", 2),
+				("食べる", 1),
+				("高い", 1),
+				("勉強", 1)
+			]
+		);
+
+		let entry = &entries[1];
+		assert_eq!(entry.term, 0);
+		assert_eq!(entry.frequency, Some(1000));
+		assert_eq!(entry.offset, text.find("行った").unwrap());
+		assert_eq!(entry.forms, ["行った", "行く"]);
+		assert_eq!(entries[5].forms, ["勉強しました"]);
+		assert_eq!(entries[0].term, 6);
+		assert_eq!(entries[0].frequency, Some(50));
+
+		assert!(db.extract_vocabulary("abc。").is_empty());
+	}
+
+	#[test]
+	fn vocabulary_order() {
+		let data = test_db();
+		let db = data.db();
+
+		let text = "本を食べる。行く。本と東京。本";
+		let sorted = |order: VocabularyOrder| {
+			let mut entries = db.extract_vocabulary(text);
+			order.sort(&mut entries);
+			entries
+				.iter()
+				.map(|x| db.term(x.term).unwrap().expression())
+				.collect::<Vec<_>>()
+		};
+		assert_eq!(
+			sorted(VocabularyOrder::Text),
+			["本", "食べる", "行く", "東京"]
+		);
+		assert_eq!(
+			sorted(VocabularyOrder::Count),
+			["本", "行く", "食べる", "東京"]
+		);
+		assert_eq!(
+			sorted(VocabularyOrder::Rarest),
+			["東京", "本", "食べる", "行く"]
+		);
+		assert_eq!(
+			sorted(VocabularyOrder::Frequent),
+			["行く", "食べる", "本", "東京"]
+		);
+	}
+}
@@ -1,14 +1,23 @@
 //! Serialization support for the database.
 
+use std::collections::BTreeMap;
 use std::collections::HashMap;
 use std::collections::HashSet;
+use std::collections::VecDeque;
 use std::io;
 use std::io::Result;
-use std::time::Instant;
 
 use unicode_segmentation::UnicodeSegmentation;
 
 use super::raw::*;
+use super::util::Timer;
+
+/// Number of keys stored for each prefix in the suggestion index.
+pub(super) const SUGGEST_SIZE: usize = 20;
+
+/// Minimum number of prefix index rows for a prefix to be included in the
+/// suggestion index. Prefixes with fewer rows are fast enough to scan.
+pub(super) const SUGGEST_MIN_ROWS: usize = 1000;
 
 /// Writer helper for the database. Provides methods for adding terms, kanji
 /// and tags to the database and a [write](Writer::write) method for outputting
@@ -121,7 +130,7 @@ impl Writer {
 	/// The binary representation of the database is designed to be memory
 	/// mapped on load. Note that `u32` are written in LE format.
 	pub fn write<W: std::io::Write>(mut self, writer: &mut W) -> std::io::Result<()> {
-		let start = Instant::now();
+		let start = Timer::start();
 
 		//
 		// Sort terms and kanji by relevance
@@ -159,6 +168,64 @@ impl Writer {
 
 		index_prefix_jp.sort_by(|a, b| self.string(a.0).cmp(self.string(b.0)));
 
+		// The suggestion index stores the best keys for the prefixes matching
+		// too many rows in the prefix index, so that suggestions for short
+		// prefixes don't need a scan. Keys are ranked by their most frequent
+		// term, which is the lowest term index.
+
+		// List of distinct keys in the prefix index as `(key, term, rows)`.
+		let mut distinct_keys: Vec<(u32, u32, usize)> = Vec::new();
+		for &(key, term) in index_prefix_jp.iter() {
+			match distinct_keys.last_mut() {
+				Some(last) if last.0 == key => {
+					last.1 = std::cmp::min(last.1, term);
+					last.2 += 1;
+				}
+				_ => distinct_keys.push((key, term, 1)),
+			}
+		}
+
+		let mut rows_before = vec![0];
+		for it in distinct_keys.iter() {
+			let rows = rows_before[rows_before.len() - 1] + it.2;
+			rows_before.push(rows);
+		}
+
+		// Prefixes are visited in sorted order: each new prefix is found at
+		// the first key that contains it.
+		let mut index_suggest = Vec::new();
+		for (i, it) in distinct_keys.iter().enumerate() {
+			let key = self.string(it.0);
+			let prev = if i > 0 {
+				self.string(distinct_keys[i - 1].0)
+			} else {
+				""
+			};
+			for (pos, chr) in key.char_indices() {
+				let prefix = &key[..pos + chr.len_utf8()];
+				if prev.starts_with(prefix) {
+					continue;
+				}
+
+				let count =
+					distinct_keys[i..].partition_point(|x| self.string(x.0).starts_with(prefix));
+				if rows_before[i + count] - rows_before[i] < SUGGEST_MIN_ROWS {
+					// Longer prefixes can only have fewer rows.
+					break;
+				}
+
+				let mut best: Vec<(u32, usize)> =
+					(i..i + count).map(|x| (distinct_keys[x].1, x)).collect();
+				best.sort();
+				best.truncate(SUGGEST_SIZE);
+				let best = best
+					.into_iter()
+					.flat_map(|(term, x)| vec![distinct_keys[x].0, term])
+					.collect::<Vec<_>>();
+				index_suggest.push((it.0, prefix.len() as u32, best));
+			}
+		}
+
 		// The suffix index is exactly like the prefix but keys are sorted by
 		// the reverse string. When searching for a suffix, the search string
 		// must be likewise reversed before performing the binary search.
@@ -181,6 +248,78 @@ impl Writer {
 			rev_a.cmp(&rev_b)
 		});
 
+		// The sequence index maps the source dictionary and sequence number of
+		// a term to its index. Unlike the term index, those are stable across
+		// database builds. Terms without a sequence number are not indexed.
+		let mut index_sequence = Vec::new();
+		for (i, it) in self.terms.iter().enumerate() {
+			if it.sequence > 0 {
+				index_sequence.push((it.source, it.sequence, i as u32));
+			}
+		}
+
+		index_sequence.sort_by(|a, b| {
+			let source_a = self.string(a.0);
+			let source_b = self.string(b.0);
+			source_a
+				.cmp(source_b)
+				.then(a.1.cmp(&b.1))
+				.then(a.2.cmp(&b.2))
+		});
+
+		// The search key trie is used for fuzzy searching the romaji search
+		// keys. Each node stores the terms for the key ending at that node.
+		let mut trie = vec![TrieBuilder::default()];
+		for (i, it) in self.terms.iter().enumerate() {
+			if it.search_key == 0 {
+				continue;
+			}
+			let mut node = 0;
+			for chr in self.string(it.search_key).chars() {
+				node = if let Some(&next) = trie[node].children.get(&chr) {
+					next
+				} else {
+					let next = trie.len();
+					trie.push(TrieBuilder::default());
+					trie[node].children.insert(chr, next);
+					next
+				};
+			}
+			trie[node].terms.push(i as u32);
+		}
+
+		// The kanji stat index maps each stat and value to the kanji with that
+		// stat (e.g. all kanji with a given JLPT level). Kanji are already
+		// sorted, so the kanji for each value are sorted by frequency.
+		let mut index_kanji_stat = Vec::new();
+		for (i, it) in self.kanji.iter().enumerate() {
+			for &(stat, value) in it.stats.iter() {
+				index_kanji_stat.push((stat, value, i as u32));
+			}
+		}
+		index_kanji_stat.sort_by(|a, b| {
+			let stat_a = self.string(self.tags[a.0 as usize].name);
+			let stat_b = self.string(self.tags[b.0 as usize].name);
+			stat_a
+				.cmp(stat_b)
+				.then(self.string(a.1).cmp(self.string(b.1)))
+				.then(a.2.cmp(&b.2))
+		});
+
+		// The tag index lists the terms with each tag in their term or
+		// definition tags, in the same order as the tags. Terms are already
+		// sorted, so each list is sorted by frequency.
+		let mut index_tag_terms = vec![Vec::new(); self.tags.len()];
+		for (i, it) in self.terms.iter().enumerate() {
+			let mut tags = it.term_tags.clone();
+			tags.extend(it.definition_tags.iter());
+			tags.sort();
+			tags.dedup();
+			for tag in tags {
+				index_tag_terms[tag as usize].push(i as u32);
+			}
+		}
+
 		// Per-character index used for "contains" style queries and fuzzy
 		// searching.
 		let mut index_chars_jp = HashMap::new();
@@ -203,7 +342,7 @@ impl Writer {
 		}
 
 		let num_char_keys = index_chars_jp.len();
-		println!(
+		debug_log!(
 			"... built index in {:?} (terms = {}, chars = {} / avg {} / max {})",
 			start.elapsed(),
 			index_prefix_jp.len(),
@@ -216,7 +355,7 @@ impl Writer {
 		// Serialization
 		//
 
-		let start = Instant::now();
+		let start = Timer::start();
 
 		let mut raw = Raw::default();
 		let mut vector_data: Vec<u32> = Vec::new();
@@ -298,7 +437,68 @@ impl Writer {
 			})
 			.collect();
 
-		// Convert the chars index into a mappable format
+		raw.index_suggest = index_suggest
+			.into_iter()
+			.map(|(key, length, keys)| SuggestIndex {
+				key: key.into(),
+				length: length.into(),
+				keys: push_vec(keys),
+			})
+			.collect();
+
+		raw.index_kanji_stat = index_kanji_stat
+			.into_iter()
+			.map(|(stat, value, kanji)| KanjiStatIndex {
+				stat: stat.into(),
+				value: value.into(),
+				kanji: kanji.into(),
+			})
+			.collect();
+
+		raw.index_tag_terms = index_tag_terms.into_iter().map(&mut push_vec).collect();
+
+		raw.index_sequence = index_sequence
+			.into_iter()
+			.map(|(source, sequence, term)| SequenceIndex {
+				source: source.into(),
+				sequence: sequence.into(),
+				term: term.into(),
+			})
+			.collect();
+
+		// Flatten the trie in breadth-first order, so that the children of
+		// each node are stored contiguously.
+		let mut queue = VecDeque::new();
+		queue.push_back(('\0', 0));
+		let mut next_child = 1;
+		while let Some((chr, node)) = queue.pop_front() {
+			let node = &mut trie[node];
+			let child_count = node.children.len();
+			raw.index_key_trie.push(TrieNode {
+				character: (chr as u32).into(),
+				first_child: (next_child as u32).into(),
+				child_count: (child_count as u32).into(),
+				terms: push_vec(std::mem::take(&mut node.terms)),
+			});
+			next_child += child_count;
+			for (&chr, &child) in node.children.iter() {
+				queue.push_back((chr, child));
+			}
+		}
+
+		// The kanji index lists the kanji indexes sorted by character, for
+		// looking up a kanji with a binary search.
+		let mut index_kanji: Vec<u32> = (0..raw.kanji.len() as u32).collect();
+		index_kanji.sort_by_key(|&index| {
+			let character: u32 = raw.kanji[index as usize].character.into();
+			character
+		});
+		raw.index_kanji = index_kanji.into_iter().map(|x| x.into()).collect();
+
+		// Convert the chars index into a mappable format. Rows are sorted by
+		// character to allow a binary search on load.
+		let mut index_chars_jp = index_chars_jp.into_iter().collect::<Vec<_>>();
+		index_chars_jp.sort_by_key(|it| it.0);
 		raw.index_chars_jp = index_chars_jp
 			.into_iter()
 			.map(|(key, val)| {
@@ -323,7 +523,7 @@ impl Writer {
 		raw.string_data = self.string_data;
 		raw.vector_data = vector_data;
 
-		println!("... prepared raw data in {:?}", start.elapsed());
+		debug_log!("... prepared raw data in {:?}", start.elapsed());
 
 		raw.write(writer)
 	}
@@ -392,6 +592,13 @@ pub struct TermData {
 	pub source: u32,
 }
 
+/// Trie node used for building the search key trie.
+#[derive(Default)]
+struct TrieBuilder {
+	children: BTreeMap<char, usize>,
+	terms: Vec<u32>,
+}
+
 /// Raw database structure used for building the database for write.
 #[derive(Default)]
 struct Raw {
@@ -401,6 +608,12 @@ struct Raw {
 	index_prefix_jp: Vec<TermIndex>,
 	index_suffix_jp: Vec<TermIndex>,
 	index_chars_jp: Vec<CharIndex>,
+	index_sequence: Vec<SequenceIndex>,
+	index_key_trie: Vec<TrieNode>,
+	index_kanji: Vec<RawUint32>,
+	index_suggest: Vec<SuggestIndex>,
+	index_tag_terms: Vec<VecHandle>,
+	index_kanji_stat: Vec<KanjiStatIndex>,
 	vector_data: Vec<u32>,
 	string_list: Vec<StrHandle>,
 	string_data: String,
@@ -417,6 +630,12 @@ impl Raw {
 		write_all(writer, self.index_prefix_jp)?;
 		write_all(writer, self.index_suffix_jp)?;
 		write_all(writer, self.index_chars_jp)?;
+		write_all(writer, self.index_sequence)?;
+		write_all(writer, self.index_key_trie)?;
+		write_all(writer, self.index_kanji)?;
+		write_all(writer, self.index_suggest)?;
+		write_all(writer, self.index_tag_terms)?;
+		write_all(writer, self.index_kanji_stat)?;
 		write_vec(writer, self.vector_data)?;
 		write_all(writer, self.string_list)?;
 		write_len(writer, self.string_data.len())?;
@@ -429,34 +648,112 @@ use super::DB;
 
 impl<'a> DB<'a> {
 	/// Load the database from a raw binary blob.
+	///
+	/// The data is trusted to be a valid database (e.g. the embedded one).
+	/// Use [try_load](DB::try_load) for data from other sources.
 	pub fn load(data: &'a [u8]) -> DB<'a> {
+		match DB::read(data, false) {
+			Ok(db) => db,
+			Err(err) => panic!("loading database: {}", err),
+		}
+	}
+
+	/// Load the database from a binary blob that may be truncated or invalid,
+	/// such as a file opened at runtime.
+	///
+	/// Unlike [load](DB::load), this validates the string data and checks the
+	/// database using [verify](DB::verify), returning the first error found
+	/// instead of panicking.
+	pub fn try_load(data: &'a [u8]) -> std::result::Result<DB<'a>, String> {
+		let db = DB::read(data, true)?;
+		db.verify()?;
+		Ok(db)
+	}
+
+	fn read(data: &'a [u8], check_utf8: bool) -> std::result::Result<DB<'a>, String> {
 		// Note that the order of operations must match the [Raw::write] method.
 		unsafe {
-			let (tags, data) = read_slice::<TagRaw>(data);
-			let (terms, data) = read_slice::<TermRaw>(data);
-			let (kanji, data) = read_slice::<KanjiRaw>(data);
-			let (index_prefix_jp, data) = read_slice::<TermIndex>(data);
-			let (index_suffix_jp, data) = read_slice::<TermIndex>(data);
-			let (index_chars_jp, data) = read_slice::<CharIndex>(data);
-			let (vector_data, data) = read_slice::<RawUint32>(data);
-			let (string_list, data) = read_slice::<StrHandle>(data);
-			let (string_data, _) = read_slice::<u8>(data);
-			let string_data = std::str::from_utf8_unchecked(string_data);
-			DB {
+			let (tags, data) = read_slice::<TagRaw>(data)?;
+			let (terms, data) = read_slice::<TermRaw>(data)?;
+			let (kanji, data) = read_slice::<KanjiRaw>(data)?;
+			let (index_prefix_jp, data) = read_slice::<TermIndex>(data)?;
+			let (index_suffix_jp, data) = read_slice::<TermIndex>(data)?;
+			let (index_chars_jp, data) = read_slice::<CharIndex>(data)?;
+			let (index_sequence, data) = read_slice::<SequenceIndex>(data)?;
+			let (index_key_trie, data) = read_slice::<TrieNode>(data)?;
+			let (index_kanji, data) = read_slice::<RawUint32>(data)?;
+			let (index_suggest, data) = read_slice::<SuggestIndex>(data)?;
+			let (index_tag_terms, data) = read_slice::<VecHandle>(data)?;
+			let (index_kanji_stat, data) = read_slice::<KanjiStatIndex>(data)?;
+			let (vector_data, data) = read_slice::<RawUint32>(data)?;
+			let (string_list, data) = read_slice::<StrHandle>(data)?;
+			let (string_data, _) = read_slice::<u8>(data)?;
+			let string_data = if check_utf8 {
+				std::str::from_utf8(string_data).map_err(|_| "invalid string data".to_string())?
+			} else {
+				std::str::from_utf8_unchecked(string_data)
+			};
+			Ok(DB {
 				tags: tags,
 				terms: terms,
 				kanji: kanji,
 				index_prefix_jp: index_prefix_jp,
 				index_suffix_jp: index_suffix_jp,
 				index_chars_jp: index_chars_jp,
+				index_sequence,
+				index_key_trie,
+				index_kanji,
+				index_suggest,
+				index_tag_terms,
+				index_kanji_stat,
 				vector_data: vector_data,
 				string_list: string_list,
 				string_data: string_data,
-			}
+			})
 		}
 	}
 }
 
+/// Owned copy of the data for a database, such as a file opened at runtime.
+///
+/// The database structures are read in place, so the data must be aligned
+/// for them. A `Vec<u8>` (e.g. from `std::fs::read`) does not guarantee that,
+/// so the data is copied into a `Vec<u32>`.
+pub struct OwnedDB {
+	buffer: Vec<u32>,
+	length: usize,
+}
+
+impl OwnedDB {
+	/// Copies the data and checks it with [DB::try_load].
+	pub fn load(data: &[u8]) -> std::result::Result<OwnedDB, String> {
+		let mut buffer = vec![0u32; data.len().div_ceil(4)];
+		unsafe {
+			let bytes = std::slice::from_raw_parts_mut(buffer.as_mut_ptr() as *mut u8, data.len());
+			bytes.copy_from_slice(data);
+		}
+		let out = OwnedDB {
+			buffer,
+			length: data.len(),
+		};
+		DB::try_load(out.bytes())?;
+		Ok(out)
+	}
+
+	/// Returns the database.
+	///
+	/// The data was checked by [load](OwnedDB::load), so this is cheap and
+	/// can be called as needed instead of storing the database.
+	pub fn db(&self) -> DB<'_> {
+		DB::load(self.bytes())
+	}
+
+	/// Returns the raw database bytes.
+	pub fn bytes(&self) -> &[u8] {
+		unsafe { std::slice::from_raw_parts(self.buffer.as_ptr() as *const u8, self.length) }
+	}
+}
+
 //
 // Write helpers
 //
@@ -511,19 +808,19 @@ unsafe fn to_bytes<T: Sized>(value: &T) -> &[u8] {
 //
 
 #[inline]
-unsafe fn read_slice<U>(src: &[u8]) -> (&[U], &[u8]) {
+unsafe fn read_slice<U>(src: &[u8]) -> std::result::Result<(&[U], &[u8]), String> {
 	const U32_LEN: usize = std::mem::size_of::<u32>();
 
-	assert!(src.len() >= U32_LEN);
-	let count: &[u32] = cast_slice(&src[0..U32_LEN]);
+	let truncated = || "truncated database".to_string();
+	let count: &[u32] = cast_slice(src.get(0..U32_LEN).ok_or_else(truncated)?);
 	let count = u32::from_le(count[0]) as usize;
 	let src = &src[U32_LEN..];
 
 	let item_size = std::mem::size_of::<U>();
-	let data_size = item_size * count;
-	let data = &src[..data_size];
+	let data_size = item_size.checked_mul(count).ok_or_else(truncated)?;
+	let data = src.get(..data_size).ok_or_else(truncated)?;
 	let next = &src[data_size..];
-	(cast_slice(data), next)
+	Ok((cast_slice(data), next))
 }
 
 #[inline]
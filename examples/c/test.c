@@ -0,0 +1,120 @@
+/* Test program for the jp-dict C API.
+ *
+ * Build and run with `make c-test`. If a database file is given as argument
+ * it is opened at runtime, otherwise the embedded database is used. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "jp_dict.h"
+
+#define CHECK(cond)                                                          \
+	if (!(cond)) {                                                           \
+		fprintf(stderr, "%s:%d: check failed: %s\n", __FILE__, __LINE__, #cond); \
+		return 1;                                                            \
+	}
+
+static void print_str(const char *label, JpDictStr str) {
+	printf("%s%.*s", label, (int)str.len, (const char *)str.ptr);
+}
+
+static int print_term(const JpDictDb *db, size_t index) {
+	print_str("- ", jp_dict_term_expression(db, index));
+	print_str(" [", jp_dict_term_reading(db, index));
+	print_str(" / ", jp_dict_term_search_key(db, index));
+	printf("] #%u (sequence: %u)\n", jp_dict_term_frequency(db, index), jp_dict_term_sequence(db, index));
+
+	size_t glossary = jp_dict_term_glossary_len(db, index);
+	for (size_t i = 0; i < glossary; i++) {
+		print_str(i == 0 ? "    " : ", ", jp_dict_term_glossary(db, index, i));
+	}
+	printf("\n");
+
+	size_t tags = jp_dict_term_tags_len(db, index, JP_DICT_TERM_TAGS_TERM_TAGS);
+	for (size_t i = 0; i < tags; i++) {
+		JpDictTag tag = jp_dict_term_tag(db, index, JP_DICT_TERM_TAGS_TERM_TAGS, i);
+		print_str("    -> ", tag.name);
+		print_str(" -- ", tag.notes);
+		printf("\n");
+	}
+
+	/* Out of range access returns empty values */
+	CHECK(jp_dict_term_glossary(db, index, glossary).len == 0);
+	CHECK(jp_dict_term_tag(db, index, JP_DICT_TERM_TAGS_TERM_TAGS, tags).name.len == 0);
+	return 0;
+}
+
+int main(int argc, char **argv) {
+	JpDictDb *opened = NULL;
+	const JpDictDb *db;
+	if (argc > 1) {
+		opened = jp_dict_open(argv[1]);
+		CHECK(opened != NULL);
+		db = opened;
+	} else {
+		db = jp_dict_get_db();
+	}
+
+	CHECK(jp_dict_term_count(db) > 0);
+	CHECK(jp_dict_term_expression(db, jp_dict_term_count(db)).len == 0);
+	CHECK(jp_dict_open("/path/does/not/exist") == NULL);
+
+	/* Files that are not a valid database are rejected */
+	const char *invalid_file = "target/c/invalid.in";
+	FILE *file = fopen(invalid_file, "wb");
+	CHECK(file != NULL);
+	fputs("not a database", file);
+	fclose(file);
+	CHECK(jp_dict_open(invalid_file) == NULL);
+	remove(invalid_file);
+
+	const char *query = "日本";
+	JpDictResults *results = jp_dict_search(db, JP_DICT_SEARCH_MODE_EXACT, (const uint8_t *)query, strlen(query));
+	CHECK(results != NULL);
+
+	size_t count = jp_dict_results_len(results);
+	printf("Found %zu term(s) for `%s`\n", count, query);
+	CHECK(count > 0);
+	for (size_t i = 0; i < count; i++) {
+		size_t index = jp_dict_results_get(results, i);
+		JpDictStr expression = jp_dict_term_expression(db, index);
+		CHECK(expression.len == strlen(query) && memcmp(expression.ptr, query, expression.len) == 0);
+		if (print_term(db, index) != 0) {
+			return 1;
+		}
+	}
+	CHECK(jp_dict_results_get(results, count) == SIZE_MAX);
+	jp_dict_results_free(results);
+
+	/* Invalid UTF-8 is rejected */
+	const uint8_t invalid[] = {0xFF, 0xFE};
+	CHECK(jp_dict_search(db, JP_DICT_SEARCH_MODE_PREFIX, invalid, sizeof(invalid)) == NULL);
+
+	/* Invalid enumeration values are rejected */
+	CHECK(jp_dict_search(db, 99, (const uint8_t *)query, strlen(query)) == NULL);
+	CHECK(jp_dict_term_tags_len(db, jp_dict_term_count(db) - 1, 99) == 0);
+
+	const char *romaji = "nihn";
+	results = jp_dict_search_fuzzy(db, (const uint8_t *)romaji, strlen(romaji), 1);
+	CHECK(results != NULL);
+	printf("Found %zu term(s) for fuzzy `%s`\n", jp_dict_results_len(results), romaji);
+	CHECK(jp_dict_results_len(results) > 0);
+	jp_dict_results_free(results);
+
+	uint32_t kanji = 0x65E5; /* 日 */
+	CHECK(jp_dict_kanji_exists(db, kanji));
+	CHECK(!jp_dict_kanji_exists(db, 'a'));
+	printf("Kanji 日:");
+	size_t onyomi = jp_dict_kanji_list_len(db, kanji, JP_DICT_KANJI_LIST_ONYOMI);
+	for (size_t i = 0; i < onyomi; i++) {
+		print_str(" ", jp_dict_kanji_list_get(db, kanji, JP_DICT_KANJI_LIST_ONYOMI, i));
+	}
+	printf("\n");
+	CHECK(onyomi > 0);
+	CHECK(jp_dict_kanji_list_len(db, kanji, 99) == 0);
+	CHECK(jp_dict_kanji_list_get(db, kanji, 99, 0).len == 0);
+
+	jp_dict_close(opened);
+	printf("OK\n");
+	return 0;
+}
@@ -1,7 +1,8 @@
 let dict = require("../native");
-let terms = dict.search_terms("ともだち");
+let result = dict.search_prefix("ともだち", 50, 0);
+let terms = result.terms;
 
-console.log(`\nFound ${terms.length} terms\n`);
+console.log(`\nFound ${result.total} terms (showing ${terms.length})\n`);
 
 const all_tags = {};
 const map_tags = tag => {
@@ -19,7 +20,7 @@ for (const term of terms) {
 	const frequency = term.frequency ? `f:${term.frequency}` : ``;
 	const score = term.score ? (frequency ? ` / s:${term.score}` : `s:${term.score}`) : ``;
 	const suffix = frequency || score ? `\t${frequency}${score}` : ``;
-	console.log(`\n${item}) ${expr}${read}${suffix}`);
+	console.log(`\n${item}) ${expr}${read}${suffix}\t#${term.sequence} ${term.search_key}`);
 	console.log();
 	for (const it of term.glossary) {
 		console.log(`    > ${it}`);
@@ -8,63 +8,172 @@ extern crate jp_dict;
 
 use neon::prelude::*;
 
+use jp_dict::{OwnedKanji, OwnedTag, OwnedTerm};
+
+/// Filters for the `search` function. All fields are optional.
+#[derive(Serialize, Deserialize, Default)]
+#[serde(default)]
+struct SearchOptions {
+	/// Either `exact` (default), `prefix` or `suffix`.
+	mode: Option<String>,
+	/// Tags required for all results.
+	tags: Vec<String>,
+	/// Tags excluded from the results.
+	exclude_tags: Vec<String>,
+	/// Source dictionaries to include (default is all).
+	sources: Vec<String>,
+	min_frequency: u32,
+	/// Only return common terms.
+	common: bool,
+	terms_only: bool,
+	kanji_only: bool,
+	/// Maximum number of terms and kanji to return (zero for no limit).
+	limit: u32,
+}
+
+/// Result for the `search` function.
+#[derive(Serialize, Deserialize)]
+struct QueryResult {
+	terms: Vec<OwnedTerm>,
+	kanji: Vec<OwnedKanji>,
+}
+
+/// Suggested key for the `suggest` function.
 #[derive(Serialize, Deserialize)]
-struct Term {
-	expression: String,
-	reading: String,
-	score: i32,
-	frequency: Option<u32>,
-	source: String,
-	glossary: Vec<String>,
-	rules: Vec<Tag>,
-	definition_tags: Vec<Tag>,
-	term_tags: Vec<Tag>,
+struct Suggestion {
+	key: String,
+	/// Index of the most frequent term with the key.
+	term: usize,
 }
 
+/// Result for the search functions.
 #[derive(Serialize, Deserialize)]
-struct Tag {
-	name: String,
-	category: String,
-	notes: String,
+struct SearchResult {
+	/// Total number of matches, including the ones outside the requested
+	/// `offset` and `limit`.
+	total: usize,
+	terms: Vec<OwnedTerm>,
 }
 
 export! {
-	fn search_terms(input: String) -> Vec<Term> {
-		let db = jp_dict::get_db();
+	/// Search for terms with an exact expression, reading or romaji key.
+	fn search_exact(input: String, limit: u32, offset: u32) -> SearchResult {
 		let mut set = jp_dict::ResultSet::default();
-		db.search_prefix(input, &mut set);
-
-		let mut results = Vec::new();
-		for index in set.iter() {
-			let src = db.term(index).unwrap();
-			let term = Term{
-				expression: src.expression().to_string(),
-				reading: src.reading().to_string(),
-				score: src.score(),
-				frequency: src.frequency(),
-				source: src.source().to_string(),
-				glossary: src.glossary().map(|x| x.to_string()).collect(),
-				rules: src.rules().map(to_tag).collect(),
-				definition_tags: src.definition_tags().map(to_tag).collect(),
-				term_tags: src.term_tags().map(to_tag).collect(),
-			};
-			results.push(term);
-		}
+		jp_dict::get_db().search_term(input, &mut set);
+		to_result(set.iter(), limit, offset)
+	}
 
-		return results;
+	/// Search for terms with an expression, reading or romaji key starting
+	/// with the input.
+	fn search_prefix(input: String, limit: u32, offset: u32) -> SearchResult {
+		let mut set = jp_dict::ResultSet::default();
+		jp_dict::get_db().search_prefix(input, &mut set);
+		to_result(set.iter(), limit, offset)
+	}
+
+	/// Search for terms with an expression, reading or romaji key ending
+	/// with the input.
+	fn search_suffix(input: String, limit: u32, offset: u32) -> SearchResult {
+		let mut set = jp_dict::ResultSet::default();
+		jp_dict::get_db().search_suffix(input, &mut set);
+		to_result(set.iter(), limit, offset)
+	}
+
+	/// Search for terms and kanji with the given filters. The filters are
+	/// applied during the search, so this is cheaper than filtering the
+	/// results of the other search functions.
+	fn search(input: String, options: SearchOptions) -> QueryResult {
+		let db = jp_dict::get_db();
+		let mut query = match options.mode.as_ref().map(|x| x.as_str()) {
+			Some("prefix") => jp_dict::Query::prefix(input),
+			Some("suffix") => jp_dict::Query::suffix(input),
+			_ => jp_dict::Query::exact(input),
+		};
+		for tag in options.tags {
+			query = query.with_tag(tag);
+		}
+		for tag in options.exclude_tags {
+			query = query.without_tag(tag);
+		}
+		for source in options.sources {
+			query = query.source(source);
+		}
+		if options.common {
+			query = query.common();
+		}
+		if options.terms_only {
+			query = query.terms_only();
+		} else if options.kanji_only {
+			query = query.kanji_only();
+		}
+		let query = query
+			.min_frequency(options.min_frequency)
+			.limit(options.limit as usize);
 
-		fn to_tag<'db, 'a>(item: jp_dict::Tag<'db, 'a>) -> Tag {
-			Tag {
-				name: item.name().to_string(),
-				category: item.category().to_string(),
-				notes: item.notes().to_string(),
-			}
+		let result = db.query(&query);
+		QueryResult {
+			terms: result.terms.iter().map(|index| db.term(index).unwrap().to_owned_term()).collect(),
+			kanji: result.kanji.iter().map(|&chr| db.kanji(chr).unwrap().to_owned_kanji()).collect(),
 		}
 	}
-}
 
-// fn hello(mut cx: FunctionContext) -> JsResult<JsString> {
-// 	Ok(cx.string("hello node"))
-// }
+	/// Search for terms by their romaji key, allowing for up to `max_distance`
+	/// typos. Results are sorted by the number of typos.
+	fn search_romaji(input: String, max_distance: u32, limit: u32, offset: u32) -> SearchResult {
+		let found = jp_dict::get_db().search_fuzzy(input, max_distance as usize);
+		to_result(found.into_iter().map(|x| x.term), limit, offset)
+	}
+
+	/// Returns up to `limit` distinct keys starting with the input, ranked by
+	/// their most frequent term. Meant for suggestions while typing.
+	fn suggest(input: String, limit: u32) -> Vec<Suggestion> {
+		let db = jp_dict::get_db();
+		db.suggest(input, limit as usize)
+			.into_iter()
+			.map(|x| Suggestion {
+				key: x.key.to_string(),
+				term: x.term,
+			})
+			.collect()
+	}
 
-// register_module!(mut cx, { cx.export_function("hello", hello) });
+	/// Returns a term by its index.
+	fn term(index: u32) -> Option<OwnedTerm> {
+		jp_dict::get_db().term(index as usize).map(|x| x.to_owned_term())
+	}
+
+	/// Returns the kanji entry for the first character of the input.
+	fn kanji(input: String) -> Option<OwnedKanji> {
+		let db = jp_dict::get_db();
+		let kanji = input.chars().next().and_then(|chr| db.kanji(chr));
+		kanji.map(|x| x.to_owned_kanji())
+	}
+
+	/// Returns all tags in the database.
+	fn tags() -> Vec<OwnedTag> {
+		jp_dict::get_db().tags().map(|x| x.to_owned_tag()).collect()
+	}
+
+	/// Returns the terms with the given tag (e.g. `P` for common terms),
+	/// sorted by frequency.
+	fn terms_with_tag(name: String, limit: u32, offset: u32) -> SearchResult {
+		to_result(jp_dict::get_db().terms_with_tag(name), limit, offset)
+	}
+}
+
+/// Builds a search result with the terms from `indexes`, skipping `offset`
+/// terms and returning at most `limit`.
+fn to_result<T: Iterator<Item = usize>>(indexes: T, limit: u32, offset: u32) -> SearchResult {
+	let db = jp_dict::get_db();
+	let indexes: Vec<usize> = indexes.collect();
+	let terms = indexes
+		.iter()
+		.skip(offset as usize)
+		.take(limit as usize)
+		.map(|&index| db.term(index).unwrap().to_owned_term())
+		.collect();
+	SearchResult {
+		total: indexes.len(),
+		terms: terms,
+	}
+}
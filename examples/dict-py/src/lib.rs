@@ -0,0 +1,335 @@
+//! Python bindings for the Japanese dictionary.
+//!
+//! The `Term`, `Kanji` and `Tag` objects only keep a reference to the
+//! database and the entry index. Their fields are read from the mapped
+//! database data when accessed.
+
+// False positive for functions returning `PyResult` in the PyO3 macros.
+#![allow(clippy::useless_conversion)]
+
+use std::sync::Arc;
+
+use pyo3::exceptions::{PyIOError, PyValueError};
+use pyo3::prelude::*;
+
+use jp_dict::{OwnedDB, ResultSet, DB};
+
+/// Database wrapped by a [Dict]. Either the database from `jp_dict::get_db`
+/// or a database loaded at runtime.
+enum Source {
+	Embedded(&'static DB<'static>),
+	Loaded(OwnedDB),
+}
+
+impl Source {
+	fn with_db<T, F: FnOnce(&DB) -> T>(&self, f: F) -> T {
+		match self {
+			Source::Embedded(db) => f(db),
+			Source::Loaded(data) => f(&data.db()),
+		}
+	}
+}
+
+/// Dictionary database.
+#[pyclass(frozen)]
+struct Dict {
+	source: Arc<Source>,
+}
+
+#[pymethods]
+impl Dict {
+	/// Search for terms with an exact expression, reading or romaji key.
+	fn search_term(&self, term: &str) -> Vec<Term> {
+		let mut set = ResultSet::default();
+		self.source.with_db(|db| db.search_term(term, &mut set));
+		self.to_terms(set, None)
+	}
+
+	/// Search for terms with an expression, reading or romaji key starting
+	/// with the given prefix. Returns at most `limit` terms, if given.
+	#[pyo3(signature = (prefix, limit = None))]
+	fn search_prefix(&self, prefix: &str, limit: Option<usize>) -> Vec<Term> {
+		let mut set = ResultSet::default();
+		self.source.with_db(|db| db.search_prefix(prefix, &mut set));
+		self.to_terms(set, limit)
+	}
+
+	/// Returns a term by its index, or `None` if out of range.
+	fn term(&self, index: usize) -> Option<Term> {
+		if index < self.source.with_db(|db| db.term_count()) {
+			Some(self.to_term(index))
+		} else {
+			None
+		}
+	}
+
+	/// Iterates over all terms in the database, sorted by relevance.
+	fn terms(&self) -> TermIter {
+		TermIter {
+			source: self.source.clone(),
+			next: 0,
+		}
+	}
+
+	/// Returns the kanji entry for a character, or `None` if not available.
+	fn kanji(&self, character: char) -> Option<Kanji> {
+		let exists = self.source.with_db(|db| db.kanji(character).is_some());
+		exists.then(|| Kanji {
+			source: self.source.clone(),
+			character,
+		})
+	}
+
+	fn __len__(&self) -> usize {
+		self.source.with_db(|db| db.term_count())
+	}
+}
+
+impl Dict {
+	fn to_term(&self, index: usize) -> Term {
+		Term {
+			source: self.source.clone(),
+			index,
+		}
+	}
+
+	fn to_terms(&self, set: ResultSet, limit: Option<usize>) -> Vec<Term> {
+		let limit = limit.unwrap_or(set.len());
+		set.iter().take(limit).map(|x| self.to_term(x)).collect()
+	}
+}
+
+/// Iterator over the database terms.
+#[pyclass]
+struct TermIter {
+	source: Arc<Source>,
+	next: usize,
+}
+
+#[pymethods]
+impl TermIter {
+	fn __iter__(slf: PyRef<'_, Self>) -> PyRef<'_, Self> {
+		slf
+	}
+
+	fn __next__(&mut self) -> Option<Term> {
+		if self.next < self.source.with_db(|db| db.term_count()) {
+			let index = self.next;
+			self.next += 1;
+			Some(Term {
+				source: self.source.clone(),
+				index,
+			})
+		} else {
+			None
+		}
+	}
+}
+
+/// Term from the database.
+#[pyclass(frozen)]
+struct Term {
+	source: Arc<Source>,
+	index: usize,
+}
+
+impl Term {
+	fn with<T, F: FnOnce(jp_dict::Term) -> T>(&self, f: F) -> T {
+		self.source.with_db(|db| f(db.term(self.index).unwrap()))
+	}
+}
+
+#[pymethods]
+impl Term {
+	/// Index of the term in the database.
+	#[getter]
+	fn index(&self) -> usize {
+		self.index
+	}
+
+	#[getter]
+	fn expression(&self) -> String {
+		self.with(|x| x.expression().to_string())
+	}
+
+	#[getter]
+	fn reading(&self) -> String {
+		self.with(|x| x.reading().to_string())
+	}
+
+	#[getter]
+	fn search_key(&self) -> String {
+		self.with(|x| x.search_key().to_string())
+	}
+
+	#[getter]
+	fn score(&self) -> i32 {
+		self.with(|x| x.score())
+	}
+
+	#[getter]
+	fn sequence(&self) -> u32 {
+		self.with(|x| x.sequence())
+	}
+
+	#[getter]
+	fn frequency(&self) -> Option<u32> {
+		self.with(|x| x.frequency())
+	}
+
+	#[getter]
+	fn source(&self) -> String {
+		self.with(|x| x.source().to_string())
+	}
+
+	#[getter]
+	fn glossary(&self) -> Vec<String> {
+		self.with(|x| x.glossary().map(|x| x.to_string()).collect())
+	}
+
+	#[getter]
+	fn rules(&self) -> Vec<Tag> {
+		self.with(|x| x.rules().map(to_tag).collect())
+	}
+
+	#[getter]
+	fn term_tags(&self) -> Vec<Tag> {
+		self.with(|x| x.term_tags().map(to_tag).collect())
+	}
+
+	#[getter]
+	fn definition_tags(&self) -> Vec<Tag> {
+		self.with(|x| x.definition_tags().map(to_tag).collect())
+	}
+
+	fn __str__(&self) -> String {
+		self.with(|x| x.to_string())
+	}
+
+	fn __repr__(&self) -> String {
+		self.with(|x| {
+			format!(
+				"<Term #{} {} [{}]>",
+				self.index,
+				x.expression(),
+				x.reading()
+			)
+		})
+	}
+}
+
+/// Kanji from the database.
+#[pyclass(frozen)]
+struct Kanji {
+	source: Arc<Source>,
+	character: char,
+}
+
+impl Kanji {
+	fn with<T, F: FnOnce(jp_dict::Kanji) -> T>(&self, f: F) -> T {
+		self.source
+			.with_db(|db| f(db.kanji(self.character).unwrap()))
+	}
+}
+
+#[pymethods]
+impl Kanji {
+	#[getter]
+	fn character(&self) -> char {
+		self.character
+	}
+
+	#[getter]
+	fn frequency(&self) -> Option<u32> {
+		self.with(|x| x.frequency())
+	}
+
+	#[getter]
+	fn source(&self) -> String {
+		self.with(|x| x.source().to_string())
+	}
+
+	#[getter]
+	fn meanings(&self) -> Vec<String> {
+		self.with(|x| x.meanings().map(|x| x.to_string()).collect())
+	}
+
+	#[getter]
+	fn onyomi(&self) -> Vec<String> {
+		self.with(|x| x.onyomi().map(|x| x.to_string()).collect())
+	}
+
+	#[getter]
+	fn kunyomi(&self) -> Vec<String> {
+		self.with(|x| x.kunyomi().map(|x| x.to_string()).collect())
+	}
+
+	#[getter]
+	fn tags(&self) -> Vec<Tag> {
+		self.with(|x| x.tags().map(to_tag).collect())
+	}
+
+	fn __str__(&self) -> String {
+		self.with(|x| x.to_string())
+	}
+
+	fn __repr__(&self) -> String {
+		format!("<Kanji {}>", self.character)
+	}
+}
+
+/// Tag for a term or kanji.
+#[pyclass(frozen, get_all)]
+struct Tag {
+	name: String,
+	category: String,
+	notes: String,
+	order: i32,
+}
+
+#[pymethods]
+impl Tag {
+	fn __repr__(&self) -> String {
+		format!("<Tag {}>", self.name)
+	}
+}
+
+fn to_tag(tag: jp_dict::Tag) -> Tag {
+	Tag {
+		name: tag.name().to_string(),
+		category: tag.category().to_string(),
+		notes: tag.notes().to_string(),
+		order: tag.order(),
+	}
+}
+
+/// Returns the default dictionary database (see `jp_dict::get_db`).
+#[pyfunction]
+fn get_db() -> Dict {
+	Dict {
+		source: Arc::new(Source::Embedded(jp_dict::get_db())),
+	}
+}
+
+/// Opens a compiled dictionary database file (e.g. `data/dictionary.in`).
+///
+/// Raises `ValueError` if the file is not a valid database.
+#[pyfunction]
+fn open(path: &str) -> PyResult<Dict> {
+	let data = std::fs::read(path).map_err(|err| PyIOError::new_err(err.to_string()))?;
+	let data = OwnedDB::load(&data).map_err(PyValueError::new_err)?;
+	Ok(Dict {
+		source: Arc::new(Source::Loaded(data)),
+	})
+}
+
+#[pymodule]
+fn jpdict(m: &Bound<'_, PyModule>) -> PyResult<()> {
+	m.add_class::<Dict>()?;
+	m.add_class::<Term>()?;
+	m.add_class::<Kanji>()?;
+	m.add_class::<Tag>()?;
+	m.add_function(wrap_pyfunction!(get_db, m)?)?;
+	m.add_function(wrap_pyfunction!(open, m)?)?;
+	Ok(())
+}
@@ -0,0 +1,16 @@
+#!/bin/sh
+#
+# Vendors the dependencies into `vendor/` and installs `vendor.toml` as the
+# cargo config, so that the extension can be built with `--offline`.
+#
+# Pass `--offline` to vendor from the local cargo cache instead of the network.
+
+set -e
+cd "$(dirname "$0")"
+
+# Remove a previous config first, otherwise cargo would try to read the
+# vendored sources while vendoring them.
+rm -f .cargo/config.toml
+cargo vendor "$@" vendor > /dev/null
+mkdir -p .cargo
+cp vendor.toml .cargo/config.toml
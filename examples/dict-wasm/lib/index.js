@@ -0,0 +1,36 @@
+// Loads the compiled database into the wasm module and runs a few lookups.
+// Exits with an error code if any lookup fails, so it can be used as a test.
+
+const fs = require("fs");
+const assert = require("assert");
+const dict = require("../pkg");
+
+const file = process.argv[2] || "../../data/dictionary.in";
+dict.load(new Uint8Array(fs.readFileSync(file)));
+
+const count = dict.term_count();
+console.log(`\nLoaded ${count} terms from ${file}\n`);
+assert(count > 0);
+
+const terms = dict.search("日本", "exact");
+assert(terms.length > 0);
+for (const term of terms) {
+	assert.strictEqual(term.expression, "日本");
+	assert.deepStrictEqual(dict.term(term.index), term);
+	const frequency = term.frequency ? ` #${term.frequency}` : ``;
+	console.log(`${term.expression} [${term.reading}]${frequency} -- ${term.glossary.join(", ")}`);
+}
+
+const prefix = dict.search("にほん", "prefix", 5);
+assert(prefix.length > 0 && prefix.length <= 5);
+
+const fuzzy = dict.search("nihn", "fuzzy", 5);
+assert(fuzzy.length > 0);
+
+const kanji = dict.kanji("日");
+assert(kanji && kanji.character === "日");
+console.log(`\n${kanji.character} [${kanji.onyomi.join(", ")} / ${kanji.kunyomi.join(", ")}]`);
+assert.strictEqual(dict.kanji("a"), undefined);
+assert.strictEqual(dict.term(count), undefined);
+
+console.log(`\nOK`);
@@ -0,0 +1,176 @@
+//! WebAssembly bindings for the Japanese dictionary.
+//!
+//! The dictionary data is not embedded. It must be passed to [load] as the
+//! bytes of the compiled database (`data/dictionary.in`).
+
+use std::cell::RefCell;
+
+use js_sys::{Array, Object, Reflect};
+use wasm_bindgen::prelude::*;
+
+use db::{OwnedDB, Query, QueryMode, ResultSet, Tag, Term, DB};
+
+thread_local! {
+	static LOADED: RefCell<Option<OwnedDB>> = const { RefCell::new(None) };
+}
+
+/// Loads the database from the bytes of the compiled database, replacing any
+/// previously loaded database.
+///
+/// Throws an error if the bytes are not a valid database, in which case any
+/// previously loaded database is kept.
+#[wasm_bindgen]
+pub fn load(bytes: &[u8]) -> Result<(), JsValue> {
+	let loaded =
+		OwnedDB::load(bytes).map_err(|err| JsValue::from(format!("invalid database: {}", err)))?;
+	LOADED.with(|x| *x.borrow_mut() = Some(loaded));
+	Ok(())
+}
+
+/// Returns the number of terms in the loaded database.
+#[wasm_bindgen]
+pub fn term_count() -> Result<usize, JsValue> {
+	with_db(|db| db.term_count())
+}
+
+/// Default maximum edit distance for `fuzzy` searches.
+const FUZZY_DISTANCE: usize = 2;
+
+/// Searches for terms. The `mode` is one of `exact`, `prefix`, `suffix`,
+/// `pattern` or `fuzzy` and results are limited to `limit` terms, if given.
+/// For `fuzzy` searches, `distance` is the maximum edit distance (defaults to
+/// 2).
+///
+/// The `exact`, `prefix` and `suffix` searches stop collecting terms once the
+/// limit is reached. Results for `pattern` and `fuzzy` are sorted after all
+/// the matches are found, so the limit only applies to the returned terms.
+///
+/// Returns an array of term objects.
+#[wasm_bindgen]
+pub fn search(
+	query: &str,
+	mode: &str,
+	limit: Option<usize>,
+	distance: Option<usize>,
+) -> Result<Array, JsValue> {
+	with_db(|db| {
+		// A zero limit for the query means no limit.
+		if limit == Some(0) {
+			return Ok(Array::new());
+		}
+
+		let indexes: Vec<usize> = match mode {
+			"exact" | "prefix" | "suffix" => {
+				let mode = match mode {
+					"exact" => QueryMode::Exact,
+					"prefix" => QueryMode::Prefix,
+					_ => QueryMode::Suffix,
+				};
+				let query = Query::new(query, mode)
+					.terms_only()
+					.limit(limit.unwrap_or(0));
+				db.query(&query).terms.iter().collect()
+			}
+			"pattern" => {
+				let mut set = ResultSet::default();
+				db.search_pattern(query, &mut set);
+				set.iter().collect()
+			}
+			"fuzzy" => {
+				let distance = distance.unwrap_or(FUZZY_DISTANCE);
+				db.search_fuzzy(query, distance)
+					.into_iter()
+					.map(|x| x.term)
+					.collect()
+			}
+			_ => return Err(JsValue::from(format!("invalid search mode: {}", mode))),
+		};
+
+		let limit = limit.unwrap_or(indexes.len());
+		let out = Array::new();
+		for index in indexes.into_iter().take(limit) {
+			out.push(&to_term(db.term(index).unwrap()));
+		}
+		Ok(out)
+	})?
+}
+
+/// Returns a term object by its index, or `undefined` if the index is out of
+/// range.
+#[wasm_bindgen]
+pub fn term(index: usize) -> Result<JsValue, JsValue> {
+	with_db(|db| match db.term(index) {
+		Some(term) => to_term(term).into(),
+		None => JsValue::undefined(),
+	})
+}
+
+/// Returns a kanji object for the first character in `character`, or
+/// `undefined` if the kanji is not in the database.
+#[wasm_bindgen]
+pub fn kanji(character: &str) -> Result<JsValue, JsValue> {
+	with_db(|db| {
+		let kanji = character.chars().next().and_then(|chr| db.kanji(chr));
+		match kanji {
+			Some(kanji) => {
+				let out = Object::new();
+				set(&out, "character", kanji.character().to_string());
+				set(&out, "frequency", kanji.frequency());
+				set(&out, "source", kanji.source());
+				set(&out, "meanings", to_array(kanji.meanings()));
+				set(&out, "onyomi", to_array(kanji.onyomi()));
+				set(&out, "kunyomi", to_array(kanji.kunyomi()));
+				set(&out, "tags", to_tags(kanji.tags()));
+				out.into()
+			}
+			None => JsValue::undefined(),
+		}
+	})
+}
+
+fn with_db<F, T>(f: F) -> Result<T, JsValue>
+where
+	F: FnOnce(&DB) -> T,
+{
+	LOADED.with(|x| match x.borrow().as_ref() {
+		Some(loaded) => Ok(f(&loaded.db())),
+		None => Err(JsValue::from("database not loaded")),
+	})
+}
+
+fn to_term(term: Term) -> Object {
+	let out = Object::new();
+	set(&out, "index", term.index() as u32);
+	set(&out, "expression", term.expression());
+	set(&out, "reading", term.reading());
+	set(&out, "search_key", term.search_key());
+	set(&out, "score", term.score());
+	set(&out, "sequence", term.sequence());
+	set(&out, "frequency", term.frequency());
+	set(&out, "source", term.source());
+	set(&out, "glossary", to_array(term.glossary()));
+	set(&out, "rules", to_tags(term.rules()));
+	set(&out, "term_tags", to_tags(term.term_tags()));
+	set(&out, "definition_tags", to_tags(term.definition_tags()));
+	out
+}
+
+fn to_tags<'a, T: Iterator<Item = Tag<'a, 'a>>>(tags: T) -> Array {
+	tags.map(|tag| {
+		let out = Object::new();
+		set(&out, "name", tag.name());
+		set(&out, "category", tag.category());
+		set(&out, "notes", tag.notes());
+		set(&out, "order", tag.order());
+		JsValue::from(out)
+	})
+	.collect()
+}
+
+fn to_array<'a, T: Iterator<Item = &'a str>>(items: T) -> Array {
+	items.map(JsValue::from).collect()
+}
+
+fn set<V: Into<JsValue>>(target: &Object, key: &str, value: V) {
+	Reflect::set(target, &JsValue::from(key), &value.into()).unwrap();
+}
@@ -0,0 +1,193 @@
+//! Tests for the bindings using headless Node. Run with `wasm-pack test --node`.
+
+use db::{KanjiData, TagData, TermData, Writer};
+use js_sys::{Array, Reflect};
+use wasm_bindgen::JsValue;
+use wasm_bindgen_test::*;
+
+/// Builds a small database with a few terms and kanji.
+fn database() -> Vec<u8> {
+	let mut w = Writer::new();
+	let tag = TagData {
+		name: w.intern("P".to_string()),
+		category: w.intern("popular".to_string()),
+		order: 10,
+		notes: w.intern("common".to_string()),
+	};
+	w.push_tag(tag);
+
+	let terms = [
+		("日本", "にほん", "nihon", 9000, "Japan"),
+		("日本人", "にほんじん", "nihonjin", 4000, "Japanese person"),
+		("友達", "ともだち", "tomodachi", 6000, "friend"),
+	];
+	for (sequence, &(expression, reading, key, frequency, glossary)) in terms.iter().enumerate() {
+		let term = TermData {
+			expression: w.intern(expression.to_string()),
+			reading: w.intern(reading.to_string()),
+			search_key: w.intern(key.to_string()),
+			score: 0,
+			sequence: sequence as u32 + 1,
+			frequency,
+			glossary: vec![w.intern(glossary.to_string())],
+			rules: Vec::new(),
+			term_tags: w.get_tags(["P"].iter()),
+			definition_tags: Vec::new(),
+			source: w.intern("JMdict".to_string()),
+		};
+		w.push_term(term);
+	}
+
+	let kanji = KanjiData {
+		character: '日',
+		frequency: 900,
+		meanings: vec![w.intern("day".to_string()), w.intern("sun".to_string())],
+		onyomi: vec![w.intern("ニチ".to_string()), w.intern("ジツ".to_string())],
+		kunyomi: vec![w.intern("ひ".to_string())],
+		tags: Vec::new(),
+		stats: Vec::new(),
+		source: w.intern("KANJIDIC".to_string()),
+	};
+	w.push_kanji(kanji);
+
+	let mut data = Vec::new();
+	w.write(&mut data).unwrap();
+	data
+}
+
+/// Returns the offset of the string list in the database data.
+fn string_list_offset(data: &[u8]) -> usize {
+	// Item size for each section before the string list, in the order they
+	// are written by `Writer::write`.
+	const SECTIONS: [usize; 13] = [16, 60, 52, 8, 8, 12, 12, 20, 4, 16, 8, 12, 4];
+	let mut offset = 0;
+	for &size in SECTIONS.iter() {
+		let mut count = [0u8; 4];
+		count.copy_from_slice(&data[offset..offset + 4]);
+		offset += 4 + u32::from_le_bytes(count) as usize * size;
+	}
+	offset
+}
+
+fn get(value: &JsValue, key: &str) -> JsValue {
+	Reflect::get(value, &JsValue::from(key)).unwrap()
+}
+
+fn get_str(value: &JsValue, key: &str) -> String {
+	get(value, key).as_string().unwrap()
+}
+
+fn expressions(terms: &Array) -> Vec<String> {
+	terms
+		.iter()
+		.map(|term| get_str(&term, "expression"))
+		.collect()
+}
+
+#[wasm_bindgen_test]
+fn load() {
+	dict_wasm::load(&database()).unwrap();
+	assert_eq!(dict_wasm::term_count().unwrap(), 3);
+}
+
+#[wasm_bindgen_test]
+fn load_invalid() {
+	dict_wasm::load(&database()).unwrap();
+
+	let data = database();
+	assert!(dict_wasm::load(b"not a database").is_err());
+	assert!(dict_wasm::load(&data[..data.len() / 2]).is_err());
+
+	// Valid structure, but with a string handle out of bounds.
+	let mut data = database();
+	let offset = string_list_offset(&data) + 4;
+	data[offset..offset + 4].copy_from_slice(&0xFFFF_FFF0u32.to_le_bytes());
+	assert!(dict_wasm::load(&data).is_err());
+
+	// The previous database is kept.
+	assert_eq!(dict_wasm::term_count().unwrap(), 3);
+}
+
+#[wasm_bindgen_test]
+fn search() {
+	dict_wasm::load(&database()).unwrap();
+
+	let exact = dict_wasm::search("日本", "exact", None, None).unwrap();
+	assert_eq!(expressions(&exact), ["日本"]);
+	assert_eq!(get_str(&exact.get(0), "reading"), "にほん");
+
+	let prefix = dict_wasm::search("にほん", "prefix", None, None).unwrap();
+	assert_eq!(expressions(&prefix), ["日本", "日本人"]);
+	let limited = dict_wasm::search("にほん", "prefix", Some(1), None).unwrap();
+	assert_eq!(expressions(&limited), ["日本"]);
+
+	let suffix = dict_wasm::search("だち", "suffix", None, None).unwrap();
+	assert_eq!(expressions(&suffix), ["友達"]);
+
+	let fuzzy = dict_wasm::search("tomodati", "fuzzy", None, None).unwrap();
+	assert_eq!(expressions(&fuzzy), ["友達"]);
+	let fuzzy = dict_wasm::search("tomodati", "fuzzy", None, Some(0)).unwrap();
+	assert!(expressions(&fuzzy).is_empty());
+	let fuzzy = dict_wasm::search("nihonjn", "fuzzy", None, Some(1)).unwrap();
+	assert_eq!(expressions(&fuzzy), ["日本人"]);
+	let fuzzy = dict_wasm::search("nihonjn", "fuzzy", Some(1), Some(2)).unwrap();
+	assert_eq!(expressions(&fuzzy), ["日本人"]);
+
+	let pattern = dict_wasm::search("日本*", "pattern", None, None).unwrap();
+	assert_eq!(expressions(&pattern), ["日本", "日本人"]);
+	let pattern = dict_wasm::search("日本*", "pattern", Some(1), None).unwrap();
+	assert_eq!(expressions(&pattern), ["日本"]);
+
+	// The most frequent terms are kept when limiting the results.
+	let limited = dict_wasm::search("にほん", "prefix", Some(0), None).unwrap();
+	assert!(expressions(&limited).is_empty());
+	let limited = dict_wasm::search("日本人", "suffix", Some(5), None).unwrap();
+	assert_eq!(expressions(&limited), ["日本人"]);
+
+	assert!(dict_wasm::search("日本", "invalid", None, None).is_err());
+}
+
+#[wasm_bindgen_test]
+fn term() {
+	dict_wasm::load(&database()).unwrap();
+
+	let exact = dict_wasm::search("友達", "exact", None, None).unwrap();
+	let index = get(&exact.get(0), "index").as_f64().unwrap() as usize;
+	let term = dict_wasm::term(index).unwrap();
+	assert_eq!(get_str(&term, "expression"), "友達");
+	assert_eq!(get_str(&term, "search_key"), "tomodachi");
+	assert_eq!(get(&term, "frequency").as_f64(), Some(6000.0));
+
+	let glossary = Array::from(&get(&term, "glossary"));
+	assert_eq!(
+		glossary
+			.iter()
+			.map(|x| x.as_string().unwrap())
+			.collect::<Vec<_>>(),
+		["friend"]
+	);
+	let tags = Array::from(&get(&term, "term_tags"));
+	assert_eq!(get_str(&tags.get(0), "name"), "P");
+
+	assert!(dict_wasm::term(3).unwrap().is_undefined());
+}
+
+#[wasm_bindgen_test]
+fn kanji() {
+	dict_wasm::load(&database()).unwrap();
+
+	let kanji = dict_wasm::kanji("日本").unwrap();
+	assert_eq!(get_str(&kanji, "character"), "日");
+	assert_eq!(get_str(&kanji, "source"), "KANJIDIC");
+	let onyomi = Array::from(&get(&kanji, "onyomi"));
+	assert_eq!(
+		onyomi
+			.iter()
+			.map(|x| x.as_string().unwrap())
+			.collect::<Vec<_>>(),
+		["ニチ", "ジツ"]
+	);
+
+	assert!(dict_wasm::kanji("本").unwrap().is_undefined());
+	assert!(dict_wasm::kanji("").unwrap().is_undefined());
+}
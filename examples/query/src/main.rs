@@ -9,13 +9,61 @@ extern crate jp_dict;
 use rustyline::error::ReadlineError;
 use rustyline::Editor;
 
+/// Number of results shown at once. Use `:more` to show the next page.
+const PAGE_SIZE: usize = 5;
+
+const HELP: &'static str = "\
+Enter words to search (exact and prefix) or term numbers to display.
+
+Commands:
+    :kanji TEXT      show the kanji entries for each kanji in TEXT
+    :suffix WORD     search terms by suffix
+    :contains WORD   search terms containing WORD
+    :romaji WORD     search romaji keys allowing for one typo
+    :en TEXT         search the English glossary (slow)
+    :more            show more results from the last search
+    :tags            list all tags
+    :stats           check the database and show statistics
+    :help            show this help";
+
+/// Results from the last search, used for paging with `:more`.
+#[derive(Default)]
+struct Results {
+	indexes: Vec<usize>,
+	shown: usize,
+}
+
+impl Results {
+	fn set<T: Iterator<Item = usize>>(&mut self, indexes: T) {
+		self.indexes = indexes.collect();
+		self.shown = 0;
+	}
+
+	fn show_next(&mut self, db: &jp_dict::DB) {
+		let end = std::cmp::min(self.shown + PAGE_SIZE, self.indexes.len());
+		for &index in self.indexes[self.shown..end].iter() {
+			println!("\n{}", db.term(index).unwrap());
+		}
+		self.shown = end;
+
+		let remaining = self.indexes.len() - self.shown;
+		if remaining > 0 {
+			println!("\n... {} more result(s), use `:more` to show", remaining);
+		}
+	}
+}
+
 fn main() {
 	let start = std::time::Instant::now();
 	let db = jp_dict::get_db();
 	println!("\nLoaded in {:?}\n", start.elapsed());
-	db.check();
-	println!();
+	if let Err(err) = db.verify() {
+		eprintln!("Database check failed: {}\n", err);
+		std::process::exit(1);
+	}
+	println!("\nEnter `:help` for the list of commands.\n");
 
+	let mut results = Results::default();
 	let mut rl = Editor::<()>::new();
 	loop {
 		let input = rl.readline(">> ");
@@ -25,6 +73,12 @@ fn main() {
 				rl.add_history_entry(line);
 				println!();
 
+				if line.trim().starts_with(':') {
+					command(db, &mut results, line.trim());
+					println!();
+					continue;
+				}
+
 				let mut first = true;
 				for it in line.split(' ') {
 					let it = it.trim();
@@ -55,10 +109,10 @@ fn main() {
 								}
 
 								println!("Searching for `{}`...", it);
-								let mut results = jp_dict::ResultSet::default();
+								let mut set = jp_dict::ResultSet::default();
 
 								let start = Instant::now();
-								let count = db.search_term(it, &mut results);
+								let count = db.search_term(it, &mut set);
 								println!(
 									"- Exact search found {} term(s) in {:?}",
 									count,
@@ -66,16 +120,15 @@ fn main() {
 								);
 
 								let start = Instant::now();
-								let count = db.search_prefix(it, &mut results);
+								let count = db.search_prefix(it, &mut set);
 								println!(
 									"- Prefix search found {} term(s) in {:?}",
 									count,
 									start.elapsed()
 								);
 
-								for index in results.iter().take(5) {
-									println!("\n{}", db.term(index).unwrap());
-								}
+								results.set(set.iter());
+								results.show_next(db);
 							}
 						}
 					}
@@ -90,3 +143,87 @@ fn main() {
 		}
 	}
 }
+
+fn command(db: &jp_dict::DB, results: &mut Results, line: &str) {
+	let (name, arg) = match line.find(' ') {
+		Some(pos) => (&line[..pos], line[pos + 1..].trim()),
+		None => (line, ""),
+	};
+
+	let needs_arg = match name {
+		":kanji" | ":suffix" | ":contains" | ":romaji" | ":en" => true,
+		_ => false,
+	};
+	if needs_arg && arg.len() == 0 {
+		println!("Missing argument for `{}`", name);
+		return;
+	}
+
+	let start = Instant::now();
+	match name {
+		":kanji" => {
+			for chr in arg.chars().filter(|x| !x.is_whitespace()) {
+				match db.kanji(chr) {
+					Some(kanji) => println!("{}\n", kanji),
+					None => println!("Kanji {} not found\n", chr),
+				}
+			}
+			return;
+		}
+		":suffix" | ":contains" | ":en" => {
+			let mut set = jp_dict::ResultSet::default();
+			match name {
+				":suffix" => db.search_suffix(arg, &mut set),
+				":contains" => db.search_pattern(format!("*{}*", arg), &mut set),
+				_ => db.search_glossary(arg, &mut set),
+			};
+			results.set(set.iter());
+		}
+		":romaji" => {
+			let found = db.search_fuzzy(arg, 1);
+			results.set(found.into_iter().map(|x| x.term));
+		}
+		":more" => {
+			if results.shown < results.indexes.len() {
+				results.show_next(db);
+			} else {
+				println!("No more results");
+			}
+			return;
+		}
+		":tags" => {
+			for tag in db.tags() {
+				println!("{}", tag);
+			}
+			return;
+		}
+		":stats" => {
+			match db.verify() {
+				Ok(stats) => {
+					println!("Database check passed in {:?}\n", start.elapsed());
+					println!("{:#?}", stats);
+				}
+				Err(err) => println!("Database check failed: {}", err),
+			}
+			return;
+		}
+		":help" => {
+			println!("{}", HELP);
+			return;
+		}
+		_ => {
+			println!(
+				"Unknown command `{}`, use `:help` for the list of commands",
+				name
+			);
+			return;
+		}
+	}
+
+	println!(
+		"Found {} term(s) in {:?}",
+		results.indexes.len(),
+		start.elapsed()
+	);
+	results.show_next(db);
+}
@@ -0,0 +1,25 @@
+//! Exporting of a compiled database to other dictionary formats.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub mod sqlite;
+pub mod yomichan;
+
+#[cfg(test)]
+mod testing;
+
+/// Reads a compiled database file (e.g. `data/dictionary.in`).
+///
+/// The returned data must be loaded with `DB::load`.
+pub fn read_database<P: AsRef<Path>>(path: P) -> io::Result<Vec<u8>> {
+	let data = fs::read(path)?;
+	if data.is_empty() {
+		return Err(io::Error::new(
+			io::ErrorKind::InvalidData,
+			"database file is empty",
+		));
+	}
+	Ok(data)
+}
@@ -0,0 +1,333 @@
+//! Export to a normalized SQLite database (see `SCHEMA`).
+
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use rusqlite::{params, Connection};
+
+use db::DB;
+
+/// Schema for the exported database. This is also printed as part of the
+/// tool help.
+pub const SCHEMA: &str = "\
+-- Source dictionaries (e.g. `JMdict (English)`).
+CREATE TABLE source (
+    id          INTEGER PRIMARY KEY,
+    name        TEXT NOT NULL UNIQUE
+);
+
+-- Tags for terms and kanji. Tags are also the names for the kanji stats.
+CREATE TABLE tag (
+    id          INTEGER PRIMARY KEY,
+    name        TEXT NOT NULL UNIQUE,
+    category    TEXT NOT NULL,
+    sort_order  INTEGER NOT NULL,
+    notes       TEXT NOT NULL
+);
+
+-- Dictionary terms. The `id` is the term index in the database, which is
+-- sorted by frequency (lower is more frequent). `frequency` is NULL for
+-- terms not in the frequency data.
+CREATE TABLE term (
+    id          INTEGER PRIMARY KEY,
+    expression  TEXT NOT NULL,
+    reading     TEXT NOT NULL,
+    search_key  TEXT NOT NULL,
+    score       INTEGER NOT NULL,
+    sequence    INTEGER NOT NULL,
+    frequency   INTEGER,
+    source_id   INTEGER NOT NULL REFERENCES source (id)
+);
+
+-- English definitions for each term, in order.
+CREATE TABLE glossary (
+    term_id     INTEGER NOT NULL REFERENCES term (id),
+    position    INTEGER NOT NULL,
+    text        TEXT NOT NULL,
+    PRIMARY KEY (term_id, position)
+);
+
+-- Tags for each term. `kind` is `term` for the term tags, `definition` for
+-- the definition tags and `rule` for the deinflection rules (e.g. `v5`).
+CREATE TABLE term_tag (
+    term_id     INTEGER NOT NULL REFERENCES term (id),
+    tag_id      INTEGER NOT NULL REFERENCES tag (id),
+    kind        TEXT NOT NULL CHECK (kind IN ('term', 'definition', 'rule')),
+    PRIMARY KEY (term_id, kind, tag_id)
+);
+
+-- Kanji entries. The same character can have an entry in each source
+-- dictionary. `frequency` is NULL for kanji not in the frequency data.
+CREATE TABLE kanji (
+    id          INTEGER PRIMARY KEY,
+    character   TEXT NOT NULL,
+    frequency   INTEGER,
+    source_id   INTEGER NOT NULL REFERENCES source (id)
+);
+
+-- English meanings for each kanji, in order.
+CREATE TABLE kanji_meaning (
+    kanji_id    INTEGER NOT NULL REFERENCES kanji (id),
+    position    INTEGER NOT NULL,
+    text        TEXT NOT NULL,
+    PRIMARY KEY (kanji_id, position)
+);
+
+-- Kanji readings. `kind` is either `onyomi` or `kunyomi`.
+CREATE TABLE kanji_reading (
+    kanji_id    INTEGER NOT NULL REFERENCES kanji (id),
+    kind        TEXT NOT NULL CHECK (kind IN ('onyomi', 'kunyomi')),
+    position    INTEGER NOT NULL,
+    reading     TEXT NOT NULL,
+    PRIMARY KEY (kanji_id, kind, position)
+);
+
+-- Tags for each kanji.
+CREATE TABLE kanji_tag (
+    kanji_id    INTEGER NOT NULL REFERENCES kanji (id),
+    tag_id      INTEGER NOT NULL REFERENCES tag (id),
+    PRIMARY KEY (kanji_id, tag_id)
+);
+
+-- Additional kanji information (e.g. `strokes`, `grade` or `jlpt`), with
+-- the stat name in the tag table.
+CREATE TABLE kanji_stat (
+    kanji_id    INTEGER NOT NULL REFERENCES kanji (id),
+    tag_id      INTEGER NOT NULL REFERENCES tag (id),
+    value       TEXT NOT NULL,
+    PRIMARY KEY (kanji_id, tag_id)
+);
+
+CREATE INDEX term_expression ON term (expression);
+CREATE INDEX term_reading ON term (reading);
+CREATE INDEX term_sequence ON term (source_id, sequence);
+CREATE INDEX term_tag_tag ON term_tag (tag_id);
+CREATE INDEX kanji_character ON kanji (character);
+CREATE INDEX kanji_tag_tag ON kanji_tag (tag_id);
+";
+
+/// Example query for the tool help.
+pub const EXAMPLE: &str = "\
+-- Godan verbs with a frequency over 1000 from JMdict:
+SELECT term.expression, term.reading, term.frequency
+FROM term
+JOIN source ON source.id = term.source_id
+JOIN term_tag ON term_tag.term_id = term.id AND term_tag.kind = 'rule'
+JOIN tag ON tag.id = term_tag.tag_id
+WHERE tag.name = 'v5' AND term.frequency > 1000
+  AND source.name LIKE 'JMdict%'
+ORDER BY term.frequency DESC;
+";
+
+/// Exports the database to a new SQLite file at `output`. An existing file
+/// is replaced.
+pub fn export<P: AsRef<Path>>(db: &DB, output: P) -> io::Result<()> {
+	let output = output.as_ref();
+	if output.exists() {
+		fs::remove_file(output)?;
+	}
+
+	let mut conn = Connection::open(output).map_err(to_io)?;
+	conn.execute_batch(SCHEMA).map_err(to_io)?;
+
+	// Everything is inserted in a single transaction, otherwise SQLite
+	// commits on every insert.
+	let tx = conn.transaction().map_err(to_io)?;
+	write_data(db, &tx).map_err(to_io)?;
+	tx.commit().map_err(to_io)?;
+	Ok(())
+}
+
+fn write_data(db: &DB, conn: &Connection) -> rusqlite::Result<()> {
+	let mut tags = HashMap::new();
+	{
+		let mut insert = conn.prepare(
+			"INSERT INTO tag (id, name, category, sort_order, notes) VALUES (?, ?, ?, ?, ?)",
+		)?;
+		for (index, tag) in db.tags().enumerate() {
+			let id = index as i64;
+			insert.execute(params![
+				id,
+				tag.name(),
+				tag.category(),
+				tag.order(),
+				tag.notes()
+			])?;
+			tags.insert(tag.name(), id);
+		}
+	}
+
+	let mut sources = HashMap::new();
+	let mut source_id = |name: &str| -> rusqlite::Result<i64> {
+		if let Some(&id) = sources.get(name) {
+			return Ok(id);
+		}
+		let id = sources.len() as i64;
+		conn.execute(
+			"INSERT INTO source (id, name) VALUES (?, ?)",
+			params![id, name],
+		)?;
+		sources.insert(name.to_string(), id);
+		Ok(id)
+	};
+
+	{
+		let mut insert_term = conn.prepare(
+			"INSERT INTO term (id, expression, reading, search_key, score, sequence, frequency, source_id) \
+			 VALUES (?, ?, ?, ?, ?, ?, ?, ?)",
+		)?;
+		let mut insert_glossary =
+			conn.prepare("INSERT INTO glossary (term_id, position, text) VALUES (?, ?, ?)")?;
+		let mut insert_tag = conn
+			.prepare("INSERT OR IGNORE INTO term_tag (term_id, tag_id, kind) VALUES (?, ?, ?)")?;
+
+		for index in 0..db.term_count() {
+			let term = db.term(index).unwrap();
+			let id = index as i64;
+			insert_term.execute(params![
+				id,
+				term.expression(),
+				term.reading(),
+				term.search_key(),
+				term.score(),
+				term.sequence(),
+				term.frequency(),
+				source_id(term.source())?
+			])?;
+
+			for (position, text) in term.glossary().enumerate() {
+				insert_glossary.execute(params![id, position as i64, text])?;
+			}
+
+			let kinds: Vec<(&str, Vec<_>)> = vec![
+				("term", term.term_tags().collect()),
+				("definition", term.definition_tags().collect()),
+				("rule", term.rules().collect()),
+			];
+			for (kind, list) in kinds {
+				for tag in list {
+					insert_tag.execute(params![id, tags[tag.name()], kind])?;
+				}
+			}
+		}
+	}
+
+	{
+		let mut insert_kanji = conn.prepare(
+			"INSERT INTO kanji (id, character, frequency, source_id) VALUES (?, ?, ?, ?)",
+		)?;
+		let mut insert_meaning =
+			conn.prepare("INSERT INTO kanji_meaning (kanji_id, position, text) VALUES (?, ?, ?)")?;
+		let mut insert_reading = conn.prepare(
+			"INSERT INTO kanji_reading (kanji_id, kind, position, reading) VALUES (?, ?, ?, ?)",
+		)?;
+		let mut insert_tag =
+			conn.prepare("INSERT OR IGNORE INTO kanji_tag (kanji_id, tag_id) VALUES (?, ?)")?;
+		let mut insert_stat = conn.prepare(
+			"INSERT OR REPLACE INTO kanji_stat (kanji_id, tag_id, value) VALUES (?, ?, ?)",
+		)?;
+
+		for (index, kanji) in db.all_kanji().enumerate() {
+			let id = index as i64;
+			let chr = kanji.character().to_string();
+			let source = source_id(kanji.source())?;
+			insert_kanji.execute(params![id, chr, kanji.frequency(), source])?;
+
+			for (position, text) in kanji.meanings().enumerate() {
+				insert_meaning.execute(params![id, position as i64, text])?;
+			}
+			for (position, reading) in kanji.onyomi().enumerate() {
+				insert_reading.execute(params![id, "onyomi", position as i64, reading])?;
+			}
+			for (position, reading) in kanji.kunyomi().enumerate() {
+				insert_reading.execute(params![id, "kunyomi", position as i64, reading])?;
+			}
+			for tag in kanji.tags() {
+				insert_tag.execute(params![id, tags[tag.name()]])?;
+			}
+			for (tag, value) in kanji.stats() {
+				insert_stat.execute(params![id, tags[tag.name()], value])?;
+			}
+		}
+	}
+
+	Ok(())
+}
+
+fn to_io(err: rusqlite::Error) -> io::Error {
+	io::Error::other(err)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+
+	use rusqlite::{Connection, NO_PARAMS};
+
+	use db::DB;
+
+	use super::super::testing;
+
+	#[test]
+	fn export() {
+		let data = testing::database();
+		let db = DB::load(&data);
+
+		let path = std::env::temp_dir().join(format!("jp-dict-sqlite-{}.db", std::process::id()));
+		super::export(&db, &path).unwrap();
+		let conn = Connection::open(&path).unwrap();
+
+		let rows: Vec<(String, String, u32)> = query(&conn, super::EXAMPLE);
+		assert_eq!(rows, vec![("書く".to_string(), "かく".to_string(), 2000)]);
+
+		// The same kanji can be in more than one source.
+		let rows: Vec<(String, String, u32)> = query(
+			&conn,
+			"SELECT source.name, kanji_meaning.text, kanji.frequency
+			FROM kanji
+			JOIN source ON source.id = kanji.source_id
+			JOIN kanji_meaning ON kanji_meaning.kanji_id = kanji.id
+			WHERE kanji.character = '東'
+			ORDER BY source.name",
+		);
+		let expected = vec![
+			("KANJIDIC".to_string(), "east".to_string(), 200),
+			("KANJIDIC (French)".to_string(), "est".to_string(), 200),
+		];
+		assert_eq!(rows, expected);
+
+		let rows: Vec<(String, String, String)> = query(
+			&conn,
+			"SELECT kanji.character, tag.name, kanji_stat.value
+			FROM kanji_stat
+			JOIN kanji ON kanji.id = kanji_stat.kanji_id
+			JOIN tag ON tag.id = kanji_stat.tag_id
+			ORDER BY kanji.character, tag.name",
+		);
+		let expected = vec![
+			("東".to_string(), "grade".to_string(), "2".to_string()),
+			("東".to_string(), "strokes".to_string(), "8".to_string()),
+			("食".to_string(), "strokes".to_string(), "9".to_string()),
+		];
+		assert_eq!(rows, expected);
+
+		drop(conn);
+		fs::remove_file(&path).unwrap();
+	}
+
+	/// Runs the query, returning the first three columns of each row.
+	fn query<A, B, C>(conn: &Connection, sql: &str) -> Vec<(A, B, C)>
+	where
+		A: rusqlite::types::FromSql,
+		B: rusqlite::types::FromSql,
+		C: rusqlite::types::FromSql,
+	{
+		let mut query = conn.prepare(sql).unwrap();
+		let rows = query
+			.query_map(NO_PARAMS, |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)))
+			.unwrap();
+		rows.collect::<rusqlite::Result<_>>().unwrap()
+	}
+}
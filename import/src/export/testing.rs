@@ -0,0 +1,170 @@
+//! Test database for the exporters.
+
+use db::{KanjiData, TagData, TermData, Writer};
+
+/// Returns the data for a small database with terms from two sources and
+/// kanji from two others, using tags, kanji stats and frequencies. The kanji
+/// `東` is in both kanji sources.
+pub fn database() -> Vec<u8> {
+	let mut w = Writer::new();
+	let tags = [
+		("P", "popular", "common word", -10),
+		("n", "partOfSpeech", "noun", 0),
+		("v1", "partOfSpeech", "Ichidan verb", 0),
+		("v5", "partOfSpeech", "Godan verb", 0),
+		("adj-i", "partOfSpeech", "adjective (keiyoushi)", 0),
+		("place", "name", "place name", 0),
+		(
+			"jouyou",
+			"frequent",
+			"included in list of regular-use characters",
+			0,
+		),
+		("strokes", "misc", "Stroke count", 0),
+		("grade", "misc", "School grade", 0),
+	];
+	for &(name, category, notes, order) in tags.iter() {
+		let tag = TagData {
+			name: w.intern(name.to_string()),
+			category: w.intern(category.to_string()),
+			order,
+			notes: w.intern(notes.to_string()),
+		};
+		w.push_tag(tag);
+	}
+
+	let glossary = ["to eat", "to live on (e.g. a salary)"];
+	let mut term = term_data(&mut w, "JMdict", "食べる", "たべる", &glossary);
+	term.score = 2;
+	term.sequence = 1358280;
+	term.frequency = 500;
+	term.rules = w.get_tags(["v1"]);
+	term.term_tags = w.get_tags(["P"]);
+	term.definition_tags = w.get_tags(["v1"]);
+	w.push_term(term);
+
+	let mut term = term_data(&mut w, "JMdict", "東京", "とうきょう", &["Tokyo"]);
+	term.sequence = 1444130;
+	term.frequency = 1000;
+	term.term_tags = w.get_tags(["P"]);
+	term.definition_tags = w.get_tags(["n"]);
+	w.push_term(term);
+
+	// Terms without a reading are exported as is.
+	let mut term = term_data(&mut w, "JMdict", "すごい", "", &["amazing"]);
+	term.score = -1;
+	term.sequence = 1374550;
+	term.rules = w.get_tags(["adj-i"]);
+	term.definition_tags = w.get_tags(["adj-i"]);
+	w.push_term(term);
+
+	let mut term = term_data(&mut w, "JMdict", "書く", "かく", &["to write"]);
+	term.sequence = 1206730;
+	term.frequency = 2000;
+	term.rules = w.get_tags(["v5"]);
+	term.term_tags = w.get_tags(["P"]);
+	term.definition_tags = w.get_tags(["v5"]);
+	w.push_term(term);
+
+	let mut term = term_data(&mut w, "JMdict", "行く", "いく", &["to go"]);
+	term.sequence = 1578850;
+	term.frequency = 800;
+	term.rules = w.get_tags(["v5"]);
+	term.definition_tags = w.get_tags(["v5"]);
+	w.push_term(term);
+
+	// Frequencies are by expression, so this is the same as in `JMdict`.
+	let mut term = term_data(&mut w, "JMnedict", "東京", "とうきょう", &["Tokyo (place)"]);
+	term.sequence = 5000000;
+	term.frequency = 1000;
+	term.definition_tags = w.get_tags(["place"]);
+	w.push_term(term);
+
+	let mut term = term_data(&mut w, "JMnedict", "京都", "きょうと", &["Kyoto"]);
+	term.sequence = 5000001;
+	term.definition_tags = w.get_tags(["place", "n"]);
+	w.push_term(term);
+
+	let mut kanji = kanji_data(&mut w, '東', &["トウ"], &["ひがし"], &["east"]);
+	kanji.frequency = 200;
+	kanji.stats = stats(&mut w, &[("strokes", "8"), ("grade", "2")]);
+	w.push_kanji(kanji);
+
+	let kunyomi = ["く.う", "た.べる"];
+	let mut kanji = kanji_data(
+		&mut w,
+		'食',
+		&["ショク", "ジキ"],
+		&kunyomi,
+		&["eat", "food"],
+	);
+	kanji.stats = stats(&mut w, &[("strokes", "9")]);
+	w.push_kanji(kanji);
+
+	// Kanji frequencies are by character, so this is the same as above.
+	let mut kanji = kanji_data(&mut w, '東', &["トウ"], &["ひがし"], &["est"]);
+	kanji.frequency = 200;
+	kanji.source = w.intern("KANJIDIC (French)".to_string());
+	w.push_kanji(kanji);
+
+	let mut data = Vec::new();
+	w.write(&mut data).unwrap();
+	data
+}
+
+/// Returns the data for a term with no tags and a zero score, sequence and
+/// frequency.
+fn term_data(
+	w: &mut Writer,
+	source: &str,
+	expression: &str,
+	reading: &str,
+	glossary: &[&str],
+) -> TermData {
+	TermData {
+		expression: w.intern(expression.to_string()),
+		reading: w.intern(reading.to_string()),
+		search_key: w.intern(expression.to_string()),
+		score: 0,
+		sequence: 0,
+		frequency: 0,
+		glossary: strings(w, glossary),
+		rules: Vec::new(),
+		term_tags: Vec::new(),
+		definition_tags: Vec::new(),
+		source: w.intern(source.to_string()),
+	}
+}
+
+/// Returns the data for a `jouyou` kanji from the `KANJIDIC` source with no
+/// stats and a zero frequency.
+fn kanji_data(
+	w: &mut Writer,
+	character: char,
+	onyomi: &[&str],
+	kunyomi: &[&str],
+	meanings: &[&str],
+) -> KanjiData {
+	KanjiData {
+		character,
+		frequency: 0,
+		meanings: strings(w, meanings),
+		onyomi: strings(w, onyomi),
+		kunyomi: strings(w, kunyomi),
+		tags: w.get_tags(["jouyou"]),
+		stats: Vec::new(),
+		source: w.intern("KANJIDIC".to_string()),
+	}
+}
+
+/// Kanji stats from `(tag, value)` pairs.
+fn stats(w: &mut Writer, stats: &[(&str, &str)]) -> Vec<(u32, u32)> {
+	stats
+		.iter()
+		.map(|&(name, value)| (w.get_tag(name), w.intern(value.to_string())))
+		.collect()
+}
+
+fn strings(w: &mut Writer, values: &[&str]) -> Vec<u32> {
+	values.iter().map(|x| w.intern(x.to_string())).collect()
+}
@@ -0,0 +1,329 @@
+//! Export to the Yomichan dictionary format (format 3).
+//!
+//! Each source dictionary in the database is written to its own zip file,
+//! with the same layout read by the importer:
+//!
+//! - `index.json` with the title (the source name), format and revision;
+//! - `term_bank_N.json` and `kanji_bank_N.json` with the entries;
+//! - `tag_bank_N.json` with all tags in the database;
+//! - `term_meta_bank_N.json` and `kanji_meta_bank_N.json` with the `freq`
+//!   entries for the terms and kanji in the dictionary.
+//!
+//! Importing the generated files reproduces the same database. The term
+//! indexes and the order of the tags may change, since those depend on the
+//! import order.
+
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+use serde_json::{json, Map, Value};
+use zip::write::FileOptions;
+use zip::ZipWriter;
+
+use db::{Kanji, Tag, Term, DB};
+
+/// Maximum number of rows in a single bank file.
+const BANK_SIZE: usize = 10000;
+
+/// Revision written to `index.json`.
+const REVISION: &str = "jp-dict export";
+
+/// Exports the database to `output_dir`, writing one zip file per source
+/// dictionary. Returns the list of generated files.
+pub fn export<P: AsRef<Path>>(db: &DB, output_dir: P) -> io::Result<Vec<PathBuf>> {
+	let output_dir = output_dir.as_ref();
+	fs::create_dir_all(output_dir)?;
+
+	// Group the entries by source dictionary, keeping the database order.
+	let mut sources: BTreeMap<&str, (Vec<Term>, Vec<Kanji>)> = BTreeMap::new();
+	for index in 0..db.term_count() {
+		let term = db.term(index).unwrap();
+		sources.entry(term.source()).or_default().0.push(term);
+	}
+	for kanji in db.all_kanji() {
+		sources.entry(kanji.source()).or_default().1.push(kanji);
+	}
+
+	let tags: Vec<Value> = db.tags().map(|x| tag_row(&x)).collect();
+
+	let mut files = Vec::new();
+	let mut used_names = HashSet::new();
+	for (source, (terms, kanji)) in sources {
+		// Sources with the same file name (after replacing the invalid
+		// characters) get a numeric suffix.
+		let base_name = file_name(source);
+		let mut name = base_name.clone();
+		let mut counter = 1;
+		while !used_names.insert(name.clone()) {
+			counter += 1;
+			name = format!("{}_{}", base_name, counter);
+		}
+
+		let mut path = output_dir.to_path_buf();
+		path.push(format!("{}.zip", name));
+		println!(
+			"... writing {} ({} terms, {} kanji)",
+			path.to_string_lossy(),
+			terms.len(),
+			kanji.len()
+		);
+
+		let file = io::BufWriter::new(fs::File::create(&path)?);
+		let mut zip = ZipWriter::new(file);
+		write_json(
+			&mut zip,
+			"index.json",
+			&json!({
+				"title": source,
+				"format": 3,
+				"revision": REVISION,
+				"sequenced": true,
+			}),
+		)?;
+
+		let term_rows: Vec<Value> = terms.iter().map(term_row).collect();
+		write_banks(&mut zip, "term", term_rows)?;
+
+		let kanji_rows: Vec<Value> = kanji.iter().map(kanji_row).collect();
+		write_banks(&mut zip, "kanji", kanji_rows)?;
+
+		write_banks(&mut zip, "tag", tags.clone())?;
+
+		// Frequencies are stored by expression, so repeated expressions
+		// are written only once.
+		let mut seen = HashSet::new();
+		let term_meta: Vec<Value> = terms
+			.iter()
+			.filter_map(|term| match term.frequency() {
+				Some(freq) if seen.insert(term.expression()) => {
+					Some(json!([term.expression(), "freq", freq]))
+				}
+				_ => None,
+			})
+			.collect();
+		write_banks(&mut zip, "term_meta", term_meta)?;
+
+		let kanji_meta: Vec<Value> = kanji
+			.iter()
+			.filter_map(|kanji| {
+				let chr = kanji.character().to_string();
+				kanji.frequency().map(|freq| json!([chr, "freq", freq]))
+			})
+			.collect();
+		write_banks(&mut zip, "kanji_meta", kanji_meta)?;
+
+		zip.finish()?;
+		files.push(path);
+	}
+
+	Ok(files)
+}
+
+/// Term row: `[expression, reading, definition tags, rules, score, glossary,
+/// sequence, term tags]`.
+fn term_row(term: &Term) -> Value {
+	let glossary: Vec<&str> = term.glossary().collect();
+	json!([
+		term.expression(),
+		term.reading(),
+		tag_names(term.definition_tags()),
+		tag_names(term.rules()),
+		term.score(),
+		glossary,
+		term.sequence(),
+		tag_names(term.term_tags()),
+	])
+}
+
+/// Kanji row: `[character, onyomi, kunyomi, tags, meanings, stats]`.
+fn kanji_row(kanji: &Kanji) -> Value {
+	let onyomi: Vec<&str> = kanji.onyomi().collect();
+	let kunyomi: Vec<&str> = kanji.kunyomi().collect();
+	let meanings: Vec<&str> = kanji.meanings().collect();
+	let mut stats = Map::new();
+	for (tag, value) in kanji.stats() {
+		stats.insert(tag.name().to_string(), Value::from(value));
+	}
+	json!([
+		kanji.character().to_string(),
+		onyomi.join(" "),
+		kunyomi.join(" "),
+		tag_names(kanji.tags()),
+		meanings,
+		stats,
+	])
+}
+
+/// Tag row: `[name, category, order, notes, score]`.
+fn tag_row(tag: &Tag) -> Value {
+	json!([tag.name(), tag.category(), tag.order(), tag.notes(), 0])
+}
+
+/// Tag names separated by spaces, as used in the term and kanji rows.
+fn tag_names<'a, T: Iterator<Item = Tag<'a, 'a>>>(tags: T) -> String {
+	tags.map(|x| x.name()).collect::<Vec<_>>().join(" ")
+}
+
+/// Writes the rows to `{kind}_bank_N.json` files with at most `BANK_SIZE`
+/// rows each. Nothing is written if there are no rows.
+fn write_banks<W: Write + io::Seek>(
+	zip: &mut ZipWriter<W>,
+	kind: &str,
+	rows: Vec<Value>,
+) -> io::Result<()> {
+	for (index, chunk) in rows.chunks(BANK_SIZE).enumerate() {
+		let name = format!("{}_bank_{}.json", kind, index + 1);
+		write_json(zip, &name, &Value::from(chunk.to_vec()))?;
+	}
+	Ok(())
+}
+
+fn write_json<W: Write + io::Seek>(
+	zip: &mut ZipWriter<W>,
+	name: &str,
+	value: &Value,
+) -> io::Result<()> {
+	zip.start_file(name, FileOptions::default())?;
+	serde_json::to_writer(&mut *zip, value)?;
+	Ok(())
+}
+
+/// File name for a source dictionary, replacing the characters that are not
+/// valid in file names.
+fn file_name(source: &str) -> String {
+	let name: String = source
+		.chars()
+		.map(|chr| match chr {
+			'/' | '\\' | ':' | '*' | '?' | '"' | '<' | '>' | '|' => '_',
+			chr if chr.is_control() => '_',
+			chr => chr,
+		})
+		.collect();
+	let name = name.trim().trim_matches('.');
+	if !name.is_empty() {
+		name.to_string()
+	} else {
+		"dictionary".to_string()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use std::fs;
+
+	use db::{Tag, DB};
+
+	use super::super::testing;
+	use generate::Wrapper;
+	use import::import_file;
+
+	#[test]
+	fn round_trip() {
+		let data = testing::database();
+		let db = DB::load(&data);
+
+		let dir = std::env::temp_dir().join(format!("jp-dict-yomichan-{}", std::process::id()));
+		let files = super::export(&db, &dir).unwrap();
+		let names: Vec<_> = files
+			.iter()
+			.map(|x| x.file_name().unwrap().to_string_lossy().to_string())
+			.collect();
+		let expected = [
+			"JMdict.zip",
+			"JMnedict.zip",
+			"KANJIDIC.zip",
+			"KANJIDIC (French).zip",
+		];
+		assert_eq!(names, expected);
+
+		let mut wrapper = Wrapper::default();
+		for path in files.iter() {
+			wrapper.import_dict(import_file(path).unwrap());
+		}
+		fs::remove_dir_all(&dir).unwrap();
+
+		let mut imported = Vec::new();
+		wrapper.into_writer().write(&mut imported).unwrap();
+		let imported = DB::load(&imported);
+
+		assert_eq!(terms(&imported), terms(&db));
+		assert_eq!(kanji(&imported), kanji(&db));
+		assert_eq!(tags(&imported), tags(&db));
+	}
+
+	/// Sorted term fields, except for the index and the search key, which is
+	/// generated by the importer.
+	fn terms(db: &DB) -> Vec<String> {
+		let mut terms: Vec<String> = (0..db.term_count())
+			.map(|index| {
+				let term = db.term(index).unwrap();
+				let glossary: Vec<&str> = term.glossary().collect();
+				let fields = (
+					term.source(),
+					term.expression(),
+					term.reading(),
+					term.score(),
+					term.sequence(),
+					term.frequency(),
+					glossary,
+					names(term.rules()),
+					names(term.term_tags()),
+					names(term.definition_tags()),
+				);
+				format!("{:?}", fields)
+			})
+			.collect();
+		terms.sort();
+		terms
+	}
+
+	/// Sorted kanji fields.
+	fn kanji(db: &DB) -> Vec<String> {
+		let mut kanji: Vec<String> = db
+			.all_kanji()
+			.map(|kanji| {
+				let mut stats: Vec<_> = kanji
+					.stats()
+					.map(|(tag, value)| (tag.name(), value))
+					.collect();
+				stats.sort();
+				let fields = (
+					kanji.source(),
+					kanji.character(),
+					kanji.frequency(),
+					kanji.meanings().collect::<Vec<_>>(),
+					kanji.onyomi().collect::<Vec<_>>(),
+					kanji.kunyomi().collect::<Vec<_>>(),
+					names(kanji.tags()),
+					stats,
+				);
+				format!("{:?}", fields)
+			})
+			.collect();
+		kanji.sort();
+		kanji
+	}
+
+	/// Sorted tag fields.
+	fn tags(db: &DB) -> Vec<String> {
+		let mut tags: Vec<String> = db
+			.tags()
+			.map(|tag| {
+				format!(
+					"{:?}",
+					(tag.name(), tag.category(), tag.notes(), tag.order())
+				)
+			})
+			.collect();
+		tags.sort();
+		tags
+	}
+
+	fn names<'a, T: Iterator<Item = Tag<'a, 'a>>>(tags: T) -> Vec<&'a str> {
+		let mut names: Vec<&str> = tags.map(|x| x.name()).collect();
+		names.sort();
+		names
+	}
+}
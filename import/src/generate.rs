@@ -56,6 +56,14 @@ impl Wrapper {
 
 	/// Outputs all data to code files.
 	pub fn output(self) -> Result<()> {
+		let w = self.into_writer();
+		println!("... writing data/dictionary.in...");
+		let mut output = BufWriter::new(fs::File::create("data/dictionary.in")?);
+		w.write(&mut output)
+	}
+
+	/// Returns a database writer with all imported data.
+	pub fn into_writer(self) -> db::Writer {
 		let mut w = db::Writer::new();
 
 		let mut tag_order = HashMap::new();
@@ -152,9 +160,7 @@ impl Wrapper {
 			w.push_term(term);
 		}
 
-		println!("... writing data/dictionary.in...");
-		let mut output = BufWriter::new(fs::File::create("data/dictionary.in")?);
-		w.write(&mut output)
+		w
 	}
 
 	fn import_tag(&mut self, tag: Tag) {
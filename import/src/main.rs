@@ -1,4 +1,5 @@
 extern crate regex;
+extern crate rusqlite;
 extern crate serde;
 extern crate serde_json;
 extern crate unicase;
@@ -17,6 +18,27 @@ use unicase::UniCase;
 
 const IMPORT_DATA_DIRECTORY: &'static str = "data";
 
+const DATABASE_FILE: &str = "data/dictionary.in";
+
+const USAGE: &str = "\
+Usage:
+    import [import]
+        Imports all Yomichan dictionaries (zip files) from the `data`
+        directory and generates `data/dictionary.in`.
+
+    import export-yomichan [--input FILE] OUTPUT_DIR
+        Exports the database as Yomichan dictionaries (format 3), writing one
+        zip file per source dictionary to OUTPUT_DIR. The database is read
+        from `data/dictionary.in` unless `--input` is given.
+
+    import export-sqlite [--input FILE] OUTPUT_FILE
+        Exports the database to a new SQLite file at OUTPUT_FILE (replacing
+        any existing file). See the schema below.
+
+    import help
+        Prints this help.
+";
+
 mod generate;
 
 mod dict;
@@ -24,7 +46,29 @@ mod dict;
 mod import;
 use import::import_file;
 
+mod export;
+
 fn main() {
+	let args: Vec<String> = std::env::args().skip(1).collect();
+	match args.first().map(|x| x.as_str()) {
+		None | Some("import") if args.len() <= 1 => run_import(),
+		Some("export-yomichan") => run_export(&args[1..], Export::Yomichan),
+		Some("export-sqlite") => run_export(&args[1..], Export::Sqlite),
+		Some("help") | Some("-h") | Some("--help") => {
+			print!("{}", USAGE);
+			println!("\nSQLite schema:\n");
+			print!("{}", export::sqlite::SCHEMA);
+			println!("\nExample query:\n");
+			print!("{}", export::sqlite::EXAMPLE);
+		}
+		_ => {
+			eprintln!("\nERROR: invalid arguments: {}\n\n{}", args.join(" "), USAGE);
+			std::process::exit(1);
+		}
+	}
+}
+
+fn run_import() {
 	let start = std::time::Instant::now();
 
 	// Validate the import data directory:
@@ -53,6 +97,67 @@ fn main() {
 	}
 }
 
+#[derive(Copy, Clone)]
+enum Export {
+	Yomichan,
+	Sqlite,
+}
+
+fn run_export(args: &[String], format: Export) {
+	let mut input = DATABASE_FILE.to_string();
+	let mut output = None;
+	let mut args = args.iter();
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"--input" => match args.next() {
+				Some(value) => input = value.clone(),
+				None => {
+					eprintln!("\nERROR: missing value for --input\n\n{}", USAGE);
+					std::process::exit(1);
+				}
+			},
+			_ if output.is_none() && !arg.starts_with('-') => output = Some(arg.clone()),
+			_ => {
+				eprintln!("\nERROR: invalid argument `{}`\n\n{}", arg, USAGE);
+				std::process::exit(1);
+			}
+		}
+	}
+
+	let output = match output {
+		Some(output) => output,
+		None => {
+			eprintln!("\nERROR: missing output path\n\n{}", USAGE);
+			std::process::exit(1);
+		}
+	};
+
+	let start = std::time::Instant::now();
+	println!("\nExporting {} to {}...", input, output);
+	let data = match export::read_database(&input) {
+		Ok(data) => data,
+		Err(err) => {
+			eprintln!("\nERROR: could not read {}: {:}\n", input, err);
+			std::process::exit(2);
+		}
+	};
+
+	let db = db::DB::load(&data[..]);
+	let result = match format {
+		Export::Yomichan => export::yomichan::export(&db, &output).map(|files| files.len()),
+		Export::Sqlite => export::sqlite::export(&db, &output).map(|_| 1),
+	};
+	match result {
+		Ok(count) => {
+			println!("\nExported {} file(s) after {:?}\n", count, start.elapsed());
+		}
+		Err(err) => {
+			eprintln!("\nERROR: export failed: {:}\n", err);
+			std::process::exit(2);
+		}
+	}
+}
+
 fn import<P: AsRef<std::path::Path>>(import_dir: P) -> std::io::Result<()> {
 	let start = std::time::Instant::now();
 	let mut entries = Vec::new();
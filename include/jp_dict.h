@@ -0,0 +1,395 @@
+/* C API for the jp-dict Japanese dictionary library.
+ *
+ * Generated with cbindgen from `src/ffi.rs` (see `make header`). */
+
+#ifndef JP_DICT_H
+#define JP_DICT_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * String list for a kanji.
+ */
+enum JpDictKanjiList
+#ifdef __cplusplus
+  : uint32_t
+#endif // __cplusplus
+ {
+  JP_DICT_KANJI_LIST_MEANINGS = 0,
+  JP_DICT_KANJI_LIST_ONYOMI = 1,
+  JP_DICT_KANJI_LIST_KUNYOMI = 2,
+};
+#ifndef __cplusplus
+typedef uint32_t JpDictKanjiList;
+#endif // __cplusplus
+
+/**
+ * Search mode for [jp_dict_search].
+ */
+enum JpDictSearchMode
+#ifdef __cplusplus
+  : uint32_t
+#endif // __cplusplus
+ {
+  /**
+   * Exact match of the term expression, reading or search key.
+   */
+  JP_DICT_SEARCH_MODE_EXACT = 0,
+  /**
+   * Prefix match of the term expression, reading or search key.
+   */
+  JP_DICT_SEARCH_MODE_PREFIX = 1,
+  /**
+   * Suffix match of the term expression, reading or search key.
+   */
+  JP_DICT_SEARCH_MODE_SUFFIX = 2,
+  /**
+   * Wildcard pattern search (see `DB::search_pattern`).
+   */
+  JP_DICT_SEARCH_MODE_PATTERN = 3,
+};
+#ifndef __cplusplus
+typedef uint32_t JpDictSearchMode;
+#endif // __cplusplus
+
+/**
+ * Tag list for a term.
+ */
+enum JpDictTermTags
+#ifdef __cplusplus
+  : uint32_t
+#endif // __cplusplus
+ {
+  JP_DICT_TERM_TAGS_TERM_TAGS = 0,
+  JP_DICT_TERM_TAGS_DEFINITION_TAGS = 1,
+  JP_DICT_TERM_TAGS_RULES = 2,
+};
+#ifndef __cplusplus
+typedef uint32_t JpDictTermTags;
+#endif // __cplusplus
+
+/**
+ * Opaque handle for a database.
+ */
+typedef struct JpDictDb JpDictDb;
+
+/**
+ * Opaque handle for a list of search results.
+ */
+typedef struct JpDictResults JpDictResults;
+
+/**
+ * UTF-8 string as a pointer and byte length. Not NUL terminated.
+ */
+typedef struct JpDictStr {
+  const uint8_t *ptr;
+  uintptr_t len;
+} JpDictStr;
+
+/**
+ * Tag fields.
+ */
+typedef struct JpDictTag {
+  struct JpDictStr name;
+  struct JpDictStr category;
+  struct JpDictStr notes;
+  int32_t order;
+} JpDictTag;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Returns the database embedded in the library. The returned handle must
+ * not be closed.
+ */
+const struct JpDictDb *jp_dict_get_db(void);
+
+/**
+ * Opens a database file (e.g. `data/dictionary.in`) at runtime.
+ *
+ * Returns NULL if the file cannot be read or is not a valid database. The
+ * handle must be freed with [jp_dict_close].
+ *
+ * # Safety
+ *
+ * `path` must be NULL or point to a NUL terminated string.
+ */
+struct JpDictDb *jp_dict_open(const char *path);
+
+/**
+ * Closes a database opened with [jp_dict_open]. Does nothing for the
+ * embedded database or a NULL handle.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a handle that has not been closed. Strings returned
+ * for the database must not be used after it is closed.
+ */
+void jp_dict_close(struct JpDictDb *db);
+
+/**
+ * Returns the number of terms in the database.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle.
+ */
+uintptr_t jp_dict_term_count(const struct JpDictDb *db);
+
+/**
+ * Searches the database for the given UTF-8 query.
+ *
+ * `mode` is one of the [JpDictSearchMode] values. Returns NULL if the mode
+ * is invalid or the query is not valid UTF-8. The results must be freed with
+ * [jp_dict_results_free].
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle and `query` must be NULL or
+ * point to `query_len` readable bytes.
+ */
+struct JpDictResults *jp_dict_search(const struct JpDictDb *db,
+                                     uint32_t mode,
+                                     const uint8_t *query,
+                                     uintptr_t query_len);
+
+/**
+ * Fuzzy searches the romaji search keys in the database (see
+ * `DB::search_fuzzy`). Results are sorted by edit distance.
+ *
+ * Returns NULL if the query is not valid UTF-8. The results must be freed
+ * with [jp_dict_results_free].
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle and `query` must be NULL or
+ * point to `query_len` readable bytes.
+ */
+struct JpDictResults *jp_dict_search_fuzzy(const struct JpDictDb *db,
+                                           const uint8_t *query,
+                                           uintptr_t query_len,
+                                           uintptr_t max_distance);
+
+/**
+ * Returns the number of terms in the search results.
+ *
+ * # Safety
+ *
+ * `results` must be NULL or search results that have not been freed.
+ */
+uintptr_t jp_dict_results_len(const struct JpDictResults *results);
+
+/**
+ * Returns the term index for the n-th search result, or `SIZE_MAX` if out
+ * of range.
+ *
+ * # Safety
+ *
+ * `results` must be NULL or search results that have not been freed.
+ */
+uintptr_t jp_dict_results_get(const struct JpDictResults *results, uintptr_t n);
+
+/**
+ * Frees search results.
+ *
+ * # Safety
+ *
+ * `results` must be NULL or search results that have not been freed. The
+ * results must not be used after this call.
+ */
+void jp_dict_results_free(struct JpDictResults *results);
+
+/**
+ * Term expression. Empty if the term index is out of range.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle. The returned string is only
+ * valid while the database is open.
+ */
+struct JpDictStr jp_dict_term_expression(const struct JpDictDb *db, uintptr_t index);
+
+/**
+ * Term reading. Empty if not available or the term index is out of range.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle. The returned string is only
+ * valid while the database is open.
+ */
+struct JpDictStr jp_dict_term_reading(const struct JpDictDb *db, uintptr_t index);
+
+/**
+ * Term romaji search key. Empty if the term index is out of range.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle. The returned string is only
+ * valid while the database is open.
+ */
+struct JpDictStr jp_dict_term_search_key(const struct JpDictDb *db, uintptr_t index);
+
+/**
+ * Term source dictionary name. Empty if the term index is out of range.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle. The returned string is only
+ * valid while the database is open.
+ */
+struct JpDictStr jp_dict_term_source(const struct JpDictDb *db, uintptr_t index);
+
+/**
+ * Term frequency. Zero if not available or the term index is out of range.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle.
+ */
+uint32_t jp_dict_term_frequency(const struct JpDictDb *db, uintptr_t index);
+
+/**
+ * Term sequence number in the source dictionary. Zero if not available or
+ * the term index is out of range.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle.
+ */
+uint32_t jp_dict_term_sequence(const struct JpDictDb *db, uintptr_t index);
+
+/**
+ * Term score. Zero if the term index is out of range.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle.
+ */
+int32_t jp_dict_term_score(const struct JpDictDb *db, uintptr_t index);
+
+/**
+ * Number of glossary entries for the term.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle.
+ */
+uintptr_t jp_dict_term_glossary_len(const struct JpDictDb *db, uintptr_t index);
+
+/**
+ * Returns the n-th glossary entry for the term. Empty if out of range.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle. The returned string is only
+ * valid while the database is open.
+ */
+struct JpDictStr jp_dict_term_glossary(const struct JpDictDb *db, uintptr_t index, uintptr_t n);
+
+/**
+ * Number of tags in the given tag list for the term. `list` is one of the
+ * [JpDictTermTags] values.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle.
+ */
+uintptr_t jp_dict_term_tags_len(const struct JpDictDb *db, uintptr_t index, uint32_t list);
+
+/**
+ * Returns the n-th tag in the given tag list for the term. All fields are
+ * empty if out of range or if `list` is not a [JpDictTermTags] value.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle. The tag
+ * strings are only valid while the database is open.
+ */
+struct JpDictTag jp_dict_term_tag(const struct JpDictDb *db,
+                                  uintptr_t index,
+                                  uint32_t list,
+                                  uintptr_t n);
+
+/**
+ * Returns true if the database has an entry for the given kanji (as an
+ * unicode code point).
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle.
+ */
+bool jp_dict_kanji_exists(const struct JpDictDb *db, uint32_t kanji);
+
+/**
+ * Kanji frequency. Zero if not available or the kanji does not exist.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle.
+ */
+uint32_t jp_dict_kanji_frequency(const struct JpDictDb *db, uint32_t kanji);
+
+/**
+ * Kanji source dictionary name. Empty if the kanji does not exist.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle. The returned string is only
+ * valid while the database is open.
+ */
+struct JpDictStr jp_dict_kanji_source(const struct JpDictDb *db, uint32_t kanji);
+
+/**
+ * Number of strings in the given list for the kanji. `list` is one of the
+ * [JpDictKanjiList] values.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle.
+ */
+uintptr_t jp_dict_kanji_list_len(const struct JpDictDb *db, uint32_t kanji, uint32_t list);
+
+/**
+ * Returns the n-th string in the given list for the kanji. Empty if out of
+ * range or if `list` is not a [JpDictKanjiList] value.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle. The returned string is only
+ * valid while the database is open.
+ */
+struct JpDictStr jp_dict_kanji_list_get(const struct JpDictDb *db,
+                                        uint32_t kanji,
+                                        uint32_t list,
+                                        uintptr_t n);
+
+/**
+ * Number of tags for the kanji.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle.
+ */
+uintptr_t jp_dict_kanji_tags_len(const struct JpDictDb *db, uint32_t kanji);
+
+/**
+ * Returns the n-th tag for the kanji. All fields are empty if out of range.
+ *
+ * # Safety
+ *
+ * `db` must be NULL or a valid database handle. The tag
+ * strings are only valid while the database is open.
+ */
+struct JpDictTag jp_dict_kanji_tag(const struct JpDictDb *db, uint32_t kanji, uintptr_t n);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* JP_DICT_H */
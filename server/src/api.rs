@@ -0,0 +1,283 @@
+//! Request routing and API endpoints.
+//!
+//! All endpoints return JSON. Errors are returned with an HTTP error status
+//! and an `{"error": "..."}` body.
+//!
+//! - `GET /health`: returns `{"status": "ok"}`.
+//! - `GET /stats`: database statistics.
+//! - `GET /search?q=QUERY&mode=MODE&offset=N&limit=N`: term search. The
+//!   `mode` is one of `exact` (default), `prefix`, `suffix` or `romaji`. The
+//!   `romaji` mode allows for typos in the romaji key, up to the `distance`
+//!   parameter (default 1). Prefix results are in the order of the matching
+//!   keys instead of by frequency (see `DB::iter_prefix`).
+//! - `GET /term/INDEX`: term by its index.
+//! - `GET /kanji/CHAR`: kanji entry for the given character.
+//! - `GET /scan?text=TEXT` or `POST /scan`: segments the text (or the request
+//!   body for `POST`) into dictionary terms.
+
+use serde::Serialize;
+use serde_json::Value;
+
+use jp_dict::DB;
+
+use model;
+
+/// Default number of results per page for searches.
+const DEFAULT_LIMIT: usize = 20;
+
+/// Maximum number of results per page for searches.
+const MAX_LIMIT: usize = 100;
+
+/// Default maximum edit distance for the `romaji` search mode.
+const DEFAULT_DISTANCE: usize = 1;
+
+/// Maximum edit distance for the `romaji` search mode.
+const MAX_DISTANCE: usize = 3;
+
+/// Maximum text length in characters for the scan endpoint.
+const MAX_SCAN_CHARS: usize = 10000;
+
+pub type Result = std::result::Result<Value, Error>;
+
+/// Error response.
+#[derive(Debug)]
+pub struct Error {
+	pub status: u16,
+	pub message: String,
+}
+
+impl Error {
+	pub fn bad_request<S: Into<String>>(message: S) -> Error {
+		Error {
+			status: 400,
+			message: message.into(),
+		}
+	}
+
+	pub fn not_found<S: Into<String>>(message: S) -> Error {
+		Error {
+			status: 404,
+			message: message.into(),
+		}
+	}
+
+	pub fn method_not_allowed() -> Error {
+		Error {
+			status: 405,
+			message: "method not allowed".to_string(),
+		}
+	}
+}
+
+/// Handles a request for the given URL. The `body` is only available for
+/// `POST` requests.
+pub fn handle(db: &DB, url: &str, body: Option<&str>) -> Result {
+	let (path, query) = match url.find('?') {
+		Some(pos) => (&url[..pos], &url[pos + 1..]),
+		None => (url, ""),
+	};
+	let query = Query::parse(query)?;
+
+	let path = decode(path)?;
+	let mut segments = path.split('/').filter(|x| !x.is_empty());
+	let route = (segments.next(), segments.next(), segments.next());
+
+	let is_post = body.is_some();
+	match route {
+		(Some("health"), None, None) if !is_post => to_json(serde_json::json!({ "status": "ok" })),
+		(Some("stats"), None, None) if !is_post => to_json(db.stats()),
+		(Some("search"), None, None) if !is_post => search(db, &query),
+		(Some("term"), Some(index), None) if !is_post => term(db, index),
+		(Some("kanji"), Some(chr), None) if !is_post => kanji(db, chr),
+		(Some("scan"), None, None) => match body {
+			Some(text) => scan(db, text),
+			None => scan(db, query.get("text").unwrap_or("")),
+		},
+		(Some("health"), None, None)
+		| (Some("stats"), None, None)
+		| (Some("search"), None, None)
+		| (Some("term"), Some(_), None)
+		| (Some("kanji"), Some(_), None) => Err(Error::method_not_allowed()),
+		_ => Err(Error::not_found("not found")),
+	}
+}
+
+fn search(db: &DB, query: &Query) -> Result {
+	let input = query.get("q").unwrap_or("");
+	if input.is_empty() {
+		return Err(Error::bad_request("missing search query `q`"));
+	}
+
+	let offset = query.get_number("offset", 0)?;
+	let limit = query.get_number("limit", DEFAULT_LIMIT)?;
+	if limit > MAX_LIMIT {
+		return Err(Error::bad_request(format!(
+			"limit must be at most {}",
+			MAX_LIMIT
+		)));
+	}
+
+	// The exact and prefix searches are lazy, so only the requested page of
+	// terms is collected. The other modes collect all matches.
+	let (total, indexes): (usize, Vec<usize>) = match query.get("mode").unwrap_or("exact") {
+		mode @ "exact" | mode @ "prefix" => {
+			let iter = || {
+				if mode == "exact" {
+					db.iter_term(input)
+				} else {
+					db.iter_prefix(input)
+				}
+			};
+			(iter().count(), iter().skip(offset).take(limit).collect())
+		}
+		"suffix" => {
+			let mut set = jp_dict::ResultSet::default();
+			db.search_suffix(input, &mut set);
+			(set.len(), set.iter().skip(offset).take(limit).collect())
+		}
+		"romaji" => {
+			let distance = query.get_number("distance", DEFAULT_DISTANCE)?;
+			if distance > MAX_DISTANCE {
+				let message = format!("distance must be at most {}", MAX_DISTANCE);
+				return Err(Error::bad_request(message));
+			}
+			let found = db.search_fuzzy(input, distance);
+			let indexes = found.iter().skip(offset).take(limit).map(|x| x.term);
+			(found.len(), indexes.collect())
+		}
+		mode => {
+			return Err(Error::bad_request(format!(
+				"invalid search mode `{}`",
+				mode
+			)))
+		}
+	};
+
+	let terms = indexes
+		.into_iter()
+		.map(|index| db.term(index).unwrap().to_owned_term())
+		.collect();
+	to_json(model::SearchResult {
+		total,
+		offset,
+		limit,
+		terms,
+	})
+}
+
+fn term(db: &DB, index: &str) -> Result {
+	let term = index.parse::<usize>().ok().and_then(|index| db.term(index));
+	match term {
+		Some(term) => to_json(term),
+		None => Err(Error::not_found(format!("term `{}` not found", index))),
+	}
+}
+
+fn kanji(db: &DB, chr: &str) -> Result {
+	let mut chars = chr.chars();
+	let kanji = match (chars.next(), chars.next()) {
+		(Some(chr), None) => db.kanji(chr),
+		_ => return Err(Error::bad_request("expected a single character")),
+	};
+	match kanji {
+		Some(kanji) => to_json(kanji),
+		None => Err(Error::not_found(format!("kanji `{}` not found", chr))),
+	}
+}
+
+fn scan(db: &DB, text: &str) -> Result {
+	if text.chars().count() > MAX_SCAN_CHARS {
+		let message = format!("text must be at most {} characters", MAX_SCAN_CHARS);
+		return Err(Error::bad_request(message));
+	}
+
+	let reading = db.to_reading(text);
+	let segments = reading
+		.segments
+		.into_iter()
+		.map(|it| model::ScanSegment {
+			text: it.text.to_string(),
+			reading: it.reading,
+			term: it.term.map(|index| db.term(index).unwrap().to_owned_term()),
+		})
+		.collect();
+	to_json(model::ScanResult {
+		reading: reading.reading,
+		segments,
+	})
+}
+
+fn to_json<T: Serialize>(value: T) -> Result {
+	Ok(serde_json::to_value(value).unwrap())
+}
+
+/// Parsed URL query parameters.
+struct Query {
+	params: Vec<(String, String)>,
+}
+
+impl Query {
+	fn parse(query: &str) -> std::result::Result<Query, Error> {
+		let mut params = Vec::new();
+		for it in query.split('&').filter(|x| !x.is_empty()) {
+			let (key, value) = match it.find('=') {
+				Some(pos) => (&it[..pos], &it[pos + 1..]),
+				None => (it, ""),
+			};
+			params.push((decode_query(key)?, decode_query(value)?));
+		}
+		Ok(Query { params })
+	}
+
+	fn get(&self, key: &str) -> Option<&str> {
+		self.params
+			.iter()
+			.find(|x| x.0 == key)
+			.map(|x| x.1.as_str())
+	}
+
+	fn get_number(&self, key: &str, default: usize) -> std::result::Result<usize, Error> {
+		match self.get(key) {
+			Some(value) => value
+				.parse()
+				.map_err(|_| Error::bad_request(format!("invalid number for `{}`", key))),
+			None => Ok(default),
+		}
+	}
+}
+
+/// Decodes a percent-encoded query string component, where a `+` is a
+/// space.
+fn decode_query(input: &str) -> std::result::Result<String, Error> {
+	decode(&input.replace('+', " "))
+}
+
+/// Decodes a percent-encoded URL component.
+///
+/// A `+` is kept as is, since it only means a space in the query string (see
+/// [decode_query]).
+fn decode(input: &str) -> std::result::Result<String, Error> {
+	let input = input.as_bytes();
+	let mut out = Vec::with_capacity(input.len());
+	let mut pos = 0;
+	while pos < input.len() {
+		match input[pos] {
+			b'%' => {
+				let hex = input.get(pos + 1..pos + 3);
+				let hex = hex.filter(|x| x.iter().all(|c| c.is_ascii_hexdigit()));
+				let hex = hex.and_then(|x| std::str::from_utf8(x).ok());
+				let byte = hex.and_then(|x| u8::from_str_radix(x, 16).ok());
+				match byte {
+					Some(byte) => out.push(byte),
+					None => return Err(Error::bad_request("invalid URL encoding")),
+				}
+				pos += 3;
+			}
+			byte => {
+				out.push(byte);
+				pos += 1;
+			}
+		}
+	}
+	String::from_utf8(out).map_err(|_| Error::bad_request("invalid UTF-8 in URL"))
+}
@@ -0,0 +1,77 @@
+//! Local HTTP server for dictionary lookups.
+//!
+//! Serves a dictionary database as JSON, so that multiple tools can share a
+//! single dictionary process. See [api] for the endpoints and [run] for
+//! serving requests.
+
+extern crate serde;
+extern crate serde_json;
+extern crate tiny_http;
+
+extern crate jp_dict;
+
+use tiny_http::{Header, Method, Request, Response, Server};
+
+use jp_dict::DB;
+
+pub mod api;
+mod model;
+
+/// Maximum size for a request body.
+const MAX_BODY_SIZE: usize = 1024 * 1024;
+
+/// Handles the incoming requests for the server with the given number of
+/// worker threads, answering them from the `db`.
+///
+/// Returns once all workers stop receiving requests.
+pub fn run(server: &Server, db: &DB, threads: usize) {
+	std::thread::scope(|scope| {
+		for _ in 0..threads {
+			scope.spawn(|| {
+				for request in server.incoming_requests() {
+					handle(db, request);
+				}
+			});
+		}
+	});
+}
+
+fn handle(db: &DB, mut request: Request) {
+	let post = match request.method() {
+		Method::Get => false,
+		Method::Post => true,
+		_ => {
+			respond(request, Err(api::Error::method_not_allowed()));
+			return;
+		}
+	};
+
+	let mut body = String::new();
+	if post {
+		use std::io::Read;
+		let mut reader = request.as_reader().take(MAX_BODY_SIZE as u64 + 1);
+		let valid = reader.read_to_string(&mut body).is_ok() && body.len() <= MAX_BODY_SIZE;
+		if !valid {
+			respond(request, Err(api::Error::bad_request("invalid request body")));
+			return;
+		}
+	}
+
+	let body = if post { Some(body.as_str()) } else { None };
+	let result = api::handle(db, request.url(), body);
+	respond(request, result);
+}
+
+fn respond(request: Request, result: api::Result) {
+	let (status, value) = match result {
+		Ok(value) => (200, value),
+		Err(err) => (err.status, serde_json::json!({ "error": err.message })),
+	};
+	let content_type = Header::from_bytes(&b"Content-Type"[..], &b"application/json"[..]).unwrap();
+	let response = Response::from_string(value.to_string())
+		.with_status_code(status)
+		.with_header(content_type);
+	if let Err(err) = request.respond(response) {
+		eprintln!("ERROR: failed to send response: {}", err);
+	}
+}
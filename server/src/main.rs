@@ -0,0 +1,66 @@
+//! Local HTTP server for dictionary lookups.
+//!
+//! Serves the embedded dictionary database as JSON, so that multiple tools
+//! can share a single dictionary process. See the `server` library for the
+//! endpoints.
+
+extern crate tiny_http;
+
+extern crate jp_dict;
+extern crate server;
+
+use tiny_http::Server;
+
+/// Default listening address. Only the loopback interface is used by
+/// default, since the server has no authentication.
+const DEFAULT_ADDRESS: &str = "127.0.0.1:7171";
+
+/// Number of threads handling requests.
+const WORKER_THREADS: usize = 4;
+
+fn main() {
+	let mut address = DEFAULT_ADDRESS.to_string();
+	let mut args = std::env::args().skip(1);
+	while let Some(arg) = args.next() {
+		match arg.as_str() {
+			"-a" | "--address" => match args.next() {
+				Some(value) => address = value,
+				None => exit_with_usage("missing value for --address"),
+			},
+			"-h" | "--help" => {
+				print_usage();
+				return;
+			}
+			_ => exit_with_usage(&format!("invalid argument `{}`", arg)),
+		}
+	}
+
+	let start = std::time::Instant::now();
+	let db = jp_dict::get_db();
+	println!("\nLoaded {} terms in {:?}", db.term_count(), start.elapsed());
+
+	let listener = match Server::http(&address) {
+		Ok(listener) => listener,
+		Err(err) => {
+			eprintln!("\nERROR: could not listen on {}: {}\n", address, err);
+			std::process::exit(1);
+		}
+	};
+	println!("Listening on http://{}/\n", address);
+
+	server::run(&listener, db, WORKER_THREADS);
+}
+
+fn print_usage() {
+	println!("Usage: jp-dict-server [--address ADDRESS]");
+	println!();
+	println!("Options:");
+	println!("    -a, --address ADDRESS   listening address (default {})", DEFAULT_ADDRESS);
+	println!("    -h, --help              print this help");
+}
+
+fn exit_with_usage(error: &str) -> ! {
+	eprintln!("ERROR: {}\n", error);
+	print_usage();
+	std::process::exit(1);
+}
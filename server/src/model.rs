@@ -0,0 +1,31 @@
+//! JSON response structures.
+
+use serde::Serialize;
+
+use jp_dict::OwnedTerm;
+
+/// Page of search results.
+#[derive(Serialize)]
+pub struct SearchResult {
+	/// Total number of matches for the search.
+	pub total: usize,
+	pub offset: usize,
+	pub limit: usize,
+	pub terms: Vec<OwnedTerm>,
+}
+
+/// Text segmented into dictionary terms (see `DB::to_reading`).
+#[derive(Serialize)]
+pub struct ScanResult {
+	/// Kana reading for the whole text.
+	pub reading: String,
+	pub segments: Vec<ScanSegment>,
+}
+
+#[derive(Serialize)]
+pub struct ScanSegment {
+	pub text: String,
+	pub reading: String,
+	/// Term matching the segment, if any.
+	pub term: Option<OwnedTerm>,
+}
@@ -0,0 +1,351 @@
+//! Tests the server endpoints over HTTP, using a small database served on a
+//! loopback port.
+
+extern crate jp_dict;
+extern crate serde_json;
+extern crate server;
+extern crate tiny_http;
+
+use std::io::{Read, Write};
+use std::net::{SocketAddr, TcpStream};
+use std::sync::OnceLock;
+
+use serde_json::Value;
+
+use jp_dict::{KanjiData, OwnedDB, TagData, TermData, Writer, DB};
+
+/// Returns the address for the test server, starting it on the first call.
+///
+/// The server runs for the whole test process, so the database is leaked.
+fn address() -> SocketAddr {
+	static ADDRESS: OnceLock<SocketAddr> = OnceLock::new();
+	*ADDRESS.get_or_init(|| {
+		let data: &'static OwnedDB = Box::leak(Box::new(OwnedDB::load(&build()).unwrap()));
+		let db: &'static DB = Box::leak(Box::new(data.db()));
+		let listener = tiny_http::Server::http("127.0.0.1:0").unwrap();
+		let address = listener.server_addr().to_ip().unwrap();
+		std::thread::spawn(move || server::run(&listener, db, 2));
+		address
+	})
+}
+
+/// Builds the test database.
+fn build() -> Vec<u8> {
+	let mut w = Writer::new();
+	let tag = TagData {
+		name: w.intern("P".to_string()),
+		category: w.intern("popular".to_string()),
+		order: 0,
+		notes: w.intern("popular term".to_string()),
+	};
+	w.push_tag(tag);
+
+	let terms = [
+		("東京", "とうきょう", "toukyou", 100),
+		("京都", "きょうと", "kyouto", 50),
+		("東", "ひがし", "higashi", 10),
+		("東口", "ひがしぐち", "higashiguchi", 0),
+	];
+	for &(expression, reading, search_key, frequency) in terms.iter() {
+		let term = TermData {
+			expression: w.intern(expression.to_string()),
+			reading: w.intern(reading.to_string()),
+			search_key: w.intern(search_key.to_string()),
+			score: 0,
+			sequence: 0,
+			frequency,
+			glossary: vec![w.intern(format!("{} (glossary)", expression))],
+			rules: Vec::new(),
+			term_tags: w.get_tags(["P"]),
+			definition_tags: Vec::new(),
+			source: w.intern("JMdict".to_string()),
+		};
+		w.push_term(term);
+	}
+
+	let kanji = KanjiData {
+		character: '東',
+		frequency: 0,
+		meanings: vec![w.intern("east".to_string())],
+		onyomi: vec![w.intern("トウ".to_string())],
+		kunyomi: vec![w.intern("ひがし".to_string())],
+		tags: Vec::new(),
+		stats: Vec::new(),
+		source: w.intern("KANJIDIC".to_string()),
+	};
+	w.push_kanji(kanji);
+
+	let mut data = Vec::new();
+	w.write(&mut data).unwrap();
+	data
+}
+
+/// Sends a request to the test server, returning the status and JSON body.
+///
+/// Uses HTTP/1.0, so that the response is not chunked.
+fn request(method: &str, path: &str, body: &str) -> (u16, Value) {
+	let mut stream = TcpStream::connect(address()).unwrap();
+	write!(
+		stream,
+		"{} {} HTTP/1.0\r\nHost: localhost\r\nContent-Length: {}\r\n\r\n{}",
+		method,
+		path,
+		body.len(),
+		body
+	)
+	.unwrap();
+
+	let mut response = String::new();
+	stream.read_to_string(&mut response).unwrap();
+	let status = response[9..12].parse().unwrap();
+	let body = &response[response.find("\r\n\r\n").unwrap() + 4..];
+	(status, serde_json::from_str(body).unwrap())
+}
+
+fn get(path: &str) -> (u16, Value) {
+	request("GET", path, "")
+}
+
+/// Percent-encodes all non-ASCII characters in the path.
+fn encode(path: &str) -> String {
+	let mut out = String::new();
+	for chr in path.chars() {
+		if chr.is_ascii() {
+			out.push(chr);
+		} else {
+			let mut bytes = [0u8; 4];
+			for byte in chr.encode_utf8(&mut bytes).bytes() {
+				out.push_str(&format!("%{:02X}", byte));
+			}
+		}
+	}
+	out
+}
+
+/// Returns the expressions for the terms in a search result.
+fn expressions(result: &Value) -> Vec<&str> {
+	let terms = result["terms"].as_array().unwrap();
+	terms
+		.iter()
+		.map(|x| x["expression"].as_str().unwrap())
+		.collect()
+}
+
+/// Asserts that the request failed with the given status and message.
+fn assert_error(response: (u16, Value), status: u16, message: &str) {
+	assert_eq!(response.0, status);
+	assert_eq!(response.1["error"], message);
+}
+
+#[test]
+fn health() {
+	let (status, value) = get("/health");
+	assert_eq!(status, 200);
+	assert_eq!(value["status"], "ok");
+}
+
+#[test]
+fn stats() {
+	let (status, value) = get("/stats");
+	assert_eq!(status, 200);
+	assert_eq!(value["terms"], 4);
+	assert_eq!(value["kanji"], 1);
+	assert_eq!(value["tags"], 1);
+}
+
+#[test]
+fn search_modes() {
+	let (status, value) = get(&encode("/search?q=東京"));
+	assert_eq!(status, 200);
+	assert_eq!(expressions(&value), vec!["東京"]);
+	assert_eq!(value["total"], 1);
+	assert_eq!(value["terms"][0]["reading"], "とうきょう");
+
+	let (status, value) = get(&encode("/search?q=東京&mode=exact"));
+	assert_eq!(status, 200);
+	assert_eq!(expressions(&value), vec!["東京"]);
+
+	let (status, value) = get(&encode("/search?q=東&mode=prefix"));
+	assert_eq!(status, 200);
+	// Prefix results are in key order.
+	assert_eq!(expressions(&value), vec!["東", "東京", "東口"]);
+
+	let (status, value) = get(&encode("/search?q=京&mode=suffix"));
+	assert_eq!(status, 200);
+	assert_eq!(expressions(&value), vec!["東京"]);
+
+	let (status, value) = get("/search?q=toukyo&mode=romaji");
+	assert_eq!(status, 200);
+	assert_eq!(expressions(&value), vec!["東京"]);
+
+	let (status, value) = get("/search?q=tokyo&mode=romaji");
+	assert_eq!(status, 200);
+	assert_eq!(expressions(&value), Vec::<&str>::new());
+
+	let (status, value) = get("/search?q=tokyo&mode=romaji&distance=2");
+	assert_eq!(status, 200);
+	assert_eq!(expressions(&value), vec!["東京"]);
+}
+
+#[test]
+fn search_pages() {
+	let (_, all) = get(&encode("/search?q=東&mode=prefix"));
+	let all = expressions(&all);
+	assert_eq!(all.len(), 3);
+
+	for offset in 0..4 {
+		let path = format!("/search?q=東&mode=prefix&offset={}&limit=1", offset);
+		let (status, value) = get(&encode(&path));
+		assert_eq!(status, 200);
+		assert_eq!(value["total"], 3);
+		assert_eq!(value["offset"], offset);
+		assert_eq!(value["limit"], 1);
+		assert_eq!(
+			expressions(&value),
+			all.iter().skip(offset).take(1).cloned().collect::<Vec<_>>()
+		);
+	}
+
+	let (status, value) = get(&encode("/search?q=東&mode=prefix&offset=1"));
+	assert_eq!(status, 200);
+	assert_eq!(value["limit"], 20);
+	assert_eq!(expressions(&value), all[1..].to_vec());
+
+	let (status, value) = get(&encode("/search?q=東&mode=prefix&limit=100"));
+	assert_eq!(status, 200);
+	assert_eq!(expressions(&value), all);
+
+	let (status, value) = get(&encode("/search?q=東&mode=prefix&limit=0"));
+	assert_eq!(status, 200);
+	assert_eq!(value["total"], 3);
+	assert_eq!(expressions(&value), Vec::<&str>::new());
+}
+
+#[test]
+fn search_errors() {
+	assert_error(get("/search"), 400, "missing search query `q`");
+	assert_error(get("/search?q="), 400, "missing search query `q`");
+	assert_error(
+		get("/search?q=a&mode=glossary"),
+		400,
+		"invalid search mode `glossary`",
+	);
+	assert_error(
+		get("/search?q=a&limit=101"),
+		400,
+		"limit must be at most 100",
+	);
+	assert_error(
+		get("/search?q=a&limit=-1"),
+		400,
+		"invalid number for `limit`",
+	);
+	assert_error(
+		get("/search?q=a&offset=x"),
+		400,
+		"invalid number for `offset`",
+	);
+	assert_error(
+		get("/search?q=a&mode=romaji&distance=4"),
+		400,
+		"distance must be at most 3",
+	);
+	assert_error(
+		request("POST", "/search?q=a", ""),
+		405,
+		"method not allowed",
+	);
+}
+
+#[test]
+fn term() {
+	let (status, value) = get("/term/0");
+	assert_eq!(status, 200);
+	assert_eq!(value["expression"], "東京");
+	assert_eq!(value["reading"], "とうきょう");
+
+	assert_error(get("/term/4"), 404, "term `4` not found");
+	assert_error(get("/term/x"), 404, "term `x` not found");
+	assert_error(get("/term"), 404, "not found");
+	assert_error(get("/term/0/1"), 404, "not found");
+	assert_error(request("POST", "/term/0", ""), 405, "method not allowed");
+}
+
+#[test]
+fn kanji() {
+	let (status, value) = get(&encode("/kanji/東"));
+	assert_eq!(status, 200);
+	assert_eq!(value["character"], "東");
+	assert_eq!(value["onyomi"][0], "トウ");
+	assert_eq!(value["kunyomi"][0], "ひがし");
+
+	assert_error(get(&encode("/kanji/京")), 404, "kanji `京` not found");
+	assert_error(
+		get(&encode("/kanji/東京")),
+		400,
+		"expected a single character",
+	);
+	assert_error(
+		request("POST", &encode("/kanji/東"), ""),
+		405,
+		"method not allowed",
+	);
+}
+
+#[test]
+fn scan() {
+	let (status, value) = get(&encode("/scan?text=東京"));
+	assert_eq!(status, 200);
+	assert_eq!(value["reading"], "とうきょう");
+	assert_eq!(value["segments"][0]["text"], "東京");
+	assert_eq!(value["segments"][0]["term"]["expression"], "東京");
+
+	let (status, value) = request("POST", "/scan", "京都");
+	assert_eq!(status, 200);
+	assert_eq!(value["reading"], "きょうと");
+	assert_eq!(value["segments"][0]["term"]["expression"], "京都");
+
+	let (status, value) = get("/scan");
+	assert_eq!(status, 200);
+	assert_eq!(value["reading"], "");
+
+	let text = "あ".repeat(10001);
+	let message = "text must be at most 10000 characters";
+	assert_error(request("POST", "/scan", &text), 400, message);
+	assert_eq!(request("POST", "/scan", &text[3..]).0, 200);
+}
+
+#[test]
+fn errors() {
+	assert_error(get("/"), 404, "not found");
+	assert_error(get("/unknown"), 404, "not found");
+	assert_error(request("PUT", "/health", ""), 405, "method not allowed");
+	assert_error(request("DELETE", "/scan", ""), 405, "method not allowed");
+	assert_error(request("POST", "/health", ""), 405, "method not allowed");
+	assert_error(request("POST", "/stats", ""), 405, "method not allowed");
+}
+
+#[test]
+fn url_decoding() {
+	// A `+` is a space only in the query string.
+	assert_error(
+		get("/search?q=a+b&mode=x%2By"),
+		400,
+		"invalid search mode `x+y`",
+	);
+	assert_error(
+		get("/search?q=a&mode=x+y"),
+		400,
+		"invalid search mode `x y`",
+	);
+	assert_error(get("/kanji/+"), 404, "kanji `+` not found");
+	assert_error(get("/kanji/%2B"), 404, "kanji `+` not found");
+
+	// Both characters after a `%` must be hex digits.
+	assert_error(get("/kanji/%+1"), 400, "invalid URL encoding");
+	assert_error(get("/kanji/%-1"), 400, "invalid URL encoding");
+	assert_error(get("/kanji/%4"), 400, "invalid URL encoding");
+	assert_error(get("/search?q=%+1"), 400, "invalid URL encoding");
+	assert_error(get("/search?q=%zz"), 400, "invalid URL encoding");
+	assert_error(get("/kanji/%FF"), 400, "invalid UTF-8 in URL");
+}
@@ -0,0 +1,878 @@
+//! C API for the dictionary.
+//!
+//! See `include/jp_dict.h` for the C header. The header is generated from
+//! this file using `make header` (requires `cbindgen`).
+//!
+//! All strings are UTF-8 and are passed as a pointer and byte length pair
+//! ([JpDictStr]). Strings returned by the API are not NUL terminated and are
+//! valid for as long as the database handle is.
+//!
+//! Enumerations such as [JpDictSearchMode] are passed as `uint32_t`, so that
+//! an invalid value from C is rejected instead of being undefined behavior.
+//!
+//! A panic must never unwind into C, so all functions catch panics and return
+//! the same value as for invalid arguments (NULL, zero or empty).
+
+use std::ffi::CStr;
+use std::os::raw::c_char;
+use std::panic;
+use std::ptr;
+
+use db::{OwnedDB, ResultSet, Tag, Term, DB};
+
+use super::get_db;
+
+/// Opaque handle for a database.
+pub enum JpDictDb {
+	/// Embedded database returned by [jp_dict_get_db].
+	Embedded(&'static DB<'static>),
+	/// Database opened at runtime by [jp_dict_open].
+	Loaded(OwnedDB),
+}
+
+impl JpDictDb {
+	fn with_db<T, F: FnOnce(&DB) -> T>(&self, f: F) -> T {
+		match self {
+			JpDictDb::Embedded(db) => f(db),
+			JpDictDb::Loaded(data) => f(&data.db()),
+		}
+	}
+}
+
+/// Opaque handle for a list of search results.
+pub struct JpDictResults {
+	indexes: Vec<usize>,
+}
+
+/// UTF-8 string as a pointer and byte length. Not NUL terminated.
+#[repr(C)]
+pub struct JpDictStr {
+	pub ptr: *const u8,
+	pub len: usize,
+}
+
+/// Tag fields.
+#[repr(C)]
+pub struct JpDictTag {
+	pub name: JpDictStr,
+	pub category: JpDictStr,
+	pub notes: JpDictStr,
+	pub order: i32,
+}
+
+/// Search mode for [jp_dict_search].
+#[repr(u32)]
+#[derive(Copy, Clone)]
+pub enum JpDictSearchMode {
+	/// Exact match of the term expression, reading or search key.
+	Exact = 0,
+	/// Prefix match of the term expression, reading or search key.
+	Prefix = 1,
+	/// Suffix match of the term expression, reading or search key.
+	Suffix = 2,
+	/// Wildcard pattern search (see `DB::search_pattern`).
+	Pattern = 3,
+}
+
+/// Tag list for a term.
+#[repr(u32)]
+#[derive(Copy, Clone)]
+pub enum JpDictTermTags {
+	TermTags = 0,
+	DefinitionTags = 1,
+	Rules = 2,
+}
+
+/// String list for a kanji.
+#[repr(u32)]
+#[derive(Copy, Clone)]
+pub enum JpDictKanjiList {
+	Meanings = 0,
+	Onyomi = 1,
+	Kunyomi = 2,
+}
+
+impl JpDictSearchMode {
+	fn from_u32(value: u32) -> Option<JpDictSearchMode> {
+		match value {
+			0 => Some(JpDictSearchMode::Exact),
+			1 => Some(JpDictSearchMode::Prefix),
+			2 => Some(JpDictSearchMode::Suffix),
+			3 => Some(JpDictSearchMode::Pattern),
+			_ => None,
+		}
+	}
+}
+
+impl JpDictTermTags {
+	fn from_u32(value: u32) -> Option<JpDictTermTags> {
+		match value {
+			0 => Some(JpDictTermTags::TermTags),
+			1 => Some(JpDictTermTags::DefinitionTags),
+			2 => Some(JpDictTermTags::Rules),
+			_ => None,
+		}
+	}
+}
+
+impl JpDictKanjiList {
+	fn from_u32(value: u32) -> Option<JpDictKanjiList> {
+		match value {
+			0 => Some(JpDictKanjiList::Meanings),
+			1 => Some(JpDictKanjiList::Onyomi),
+			2 => Some(JpDictKanjiList::Kunyomi),
+			_ => None,
+		}
+	}
+}
+
+/// Returns the database embedded in the library, or NULL if it cannot be
+/// loaded. The returned handle must not be closed.
+#[no_mangle]
+pub extern "C" fn jp_dict_get_db() -> *const JpDictDb {
+	lazy_static! {
+		static ref EMBEDDED: JpDictDb = JpDictDb::Embedded(get_db());
+	}
+	guard(ptr::null(), || &*EMBEDDED as *const JpDictDb)
+}
+
+/// Opens a database file (e.g. `data/dictionary.in`) at runtime.
+///
+/// Returns NULL if the file cannot be read or is not a valid database. The
+/// handle must be freed with [jp_dict_close].
+///
+/// # Safety
+///
+/// `path` must be NULL or point to a NUL terminated string.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_open(path: *const c_char) -> *mut JpDictDb {
+	guard(ptr::null_mut(), || {
+		if path.is_null() {
+			return ptr::null_mut();
+		}
+		let path = match CStr::from_ptr(path).to_str() {
+			Ok(path) => path,
+			Err(_) => return ptr::null_mut(),
+		};
+		let data = match std::fs::read(path) {
+			Ok(data) => data,
+			Err(_) => return ptr::null_mut(),
+		};
+		match OwnedDB::load(&data) {
+			Ok(db) => Box::into_raw(Box::new(JpDictDb::Loaded(db))),
+			Err(_) => ptr::null_mut(),
+		}
+	})
+}
+
+/// Closes a database opened with [jp_dict_open]. Does nothing for the
+/// embedded database or a NULL handle.
+///
+/// # Safety
+///
+/// `db` must be NULL or a handle that has not been closed. Strings returned
+/// for the database must not be used after it is closed.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_close(db: *mut JpDictDb) {
+	guard((), || {
+		if let Some(JpDictDb::Loaded(..)) = db.as_ref() {
+			drop(Box::from_raw(db));
+		}
+	})
+}
+
+/// Returns the number of terms in the database.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_term_count(db: *const JpDictDb) -> usize {
+	guard(0, || match db.as_ref() {
+		Some(db) => db.with_db(|db| db.term_count()),
+		None => 0,
+	})
+}
+
+//
+// Search
+//
+
+/// Searches the database for the given UTF-8 query.
+///
+/// `mode` is one of the [JpDictSearchMode] values. Returns NULL if the mode
+/// is invalid or the query is not valid UTF-8. The results must be freed with
+/// [jp_dict_results_free].
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle and `query` must be NULL or
+/// point to `query_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_search(
+	db: *const JpDictDb,
+	mode: u32,
+	query: *const u8,
+	query_len: usize,
+) -> *mut JpDictResults {
+	guard(ptr::null_mut(), || {
+		let mode = match JpDictSearchMode::from_u32(mode) {
+			Some(mode) => mode,
+			None => return ptr::null_mut(),
+		};
+		let (db, query) = match (db.as_ref(), from_utf8(query, query_len)) {
+			(Some(db), Some(query)) => (db, query),
+			_ => return ptr::null_mut(),
+		};
+
+		let mut set = ResultSet::default();
+		db.with_db(|db| match mode {
+			JpDictSearchMode::Exact => db.search_term(query, &mut set),
+			JpDictSearchMode::Prefix => db.search_prefix(query, &mut set),
+			JpDictSearchMode::Suffix => db.search_suffix(query, &mut set),
+			JpDictSearchMode::Pattern => db.search_pattern(query, &mut set),
+		});
+
+		let results = JpDictResults {
+			indexes: set.iter().collect(),
+		};
+		Box::into_raw(Box::new(results))
+	})
+}
+
+/// Fuzzy searches the romaji search keys in the database (see
+/// `DB::search_fuzzy`). Results are sorted by edit distance.
+///
+/// Returns NULL if the query is not valid UTF-8. The results must be freed
+/// with [jp_dict_results_free].
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle and `query` must be NULL or
+/// point to `query_len` readable bytes.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_search_fuzzy(
+	db: *const JpDictDb,
+	query: *const u8,
+	query_len: usize,
+	max_distance: usize,
+) -> *mut JpDictResults {
+	guard(ptr::null_mut(), || {
+		let (db, query) = match (db.as_ref(), from_utf8(query, query_len)) {
+			(Some(db), Some(query)) => (db, query),
+			_ => return ptr::null_mut(),
+		};
+
+		let found = db.with_db(|db| db.search_fuzzy(query, max_distance));
+		let results = JpDictResults {
+			indexes: found.into_iter().map(|x| x.term).collect(),
+		};
+		Box::into_raw(Box::new(results))
+	})
+}
+
+/// Returns the number of terms in the search results.
+///
+/// # Safety
+///
+/// `results` must be NULL or search results that have not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_results_len(results: *const JpDictResults) -> usize {
+	guard(0, || match results.as_ref() {
+		Some(results) => results.indexes.len(),
+		None => 0,
+	})
+}
+
+/// Returns the term index for the n-th search result, or `SIZE_MAX` if out
+/// of range.
+///
+/// # Safety
+///
+/// `results` must be NULL or search results that have not been freed.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_results_get(results: *const JpDictResults, n: usize) -> usize {
+	guard(usize::MAX, || {
+		match results.as_ref().and_then(|x| x.indexes.get(n)) {
+			Some(&index) => index,
+			None => usize::MAX,
+		}
+	})
+}
+
+/// Frees search results.
+///
+/// # Safety
+///
+/// `results` must be NULL or search results that have not been freed. The
+/// results must not be used after this call.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_results_free(results: *mut JpDictResults) {
+	guard((), || {
+		if !results.is_null() {
+			drop(Box::from_raw(results));
+		}
+	})
+}
+
+//
+// Terms
+//
+
+/// Term expression. Empty if the term index is out of range.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle. The returned string is only
+/// valid while the database is open.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_term_expression(db: *const JpDictDb, index: usize) -> JpDictStr {
+	with_term(db, index, |term| to_str(term.expression())).unwrap_or(EMPTY)
+}
+
+/// Term reading. Empty if not available or the term index is out of range.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle. The returned string is only
+/// valid while the database is open.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_term_reading(db: *const JpDictDb, index: usize) -> JpDictStr {
+	with_term(db, index, |term| to_str(term.reading())).unwrap_or(EMPTY)
+}
+
+/// Term romaji search key. Empty if the term index is out of range.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle. The returned string is only
+/// valid while the database is open.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_term_search_key(db: *const JpDictDb, index: usize) -> JpDictStr {
+	with_term(db, index, |term| to_str(term.search_key())).unwrap_or(EMPTY)
+}
+
+/// Term source dictionary name. Empty if the term index is out of range.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle. The returned string is only
+/// valid while the database is open.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_term_source(db: *const JpDictDb, index: usize) -> JpDictStr {
+	with_term(db, index, |term| to_str(term.source())).unwrap_or(EMPTY)
+}
+
+/// Term frequency. Zero if not available or the term index is out of range.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_term_frequency(db: *const JpDictDb, index: usize) -> u32 {
+	with_term(db, index, |term| term.frequency().unwrap_or(0)).unwrap_or(0)
+}
+
+/// Term sequence number in the source dictionary. Zero if not available or
+/// the term index is out of range.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_term_sequence(db: *const JpDictDb, index: usize) -> u32 {
+	with_term(db, index, |term| term.sequence()).unwrap_or(0)
+}
+
+/// Term score. Zero if the term index is out of range.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_term_score(db: *const JpDictDb, index: usize) -> i32 {
+	with_term(db, index, |term| term.score()).unwrap_or(0)
+}
+
+/// Number of glossary entries for the term.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_term_glossary_len(db: *const JpDictDb, index: usize) -> usize {
+	with_term(db, index, |term| term.glossary().count()).unwrap_or(0)
+}
+
+/// Returns the n-th glossary entry for the term. Empty if out of range.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle. The returned string is only
+/// valid while the database is open.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_term_glossary(
+	db: *const JpDictDb,
+	index: usize,
+	n: usize,
+) -> JpDictStr {
+	with_term(db, index, |term| term.glossary().nth(n).map(to_str))
+		.and_then(|x| x)
+		.unwrap_or(EMPTY)
+}
+
+/// Number of tags in the given tag list for the term. `list` is one of the
+/// [JpDictTermTags] values.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_term_tags_len(
+	db: *const JpDictDb,
+	index: usize,
+	list: u32,
+) -> usize {
+	with_term(db, index, |term| term_tags(&term, list).len()).unwrap_or(0)
+}
+
+/// Returns the n-th tag in the given tag list for the term. All fields are
+/// empty if out of range or if `list` is not a [JpDictTermTags] value.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle. The tag
+/// strings are only valid while the database is open.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_term_tag(
+	db: *const JpDictDb,
+	index: usize,
+	list: u32,
+	n: usize,
+) -> JpDictTag {
+	with_term(db, index, |term| {
+		term_tags(&term, list).into_iter().nth(n).map(to_tag)
+	})
+	.and_then(|x| x)
+	.unwrap_or(JpDictTag {
+		name: EMPTY,
+		category: EMPTY,
+		notes: EMPTY,
+		order: 0,
+	})
+}
+
+//
+// Kanji
+//
+
+/// Returns true if the database has an entry for the given kanji (as an
+/// unicode code point).
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_kanji_exists(db: *const JpDictDb, kanji: u32) -> bool {
+	with_kanji(db, kanji, |_| true).unwrap_or(false)
+}
+
+/// Kanji frequency. Zero if not available or the kanji does not exist.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_kanji_frequency(db: *const JpDictDb, kanji: u32) -> u32 {
+	with_kanji(db, kanji, |kanji| kanji.frequency().unwrap_or(0)).unwrap_or(0)
+}
+
+/// Kanji source dictionary name. Empty if the kanji does not exist.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle. The returned string is only
+/// valid while the database is open.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_kanji_source(db: *const JpDictDb, kanji: u32) -> JpDictStr {
+	with_kanji(db, kanji, |kanji| to_str(kanji.source())).unwrap_or(EMPTY)
+}
+
+/// Number of strings in the given list for the kanji. `list` is one of the
+/// [JpDictKanjiList] values.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_kanji_list_len(
+	db: *const JpDictDb,
+	kanji: u32,
+	list: u32,
+) -> usize {
+	let list = match JpDictKanjiList::from_u32(list) {
+		Some(list) => list,
+		None => return 0,
+	};
+	with_kanji(db, kanji, |kanji| match list {
+		JpDictKanjiList::Meanings => kanji.meanings().count(),
+		JpDictKanjiList::Onyomi => kanji.onyomi().count(),
+		JpDictKanjiList::Kunyomi => kanji.kunyomi().count(),
+	})
+	.unwrap_or(0)
+}
+
+/// Returns the n-th string in the given list for the kanji. Empty if out of
+/// range or if `list` is not a [JpDictKanjiList] value.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle. The returned string is only
+/// valid while the database is open.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_kanji_list_get(
+	db: *const JpDictDb,
+	kanji: u32,
+	list: u32,
+	n: usize,
+) -> JpDictStr {
+	let list = match JpDictKanjiList::from_u32(list) {
+		Some(list) => list,
+		None => return EMPTY,
+	};
+	with_kanji(db, kanji, |kanji| {
+		let value = match list {
+			JpDictKanjiList::Meanings => kanji.meanings().nth(n),
+			JpDictKanjiList::Onyomi => kanji.onyomi().nth(n),
+			JpDictKanjiList::Kunyomi => kanji.kunyomi().nth(n),
+		};
+		value.map(to_str)
+	})
+	.and_then(|x| x)
+	.unwrap_or(EMPTY)
+}
+
+/// Number of tags for the kanji.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_kanji_tags_len(db: *const JpDictDb, kanji: u32) -> usize {
+	with_kanji(db, kanji, |kanji| kanji.tags().count()).unwrap_or(0)
+}
+
+/// Returns the n-th tag for the kanji. All fields are empty if out of range.
+///
+/// # Safety
+///
+/// `db` must be NULL or a valid database handle. The tag
+/// strings are only valid while the database is open.
+#[no_mangle]
+pub unsafe extern "C" fn jp_dict_kanji_tag(db: *const JpDictDb, kanji: u32, n: usize) -> JpDictTag {
+	with_kanji(db, kanji, |kanji| kanji.tags().nth(n).map(to_tag))
+		.and_then(|x| x)
+		.unwrap_or(JpDictTag {
+			name: EMPTY,
+			category: EMPTY,
+			notes: EMPTY,
+			order: 0,
+		})
+}
+
+//
+// Helpers
+//
+
+const EMPTY: JpDictStr = JpDictStr {
+	ptr: b"".as_ptr(),
+	len: 0,
+};
+
+fn to_str(value: &str) -> JpDictStr {
+	JpDictStr {
+		ptr: value.as_ptr(),
+		len: value.len(),
+	}
+}
+
+fn to_tag(tag: Tag) -> JpDictTag {
+	JpDictTag {
+		name: to_str(tag.name()),
+		category: to_str(tag.category()),
+		notes: to_str(tag.notes()),
+		order: tag.order(),
+	}
+}
+
+unsafe fn from_utf8<'a>(ptr: *const u8, len: usize) -> Option<&'a str> {
+	if ptr.is_null() {
+		None
+	} else {
+		std::str::from_utf8(std::slice::from_raw_parts(ptr, len)).ok()
+	}
+}
+
+/// Returns the tags for a [JpDictTermTags] list, or none for an invalid list.
+fn term_tags<'db, 'a>(term: &'a Term<'db, 'a>, list: u32) -> Vec<Tag<'db, 'a>> {
+	match JpDictTermTags::from_u32(list) {
+		Some(JpDictTermTags::TermTags) => term.term_tags().collect(),
+		Some(JpDictTermTags::DefinitionTags) => term.definition_tags().collect(),
+		Some(JpDictTermTags::Rules) => term.rules().collect(),
+		None => Vec::new(),
+	}
+}
+
+/// Runs `f`, returning `default` if it panics.
+///
+/// The database is read-only, so a panic cannot leave it in an inconsistent
+/// state and it is fine to keep using it afterwards.
+fn guard<T, F: FnOnce() -> T>(default: T, f: F) -> T {
+	panic::catch_unwind(panic::AssertUnwindSafe(f)).unwrap_or(default)
+}
+
+unsafe fn with_term<F, T>(db: *const JpDictDb, index: usize, f: F) -> Option<T>
+where
+	F: FnOnce(Term) -> T,
+{
+	let db = db.as_ref()?;
+	guard(None, || db.with_db(|db| db.term(index).map(f)))
+}
+
+unsafe fn with_kanji<F, T>(db: *const JpDictDb, kanji: u32, f: F) -> Option<T>
+where
+	F: FnOnce(db::Kanji) -> T,
+{
+	let db = db.as_ref()?;
+	let kanji = std::char::from_u32(kanji)?;
+	guard(None, || db.with_db(|db| db.kanji(kanji).map(f)))
+}
+
+#[cfg(test)]
+mod tests {
+	use std::ffi::CString;
+	use std::ptr;
+
+	use db::{KanjiData, TagData, TermData, Writer};
+
+	use super::*;
+
+	/// Writes the data to a temporary file and opens it with [jp_dict_open].
+	fn open_data(name: &str, data: &[u8]) -> *mut JpDictDb {
+		let mut path = std::env::temp_dir();
+		path.push(format!("jp_dict_ffi_{}_{}.in", name, std::process::id()));
+		std::fs::write(&path, data).unwrap();
+		let c_path = CString::new(path.to_str().unwrap()).unwrap();
+		let db = unsafe { jp_dict_open(c_path.as_ptr()) };
+		std::fs::remove_file(&path).unwrap();
+		db
+	}
+
+	/// Opens a small test database.
+	fn open_db(name: &str) -> *mut JpDictDb {
+		let mut w = Writer::new();
+		let tag = TagData {
+			name: w.intern("P".to_string()),
+			category: w.intern("popular".to_string()),
+			order: -10,
+			notes: w.intern("common word".to_string()),
+		};
+		w.push_tag(tag);
+
+		let terms = [
+			("東京", "とうきょう", "toukyou", 100),
+			("東", "ひがし", "higashi", 10),
+		];
+		for &(expression, reading, search_key, frequency) in terms.iter() {
+			let term = TermData {
+				expression: w.intern(expression.to_string()),
+				reading: w.intern(reading.to_string()),
+				search_key: w.intern(search_key.to_string()),
+				score: 5,
+				sequence: 1000 + frequency,
+				frequency,
+				glossary: vec![
+					w.intern(format!("{} (1)", expression)),
+					w.intern(format!("{} (2)", expression)),
+				],
+				rules: Vec::new(),
+				term_tags: w.get_tags(["P"]),
+				definition_tags: Vec::new(),
+				source: w.intern("JMdict".to_string()),
+			};
+			w.push_term(term);
+		}
+
+		let kanji = KanjiData {
+			character: '東',
+			frequency: 500,
+			meanings: vec![w.intern("east".to_string())],
+			onyomi: vec![w.intern("トウ".to_string())],
+			kunyomi: vec![w.intern("ひがし".to_string())],
+			tags: w.get_tags(["P"]),
+			stats: Vec::new(),
+			source: w.intern("KANJIDIC".to_string()),
+		};
+		w.push_kanji(kanji);
+
+		let mut data = Vec::new();
+		w.write(&mut data).unwrap();
+		let db = open_data(name, &data);
+		assert!(!db.is_null());
+		db
+	}
+
+	fn text(value: JpDictStr) -> &'static str {
+		unsafe {
+			let bytes = std::slice::from_raw_parts(value.ptr, value.len);
+			std::str::from_utf8(bytes).unwrap()
+		}
+	}
+
+	/// Returns the expressions for the search results and frees them.
+	unsafe fn expressions(db: *const JpDictDb, results: *mut JpDictResults) -> Vec<&'static str> {
+		assert!(!results.is_null());
+		let out = (0..jp_dict_results_len(results))
+			.map(|n| text(jp_dict_term_expression(db, jp_dict_results_get(results, n))))
+			.collect();
+		jp_dict_results_free(results);
+		out
+	}
+
+	#[test]
+	fn open() {
+		unsafe {
+			let db = open_db("open");
+			assert_eq!(jp_dict_term_count(db), 2);
+			jp_dict_close(db);
+
+			assert!(jp_dict_open(ptr::null()).is_null());
+			let missing = CString::new("/nonexistent/dictionary.in").unwrap();
+			assert!(jp_dict_open(missing.as_ptr()).is_null());
+		}
+		assert!(open_data("invalid", b"not a database").is_null());
+		assert!(open_data("empty", b"").is_null());
+	}
+
+	#[test]
+	fn search() {
+		unsafe {
+			let db = open_db("search");
+			let search = |mode: u32, query: &str| {
+				let results = jp_dict_search(db, mode, query.as_ptr(), query.len());
+				expressions(db, results)
+			};
+			assert_eq!(search(0, "東京"), ["東京"]);
+			assert_eq!(search(0, "ひがし"), ["東"]);
+			assert_eq!(search(1, "東"), ["東京", "東"]);
+			assert_eq!(search(2, "京"), ["東京"]);
+			assert_eq!(search(3, "?京"), ["東京"]);
+			assert!(search(0, "京").is_empty());
+
+			let query = "toukyo";
+			let results = jp_dict_search_fuzzy(db, query.as_ptr(), query.len(), 1);
+			assert_eq!(expressions(db, results), ["東京"]);
+
+			let results = jp_dict_search(db, 0, "東".as_ptr(), 3);
+			assert_eq!(jp_dict_results_get(results, 1), usize::MAX);
+			jp_dict_results_free(results);
+
+			// Invalid mode, UTF-8 query and NULL pointers.
+			let query = "東";
+			assert!(jp_dict_search(db, 4, query.as_ptr(), query.len()).is_null());
+			assert!(jp_dict_search(db, 0, query.as_ptr(), 2).is_null());
+			assert!(jp_dict_search(db, 0, ptr::null(), 0).is_null());
+			assert!(jp_dict_search(ptr::null(), 0, query.as_ptr(), query.len()).is_null());
+			assert!(jp_dict_search_fuzzy(db, ptr::null(), 0, 1).is_null());
+			assert!(jp_dict_search_fuzzy(ptr::null(), query.as_ptr(), query.len(), 1).is_null());
+
+			jp_dict_close(db);
+		}
+	}
+
+	#[test]
+	fn terms() {
+		unsafe {
+			let db = open_db("terms");
+			assert_eq!(text(jp_dict_term_expression(db, 0)), "東京");
+			assert_eq!(text(jp_dict_term_reading(db, 0)), "とうきょう");
+			assert_eq!(text(jp_dict_term_search_key(db, 0)), "toukyou");
+			assert_eq!(text(jp_dict_term_source(db, 0)), "JMdict");
+			assert_eq!(jp_dict_term_frequency(db, 0), 100);
+			assert_eq!(jp_dict_term_sequence(db, 0), 1100);
+			assert_eq!(jp_dict_term_score(db, 0), 5);
+
+			assert_eq!(jp_dict_term_glossary_len(db, 1), 2);
+			assert_eq!(text(jp_dict_term_glossary(db, 1, 1)), "東 (2)");
+			assert_eq!(text(jp_dict_term_glossary(db, 1, 2)), "");
+
+			assert_eq!(jp_dict_term_tags_len(db, 0, 0), 1);
+			assert_eq!(jp_dict_term_tags_len(db, 0, 1), 0);
+			let tag = jp_dict_term_tag(db, 0, 0, 0);
+			assert_eq!(text(tag.name), "P");
+			assert_eq!(text(tag.category), "popular");
+			assert_eq!(text(tag.notes), "common word");
+			assert_eq!(tag.order, -10);
+
+			// Out of range indexes and invalid lists.
+			assert_eq!(text(jp_dict_term_expression(db, 2)), "");
+			assert_eq!(jp_dict_term_frequency(db, 2), 0);
+			assert_eq!(jp_dict_term_glossary_len(db, 2), 0);
+			assert_eq!(jp_dict_term_tags_len(db, 0, 3), 0);
+			assert_eq!(text(jp_dict_term_tag(db, 0, 3, 0).name), "");
+			assert_eq!(text(jp_dict_term_tag(db, 0, 0, 1).name), "");
+
+			jp_dict_close(db);
+		}
+	}
+
+	#[test]
+	fn kanji() {
+		unsafe {
+			let db = open_db("kanji");
+			let chr = '東' as u32;
+			assert!(jp_dict_kanji_exists(db, chr));
+			assert_eq!(jp_dict_kanji_frequency(db, chr), 500);
+			assert_eq!(text(jp_dict_kanji_source(db, chr)), "KANJIDIC");
+			assert_eq!(jp_dict_kanji_list_len(db, chr, 0), 1);
+			assert_eq!(text(jp_dict_kanji_list_get(db, chr, 0, 0)), "east");
+			assert_eq!(text(jp_dict_kanji_list_get(db, chr, 1, 0)), "トウ");
+			assert_eq!(text(jp_dict_kanji_list_get(db, chr, 2, 0)), "ひがし");
+			assert_eq!(jp_dict_kanji_tags_len(db, chr), 1);
+			assert_eq!(text(jp_dict_kanji_tag(db, chr, 0).name), "P");
+
+			// Missing kanji, invalid code points and lists.
+			assert!(!jp_dict_kanji_exists(db, '京' as u32));
+			assert!(!jp_dict_kanji_exists(db, 0xD800));
+			assert_eq!(jp_dict_kanji_list_len(db, chr, 3), 0);
+			assert_eq!(text(jp_dict_kanji_list_get(db, chr, 3, 0)), "");
+			assert_eq!(text(jp_dict_kanji_list_get(db, chr, 0, 1)), "");
+			assert_eq!(text(jp_dict_kanji_tag(db, chr, 1).name), "");
+
+			jp_dict_close(db);
+		}
+	}
+
+	#[test]
+	fn null_handles() {
+		unsafe {
+			let db = ptr::null();
+			assert_eq!(jp_dict_term_count(db), 0);
+			assert_eq!(text(jp_dict_term_expression(db, 0)), "");
+			assert_eq!(jp_dict_term_tags_len(db, 0, 0), 0);
+			assert!(!jp_dict_kanji_exists(db, '東' as u32));
+			assert_eq!(text(jp_dict_kanji_list_get(db, '東' as u32, 0, 0)), "");
+
+			assert_eq!(jp_dict_results_len(ptr::null()), 0);
+			assert_eq!(jp_dict_results_get(ptr::null(), 0), usize::MAX);
+			jp_dict_results_free(ptr::null_mut());
+			jp_dict_close(ptr::null_mut());
+		}
+	}
+
+	#[test]
+	fn panics() {
+		assert_eq!(guard(1, || 2), 2);
+		assert_eq!(guard(1, || -> i32 { panic!("guarded panic") }), 1);
+	}
+}
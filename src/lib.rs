@@ -6,3 +6,5 @@ pub use db::*;
 
 mod data;
 pub use data::get_db;
+
+pub mod ffi;